@@ -0,0 +1,545 @@
+//!
+//! ## Expansion
+//!
+//! Lowers parsed PonyX into Rust expressions building a component tree
+//! with `::avpony_runtime`.
+//!
+//! Rust expressions, patterns, and declarations are not re-printed from the
+//! parsed [syn] trees, but sliced out of the macro's input (see [Source]),
+//! so they keep their spans (and hygiene).
+//!
+//! Logic blocks become Rust control flow where possible:
+//! `{#if}` to `if`, `{#match}` to `match`, and `{#for}` to iterators.
+//!
+
+use avpony_lang::{
+    lexical::{boolean::BooleanLit, number::NumberLit, Identifier, Literal},
+    ponyx::{
+        blocks::{
+            await_block::{AwaitBlock, Branch as AwaitBranch},
+            for_block::ForBlock,
+            if_block::{Branch as IfBranch, IfBlock},
+            match_block::{Arm, MatchBlock},
+            LogicBlock,
+        },
+        statement::AtStatement,
+        tag::{
+            attribute::{Attribute, AttributeKey},
+            Tag,
+        },
+        Node,
+    },
+    syntax::{
+        external::rust::Rust,
+        map::Field,
+        operator::{BinaryOperator, UnaryOperator},
+        Expr, SoloExpr,
+    },
+    utils::{placeholder::Maybe, Spanned},
+};
+use chumsky::span::Span as _;
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    LitBool, LitFloat, LitInt, LitStr, Token,
+};
+
+use crate::source::Source;
+
+///
+/// `{#for <ident> in <expr> (by <expr>)?}`, after the `#for`.
+///
+struct ForHeader {
+    ident: Ident,
+    iter: syn::Expr,
+    key: Option<syn::Expr>,
+}
+
+impl Parse for ForHeader {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        input.parse::<Token![in]>()?;
+        let iter = syn::Expr::parse_without_eager_brace(input)?;
+
+        let key = match input.peek(syn::Ident) {
+            true => {
+                let by: Ident = input.parse()?;
+                if by != "by" {
+                    return Err(syn::Error::new(by.span(), "expected `by`"));
+                }
+                Some(input.parse()?)
+            }
+            false => None,
+        };
+
+        Ok(Self { ident, iter, key })
+    }
+}
+
+fn present<T>(maybe: &Maybe<T>) -> syn::Result<&T> {
+    match maybe {
+        Maybe::Present(t) => Ok(t),
+        Maybe::Placeholder(_) => Err(syn::Error::new(Span::call_site(), "incomplete markup")),
+    }
+}
+
+pub struct Expander<'a> {
+    source: &'a Source,
+}
+
+impl<'a> Expander<'a> {
+    pub fn new(source: &'a Source) -> Self {
+        Self { source }
+    }
+
+    fn span(&self, spanned: &impl Spanned) -> Span {
+        let span = spanned.span();
+        self.source.span_at(span.start()..span.end())
+    }
+
+    fn tokens(&self, spanned: &impl Spanned) -> TokenStream {
+        let span = spanned.span();
+        self.source.tokens_in(span.start()..span.end())
+    }
+
+    fn ident(&self, ident: &Identifier) -> Ident {
+        Ident::new(&ident.value, self.span(ident))
+    }
+
+    ///
+    /// The brace-delimited group that `spanned` starts with.
+    ///
+    fn group(&self, spanned: &impl Spanned) -> syn::Result<Group> {
+        self.source
+            .group_from(spanned.span().start())
+            .ok_or_else(|| syn::Error::new(self.span(spanned), "could not find this in the input"))
+    }
+
+    ///
+    /// The header of a block, or leaf, without its first `keywords` tokens,
+    /// e.g. `cond` from `{:else if cond}` (with 3 keywords: `:`, `else`, `if`).
+    ///
+    fn header(&self, spanned: &impl Spanned, keywords: usize) -> syn::Result<TokenStream> {
+        Ok(self
+            .group(spanned)?
+            .stream()
+            .into_iter()
+            .skip(keywords)
+            .collect())
+    }
+
+    fn empty(&self) -> TokenStream {
+        quote!({ ::avpony_runtime::fragment(::std::vec::Vec::new()) })
+    }
+
+    ///
+    /// A block expression building a fragment of `nodes`,
+    /// after any `{@let}`, `{@const}`, and `{@debug}` statements.
+    ///
+    pub fn fragment(&self, nodes: &[Node<Rust>]) -> syn::Result<TokenStream> {
+        let mut stmts = Vec::new();
+        let mut items = Vec::new();
+
+        for node in nodes {
+            match node {
+                Node::Statement(AtStatement::Let(decl)) => {
+                    let tokens = self.tokens(decl);
+                    stmts.push(quote!(#tokens;));
+                }
+                Node::Statement(AtStatement::Const(decl)) => {
+                    let tokens = self.tokens(decl);
+                    stmts.push(quote!(#tokens;));
+                }
+                Node::Statement(AtStatement::Debug(debug)) => {
+                    // Skip the `debug` keyword.
+                    let expr: TokenStream = self.tokens(debug).into_iter().skip(1).collect();
+                    stmts.push(quote_spanned!(self.span(debug)=> ::std::dbg!(&(#expr));));
+                }
+                node => items.extend(self.node(node)?),
+            }
+        }
+
+        Ok(quote!({
+            #(#stmts)*
+            ::avpony_runtime::fragment(::std::vec![#(#items),*])
+        }))
+    }
+
+    fn node(&self, node: &Node<Rust>) -> syn::Result<Option<TokenStream>> {
+        Ok(Some(match node {
+            Node::Comment(_) | Node::Statement(_) => return Ok(None),
+            Node::Text(text) if text.text.trim().is_empty() => return Ok(None),
+            Node::Text(text) => {
+                let text = LitStr::new(&text.text, self.span(text));
+                quote!(::avpony_runtime::text(#text))
+            }
+            Node::Entity(entity) => {
+                let text = LitStr::new(&entity.value, self.span(entity));
+                quote!(::avpony_runtime::text(#text))
+            }
+            Node::Mustache(expr) => {
+                let expr = self.group(expr)?;
+                quote!(::avpony_runtime::expr(#expr))
+            }
+            Node::Block(block) => self.block(block)?,
+            Node::Tag(tag) => self.tag(tag)?,
+        }))
+    }
+
+    fn block(&self, block: &LogicBlock<Rust>) -> syn::Result<TokenStream> {
+        match block {
+            LogicBlock::If(block) => self.if_block(block),
+            LogicBlock::For(block) => self.for_block(block),
+            LogicBlock::Await(block) => self.await_block(block),
+            LogicBlock::Key(block) => {
+                let expr = self.header(block, 2)?;
+                let children = self.fragment(&block.children)?;
+                Ok(quote!(::avpony_runtime::key(#expr, #children)))
+            }
+            LogicBlock::Match(block) => self.match_block(block),
+        }
+    }
+
+    fn if_block(&self, block: &IfBlock<Rust>) -> syn::Result<TokenStream> {
+        let mut out = TokenStream::new();
+        let mut has_else = false;
+
+        for branch in &block.branches {
+            match branch {
+                IfBranch::If(branch) => {
+                    let cond = self.header(branch, 2)?;
+                    let children = self.fragment(&branch.contents)?;
+                    out.extend(quote!(if #cond #children));
+                }
+                IfBranch::ElseIf(branch) => {
+                    let cond = self.header(branch, 3)?;
+                    let children = self.fragment(&branch.contents)?;
+                    out.extend(quote!(else if #cond #children));
+                }
+                IfBranch::Else(branch) => {
+                    has_else = true;
+                    let children = self.fragment(&branch.contents)?;
+                    out.extend(quote!(else #children));
+                }
+            }
+        }
+
+        if !has_else {
+            let empty = self.empty();
+            out.extend(quote!(else #empty));
+        }
+
+        Ok(out)
+    }
+
+    fn for_block(&self, block: &ForBlock<Rust>) -> syn::Result<TokenStream> {
+        let ForHeader { ident, iter, key } = syn::parse2(self.header(block, 2)?)?;
+
+        let children = self.fragment(&block.children)?;
+        let item = match key {
+            Some(key) => quote!(::avpony_runtime::keyed(#key, #children)),
+            None => children,
+        };
+
+        let items = Ident::new("items", Span::mixed_site());
+        let empty = match &block.empty_case {
+            Some(nodes) => self.fragment(nodes)?,
+            None => self.empty(),
+        };
+
+        Ok(quote!({
+            let #items: ::std::vec::Vec<_> = ::std::iter::IntoIterator::into_iter(#iter)
+                .map(|#ident| #item)
+                .collect();
+
+            if #items.is_empty() #empty else { ::avpony_runtime::fragment(#items) }
+        }))
+    }
+
+    fn await_block(&self, block: &AwaitBlock<Rust>) -> syn::Result<TokenStream> {
+        let mut header: Vec<TokenTree> = self.header(block, 2)?.into_iter().collect();
+
+        // Inline form: `{#await promise then result}`, so drop the `then result`.
+        if let Some((AwaitBranch::Success(ident) | AwaitBranch::Failure(ident), _)) =
+            block.branches.first()
+        {
+            let ident = present(ident)?.span().start();
+            header.retain(|tt| self.source.offset(tt.span()).start < ident);
+            header.pop();
+        }
+
+        let promise: TokenStream = header.into_iter().collect();
+        let mut pending = self.empty();
+        let mut then = quote!(|_| ::avpony_runtime::fragment(::std::vec::Vec::new()));
+        let mut catch = then.clone();
+
+        for (branch, nodes) in &block.branches {
+            let children = self.fragment(nodes)?;
+            match branch {
+                AwaitBranch::Pending => pending = children,
+                AwaitBranch::Success(ident) => {
+                    let ident = self.ident(present(ident)?);
+                    then = quote!(|#ident| #children);
+                }
+                AwaitBranch::Failure(ident) => {
+                    let ident = self.ident(present(ident)?);
+                    catch = quote!(|#ident| #children);
+                }
+            }
+        }
+
+        Ok(quote!(::avpony_runtime::awaiting(#promise, #pending, #then, #catch)))
+    }
+
+    fn match_block(&self, block: &MatchBlock<Rust>) -> syn::Result<TokenStream> {
+        let scrutinee = self.header(block, 2)?;
+        let mut arms = Vec::new();
+
+        for arm in &block.arms {
+            match arm {
+                Arm::When(arm) => {
+                    let pat = self.header(arm, 2)?;
+                    let children = self.fragment(&arm.children)?;
+                    arms.push(quote!(#pat => #children,));
+                }
+                Arm::Default(arm) => {
+                    let binding = match &arm.binding {
+                        Some(ident) => self.ident(ident).into_token_stream(),
+                        None => quote!(_),
+                    };
+                    let children = self.fragment(&arm.children)?;
+                    arms.push(quote!(#binding => #children,));
+                }
+            }
+        }
+
+        if block.default_arm().is_none() {
+            let empty = self.empty();
+            arms.push(quote!(_ => #empty,));
+        }
+
+        Ok(quote!(match #scrutinee { #(#arms)* }))
+    }
+
+    fn tag(&self, tag: &Tag<Rust>) -> syn::Result<TokenStream> {
+        let (name, attributes, children) = match tag {
+            Tag::SelfClosing(tag) => (&tag.name, &tag.attributes, None),
+            Tag::Enclosing(tag) => (&tag.name, &tag.attributes, Some(&tag.children)),
+        };
+
+        let name = self.tokens(name);
+        let props = attributes
+            .iter()
+            .map(|attribute| self.attribute(attribute))
+            .collect::<syn::Result<Vec<_>>>()?;
+        let children = match children {
+            Some(nodes) => self.fragment(nodes)?,
+            None => self.empty(),
+        };
+
+        Ok(quote!(
+            ::avpony_runtime::element::<#name>(::std::vec![#(#props),*], #children)
+        ))
+    }
+
+    fn attribute(&self, attribute: &Attribute<Rust>) -> syn::Result<TokenStream> {
+        match attribute {
+            Attribute::Key(key) => {
+                let key = self.attribute_key(key)?;
+                Ok(quote!((#key, ::avpony_runtime::Value::from(true))))
+            }
+            Attribute::KeyValue(assignment) => {
+                let key = self.attribute_key(&assignment.key)?;
+                let value = self.solo_expr(present(&assignment.value)?)?;
+                Ok(quote!((#key, #value)))
+            }
+        }
+    }
+
+    fn attribute_key(&self, key: &AttributeKey) -> syn::Result<LitStr> {
+        Ok(match key {
+            AttributeKey::Named(name) => LitStr::new(&name.value, self.span(name)),
+            AttributeKey::Directive(directive) => LitStr::new(
+                &format!(
+                    "{}:{}",
+                    directive.base.value,
+                    present(&directive.director)?.value
+                ),
+                self.span(directive),
+            ),
+        })
+    }
+
+    fn solo_expr(&self, expr: &SoloExpr<Rust>) -> syn::Result<TokenStream> {
+        let expr = match expr.clone() {
+            SoloExpr::Literal(t) => Expr::Literal(t),
+            SoloExpr::Identifier(t) => Expr::Identifier(t),
+            SoloExpr::Array(t) => Expr::Array(t),
+            SoloExpr::Map(t) => Expr::Map(t),
+            SoloExpr::Tuple(t) => Expr::Tuple(t),
+            SoloExpr::Parenthesised(t) => Expr::Parenthesised(t),
+            SoloExpr::External(t) => Expr::External(t),
+            SoloExpr::UnaryOp(t) => Expr::UnaryOp(t),
+        };
+
+        self.expr(&expr)
+    }
+
+    ///
+    /// Lower a Pony expression into an `::avpony_runtime::Value`.
+    ///
+    fn expr(&self, expr: &Expr<Rust>) -> syn::Result<TokenStream> {
+        let value = quote!(::avpony_runtime::Value);
+
+        Ok(match expr {
+            Expr::Literal(literal) => {
+                let literal = self.literal(literal);
+                quote!(#value::from(#literal))
+            }
+            Expr::Identifier(ident) => {
+                let ident = self.ident(ident);
+                quote!(#value::from(#ident))
+            }
+            Expr::UnaryOp(op) => {
+                let UnaryOperator::Symbols(symbol) = &op.operator;
+                let symbol = LitStr::new(&symbol.value, self.span(symbol));
+                let operand = self.expr(&op.operand)?;
+                quote!(#value::unary(#symbol, #operand))
+            }
+            Expr::Array(array) => {
+                let items = array
+                    .contents
+                    .iter()
+                    .map(|item| self.expr(item))
+                    .collect::<syn::Result<Vec<_>>>()?;
+                quote!(#value::array([#(#items),*]))
+            }
+            Expr::Map(map) => {
+                let fields = map
+                    .fields
+                    .iter()
+                    .map(|field| match field {
+                        Field::Key(field) => {
+                            let key = present(&field.ident)?;
+                            let key = LitStr::new(&key.value, self.span(key));
+                            Ok(quote!((#key, #value::from(true))))
+                        }
+                        Field::KeyValue(field) => {
+                            let key = present(&field.key)?;
+                            let key = LitStr::new(&key.value, self.span(key));
+                            let field = self.expr(present(&field.value)?)?;
+                            Ok(quote!((#key, #field)))
+                        }
+                    })
+                    .collect::<syn::Result<Vec<_>>>()?;
+                quote!(#value::map([#(#fields),*]))
+            }
+            Expr::Tuple(tuple) => {
+                let items = tuple
+                    .items
+                    .iter()
+                    .map(|item| self.expr(item))
+                    .collect::<syn::Result<Vec<_>>>()?;
+                quote!(#value::tuple([#(#items),*]))
+            }
+            Expr::Parenthesised(paren) => self.expr(&paren.inner)?,
+            Expr::External(expr) => {
+                let expr = self.group(expr)?;
+                quote!(#value::from(#expr))
+            }
+            Expr::MemberAccess(access) => {
+                let receiver = self.expr(&access.receiver)?;
+                let member = present(&access.member)?;
+                let member = LitStr::new(&member.value, self.span(member));
+                quote!(#receiver.member(#member))
+            }
+            Expr::Indexing(indexing) => {
+                let receiver = self.expr(&indexing.receiver)?;
+                let index = self.expr(present(&indexing.index)?)?;
+                quote!(#receiver.index(#index))
+            }
+            Expr::BinaryOp(op) => {
+                let operator = match &op.operator {
+                    BinaryOperator::Symbols(symbol) => {
+                        LitStr::new(&symbol.value, self.span(symbol))
+                    }
+                    BinaryOperator::Named(named) => {
+                        LitStr::new(&named.ident.value, self.span(named))
+                    }
+                };
+                let left = self.expr(&op.operands.0)?;
+                let right = self.expr(present(&op.operands.1)?)?;
+                quote!(#value::binary(#operator, #left, #right))
+            }
+            Expr::Application(application) => {
+                let function = self.expr(&application.function)?;
+                let argument = self.expr(&application.argument)?;
+                quote!(#value::apply(#function, #argument))
+            }
+        })
+    }
+
+    fn literal(&self, literal: &Literal) -> TokenStream {
+        let span = self.span(literal);
+        match literal {
+            Literal::Number(NumberLit::Integer(int)) => {
+                LitInt::new(&int.value.to_string(), span).into_token_stream()
+            }
+            Literal::Number(NumberLit::Float(float)) => {
+                LitFloat::new(&format!("{:?}", float.value), span).into_token_stream()
+            }
+            Literal::String(string) => LitStr::new(&string.value, span).into_token_stream(),
+            Literal::Boolean(boolean) => {
+                LitBool::new(matches!(boolean, BooleanLit::True(_)), span).into_token_stream()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use avpony_lang::{
+        ponyx::Node,
+        syntax::external::rust::Rust,
+        utils::{Parseable, SourceFile},
+    };
+    use chumsky::{IterParser, Parser};
+    use proc_macro2::TokenStream;
+
+    use crate::source::Source;
+
+    use super::Expander;
+
+    fn expand(src: &str) -> String {
+        let source = Source::new(src.parse::<TokenStream>().unwrap()).unwrap();
+        let file = SourceFile::new("TEST", source.text.clone());
+        let nodes = Node::<Rust>::parser()
+            .padded()
+            .repeated()
+            .collect::<Vec<_>>()
+            .parse(file.stream())
+            .into_result()
+            .unwrap();
+
+        Expander::new(&source).fragment(&nodes).unwrap().to_string()
+    }
+
+    #[test]
+    fn expand_tag() {
+        let out = expand(r#"<Button primary on:click={|| clicked()}>Hello, {name}!</Button>"#);
+        assert!(out.contains("element :: < Button >"));
+        assert!(out.contains(r#""on:click""#) && out.contains("clicked ()"));
+        assert!(out.contains("{ name }"));
+    }
+
+    #[test]
+    fn expand_blocks() {
+        let out = expand(
+            r#"{#for dog in kennel.iter() by dog.id}{#if dog.good}Good dog!{:else}Also a good dog.{/if}{/for}"#,
+        );
+        assert!(out.contains("into_iter (kennel . iter ())"));
+        assert!(out.contains("keyed (dog . id ,"));
+        assert!(out.contains("if dog . good"));
+    }
+}
@@ -0,0 +1,108 @@
+#![feature(proc_macro_diagnostic)]
+//!
+//! # AvPony Inline
+//!
+//! The `pony!` macro, for writing PonyX directly in Rust,
+//! without a separate `.pony` file:
+//!
+//! ```ignore
+//! use avpony_inline::pony;
+//!
+//! let view = pony! {
+//!     <Button on:click={|| CLICKED.fetch_add(1, Ordering::Relaxed)}>
+//!         Clicked {CLICKED.load(Ordering::Relaxed)} times!
+//!     </Button>
+//! };
+//! ```
+//!
+//! Expressions inside `{}` are Rust, parsed by `avpony-lang`
+//! (see [avpony_lang::syntax::external::rust]).
+//!
+//! ## Runtime
+//! The macro expands to calls into the `avpony_runtime` crate, which must be
+//! a dependency of the calling crate:
+//! * `fragment(Vec<Node>) -> Node`
+//! * `text(&str) -> Node`, and `expr(impl Into<Node>) -> Node`
+//! * `element::<Component>(Vec<(&str, Value)>, Node) -> Node`
+//! * `keyed(key, Node) -> Node`, and `key(key, Node) -> Node`
+//! * `awaiting(future, Node, impl FnOnce(T) -> Node, impl FnOnce(E) -> Node) -> Node`
+//! * `Value` (for Pony expressions in attributes): `from`, `array`, `map`, `tuple`,
+//!   `unary`, `binary`, `apply`, `.member(..)`, and `.index(..)`.
+//!
+//! ## Limitations
+//! Markup has to be valid Rust tokens, so text with unbalanced quotes, or
+//! delimiters (like `don't`) has to be written as a string: `{"don't"}`.
+//!
+
+mod expand;
+mod source;
+
+use avpony_lang::{
+    lint::{self, Level},
+    ponyx::Node,
+    syntax::external::rust::Rust,
+    utils::{Error, Parseable, SourceFile, Spanned},
+};
+use chumsky::{span::Span as _, IterParser, Parser};
+use expand::Expander;
+use quote::quote;
+use source::Source;
+
+///
+/// Turn a parse error into a compiler diagnostic,
+/// placed at the tokens it refers to.
+///
+fn emit(error: Error, source: &Source, file: &SourceFile) -> proc_macro::Level {
+    let span = error.span();
+    let span = source.span_at(span.start()..span.end()).unwrap();
+
+    let level = match lint::default_level(&error) {
+        Level::Deny => proc_macro::Level::Error,
+        Level::Warn | Level::Allow => proc_macro::Level::Warning,
+    };
+
+    let message = error.render(file);
+    proc_macro::Diagnostic::spanned(span, level, message.trim_end()).emit();
+    level
+}
+
+///
+/// Write PonyX markup in Rust, see the [crate] docs.
+///
+#[proc_macro]
+pub fn pony(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let source = match Source::new(input.into()) {
+        Ok(source) => source,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let file = SourceFile::new("pony!", source.text.clone());
+
+    // The parser gets byte offsets from `syn` spans,
+    // which are only available with proc-macro2's fallback.
+    proc_macro2::fallback::force();
+    let (nodes, errors) = Node::<Rust>::parser()
+        .padded()
+        .repeated()
+        .collect::<Vec<_>>()
+        .parse(file.stream())
+        .into_output_errors();
+    proc_macro2::fallback::unforce();
+
+    let failed = errors
+        .into_iter()
+        .map(|error| emit(error, &source, &file))
+        .fold(false, |failed, level| {
+            failed || matches!(level, proc_macro::Level::Error)
+        });
+
+    let empty = quote!(::avpony_runtime::fragment(::std::vec::Vec::new()));
+    let Some(nodes) = nodes.filter(|_| !failed) else {
+        return empty.into();
+    };
+
+    match Expander::new(&source).fragment(&nodes) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,170 @@
+//!
+//! ## Macro Input
+//!
+//! The tokens given to `pony!`, along with their original source text.
+//!
+//! PonyX is whitespace-sensitive, so it's parsed from the source text,
+//! rather than from tokens. Byte offsets in that text are then mapped
+//! back to the input tokens, so that errors, and Rust expressions keep
+//! the spans the user wrote.
+//!
+
+use std::ops::Range;
+
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+pub struct Source {
+    pub text: String,
+    stream: TokenStream,
+
+    ///
+    /// Byte offset of the first token in its file.
+    ///
+    base: usize,
+
+    ///
+    /// Every token (including group delimiters) in order,
+    /// with its byte range in `text`.
+    ///
+    tokens: Vec<(Range<usize>, Span)>,
+}
+
+fn flatten(stream: TokenStream, tokens: &mut Vec<(Range<usize>, Span)>) {
+    for tt in stream {
+        match tt {
+            TokenTree::Group(group) => {
+                tokens.push((group.span_open().byte_range(), group.span_open()));
+                flatten(group.stream(), tokens);
+                tokens.push((group.span_close().byte_range(), group.span_close()));
+            }
+            tt => tokens.push((tt.span().byte_range(), tt.span())),
+        }
+    }
+}
+
+impl Source {
+    pub fn new(stream: TokenStream) -> syn::Result<Self> {
+        let mut tokens = Vec::new();
+        flatten(stream.clone(), &mut tokens);
+
+        let (Some((first_range, first)), Some((_, last))) = (tokens.first(), tokens.last()) else {
+            return Ok(Self {
+                text: String::new(),
+                stream,
+                base: 0,
+                tokens,
+            });
+        };
+
+        let text = first
+            .join(*last)
+            .and_then(|span| span.source_text())
+            .ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    "`pony!` can only be used with markup written in source code",
+                )
+            })?;
+
+        let base = first_range.start;
+        for (range, _) in &mut tokens {
+            *range = range.start.saturating_sub(base)..range.end.saturating_sub(base);
+        }
+
+        Ok(Self {
+            text,
+            stream,
+            base,
+            tokens,
+        })
+    }
+
+    ///
+    /// Byte range of `span` in [Self::text].
+    ///
+    pub fn offset(&self, span: Span) -> Range<usize> {
+        let range = span.byte_range();
+        range.start.saturating_sub(self.base)..range.end.saturating_sub(self.base)
+    }
+
+    ///
+    /// A span covering all tokens overlapping `range`.
+    ///
+    /// Empty ranges (from errors at the end of something) are given
+    /// the span of the next token.
+    ///
+    pub fn span_at(&self, range: Range<usize>) -> Span {
+        let end = range.end.max(range.start + 1);
+        let mut overlapping = self
+            .tokens
+            .iter()
+            .filter(|(token, _)| token.start < end && range.start < token.end)
+            .map(|(_, span)| *span);
+
+        if let Some(first) = overlapping.next() {
+            return overlapping
+                .last()
+                .and_then(|last| first.join(last))
+                .unwrap_or(first);
+        }
+
+        self.tokens
+            .iter()
+            .find(|(token, _)| token.start >= range.start)
+            .or(self.tokens.last())
+            .map(|(_, span)| *span)
+            .unwrap_or_else(Span::call_site)
+    }
+
+    ///
+    /// The input tokens lying within `range`, keeping their spans.
+    ///
+    pub fn tokens_in(&self, range: Range<usize>) -> TokenStream {
+        self.filter(self.stream.clone(), &range)
+    }
+
+    fn filter(&self, stream: TokenStream, range: &Range<usize>) -> TokenStream {
+        let mut out = TokenStream::new();
+
+        for tt in stream {
+            let token = self.offset(tt.span());
+
+            if range.start <= token.start && token.end <= range.end {
+                out.extend([tt]);
+            } else if let TokenTree::Group(group) = tt {
+                if token.start < range.end && range.start < token.end {
+                    out.extend(self.filter(group.stream(), range));
+                }
+            }
+        }
+
+        out
+    }
+
+    ///
+    /// The first brace-delimited group starting at, or after `offset`,
+    /// such as the `{#if cond}` header of a block.
+    ///
+    pub fn group_from(&self, offset: usize) -> Option<Group> {
+        self.find_group(self.stream.clone(), offset)
+    }
+
+    fn find_group(&self, stream: TokenStream, offset: usize) -> Option<Group> {
+        stream.into_iter().find_map(|tt| {
+            let TokenTree::Group(group) = tt else {
+                return None;
+            };
+
+            let range = self.offset(group.span());
+            if range.end <= offset {
+                return None;
+            }
+
+            if group.delimiter() == Delimiter::Brace && range.start >= offset {
+                return Some(group);
+            }
+
+            self.find_group(group.stream(), offset)
+        })
+    }
+}
@@ -51,9 +51,11 @@ impl<'de> serde::Deserialize<'de> for EntityId {
             where
                 E: serde::de::Error,
             {
-                // I know this is inefficient, and a slice is better.
-                // TODO: replace this with a slice.
-                Ok(EntityId(v[1..v.len() - 1].to_string()))
+                // Legacy entities (like `&amp`) don't need their `;`.
+                let v = v.strip_prefix('&').unwrap_or(v);
+                let v = v.strip_suffix(';').unwrap_or(v);
+
+                Ok(EntityId(v.to_string()))
             }
         }
 
@@ -72,6 +74,7 @@ pub fn register_entities(span: Span, contents: String) -> impl Iterator<Item = s
 
     let mut map = map.into_iter().collect::<Vec<_>>();
     map.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    map.dedup_by(|a, b| a.0 == b.0);
 
     use syn::token::Comma;
     let (codes, values): (Punctuated<_, Comma>, Punctuated<_, Comma>) = map
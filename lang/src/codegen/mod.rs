@@ -0,0 +1,28 @@
+//!
+//! ## Code Generation
+//!
+//! Compiles parsed AvPony files into modules of their external language.
+//!
+//! Generated modules export a single default render function, which builds
+//! its UI through the `@avpony/runtime` package:
+//!
+//! | PonyX                     | Runtime call                                            |
+//! |---------------------------|---------------------------------------------------------|
+//! | `<Tag a=1>...</Tag>`      | `$.element(Tag, { a: 1 }, () => [...])`                 |
+//! | `{expr}`                  | `$.expr(() => expr)`                                    |
+//! | `{#if c}...{/if}`         | `$.branch([[() => c, () => [...]]], else?)`             |
+//! | `{#for x in xs by k}`     | `$.each(() => xs, (x) => [...], (x) => k, empty?)`      |
+//! | `{#await p}`              | `$.awaiting(() => p, { pending, then, catch })`         |
+//! | `{#key k}`                | `$.key(() => k, () => [...])`                           |
+//! | `{#match v}`              | `$.match(() => v, [[pattern, () => [...]]], default?)`  |
+//! | `{@debug e}`              | `$.debug(() => e)`                                      |
+//!
+//! Pony expressions (in attribute values) that have no direct counterpart
+//! are lowered to runtime helpers: `$.tuple(..)`, `$.unary(op, a)`,
+//! `$.binary(op, a, b)`, and `$.apply(f, x)`.
+//!
+//! External languages supported:
+//! * TypeScript.
+//!
+
+pub mod typescript;
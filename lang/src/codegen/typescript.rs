@@ -0,0 +1,751 @@
+//!
+//! ## TypeScript Code Generation
+//!
+//! Compiles a [File] into a TypeScript module, using [swc_ecma_ast] nodes,
+//! which can then be printed with [emit].
+//!
+//! The module is kept as it is, at the top-level (so its exports can refer to
+//! anything it declares), followed by the default-exported render function:
+//!
+//! ```ts
+//! import * as $ from "@avpony/runtime";
+//! let i = 0;
+//!
+//! export default function component($props) {
+//!     return $.fragment(() => [ ... ]);
+//! }
+//! ```
+//!
+//! Placeholders (from files with syntax errors) are compiled to `undefined`,
+//! and tag generics are erased.
+//!
+
+use swc_common::{sync::Lrc, FileName, SourceMap, DUMMY_SP};
+use swc_ecma_ast as ast;
+use swc_ecma_codegen::{text_writer::JsWriter, Config, Emitter};
+
+use crate::{
+    lexical::{self, boolean::BooleanLit, number::NumberLit, Literal},
+    ponyx::{
+        blocks::{
+            await_block::{AwaitBlock, Branch as AwaitBranch},
+            for_block::ForBlock,
+            if_block::{Branch as IfBranch, IfBlock},
+            key_block::KeyBlock,
+            match_block::{Arm, MatchBlock},
+            LogicBlock,
+        },
+        statement::AtStatement,
+        tag::{
+            attribute::{Attribute, AttributeKey},
+            name::TagName,
+            Tag,
+        },
+        Node,
+    },
+    syntax::{
+        external::typescript::{TypeScript, VarDecl},
+        file::File,
+        map::Field,
+        operator::{BinaryOperator, UnaryOperator},
+        Expr, SoloExpr,
+    },
+    utils::{placeholder::Maybe, SourceFile},
+};
+
+///
+/// Local name of the imported runtime namespace.
+///
+const RUNTIME: &str = "$";
+
+///
+/// Module specifier of the runtime package.
+///
+const RUNTIME_MODULE: &str = "@avpony/runtime";
+
+///
+/// Compile a TypeScript AvPony file into a render module.
+///
+pub fn compile(file: &File<TypeScript>) -> ast::Module {
+    let mut body = vec![ast::ModuleItem::ModuleDecl(ast::ModuleDecl::Import(
+        ast::ImportDecl {
+            span: DUMMY_SP,
+            specifiers: vec![ast::ImportSpecifier::Namespace(
+                ast::ImportStarAsSpecifier {
+                    span: DUMMY_SP,
+                    local: ident(RUNTIME),
+                },
+            )],
+            src: Box::new(ast::Str {
+                span: DUMMY_SP,
+                value: RUNTIME_MODULE.into(),
+                raw: None,
+            }),
+            type_only: false,
+            with: None,
+            phase: Default::default(),
+        },
+    ))];
+
+    body.extend(file.module.body.iter().cloned());
+
+    let stmts = vec![ast::Stmt::Return(ast::ReturnStmt {
+        span: DUMMY_SP,
+        arg: Some(Box::new(call(
+            runtime("fragment"),
+            [children(std::slice::from_ref(&file.pony))],
+        ))),
+    })];
+
+    body.push(ast::ModuleItem::ModuleDecl(
+        ast::ModuleDecl::ExportDefaultDecl(ast::ExportDefaultDecl {
+            span: DUMMY_SP,
+            decl: ast::DefaultDecl::Fn(ast::FnExpr {
+                ident: Some(ident("component")),
+                function: Box::new(ast::Function {
+                    params: vec![ast::Param {
+                        span: DUMMY_SP,
+                        decorators: vec![],
+                        pat: binding("$props"),
+                    }],
+                    decorators: vec![],
+                    span: DUMMY_SP,
+                    body: Some(ast::BlockStmt {
+                        span: DUMMY_SP,
+                        stmts,
+                    }),
+                    is_generator: false,
+                    is_async: false,
+                    type_params: None,
+                    return_type: None,
+                }),
+            }),
+        }),
+    ));
+
+    ast::Module {
+        span: DUMMY_SP,
+        body,
+        shebang: None,
+    }
+}
+
+///
+/// Print a compiled module as TypeScript source code.
+///
+/// `source` is the file the module was compiled from,
+/// as nodes from its module keep their original spans.
+///
+pub fn emit(module: &ast::Module, source: &SourceFile) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    cm.new_source_file(
+        FileName::Custom(source.path().to_string()),
+        source.contents().to_string(),
+    );
+
+    let mut buf = Vec::new();
+    {
+        let mut emitter = Emitter {
+            cfg: Config::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+
+        emitter
+            .emit_module(module)
+            .expect("writing to a `Vec` cannot fail");
+    }
+
+    String::from_utf8(buf).expect("swc only emits UTF-8")
+}
+
+fn ident(name: &str) -> ast::Ident {
+    ast::Ident::new(name.into(), DUMMY_SP)
+}
+
+fn binding(name: &str) -> ast::Pat {
+    ast::Pat::Ident(ident(name).into())
+}
+
+fn str_lit(value: &str) -> ast::Expr {
+    ast::Expr::Lit(ast::Lit::Str(ast::Str {
+        span: DUMMY_SP,
+        value: value.into(),
+        raw: None,
+    }))
+}
+
+fn undefined() -> ast::Expr {
+    ast::Expr::Ident(ident("undefined"))
+}
+
+///
+/// `$.<name>`
+///
+fn runtime(name: &str) -> ast::Expr {
+    ast::Expr::Member(ast::MemberExpr {
+        span: DUMMY_SP,
+        obj: Box::new(ast::Expr::Ident(ident(RUNTIME))),
+        prop: ast::MemberProp::Ident(ident(name)),
+    })
+}
+
+fn call(callee: ast::Expr, args: impl IntoIterator<Item = ast::Expr>) -> ast::Expr {
+    ast::Expr::Call(ast::CallExpr {
+        span: DUMMY_SP,
+        callee: ast::Callee::Expr(Box::new(callee)),
+        args: args
+            .into_iter()
+            .map(|expr| ast::ExprOrSpread {
+                spread: None,
+                expr: Box::new(expr),
+            })
+            .collect(),
+        type_args: None,
+    })
+}
+
+fn arrow(params: impl IntoIterator<Item = ast::Pat>, body: ast::BlockStmtOrExpr) -> ast::Expr {
+    ast::Expr::Arrow(ast::ArrowExpr {
+        span: DUMMY_SP,
+        params: params.into_iter().collect(),
+        body: Box::new(body),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    })
+}
+
+///
+/// `() => <expr>`
+///
+fn thunk(expr: ast::Expr) -> ast::Expr {
+    arrow([], ast::BlockStmtOrExpr::Expr(Box::new(expr)))
+}
+
+fn array(items: impl IntoIterator<Item = ast::Expr>) -> ast::Expr {
+    ast::Expr::Array(ast::ArrayLit {
+        span: DUMMY_SP,
+        elems: items
+            .into_iter()
+            .map(|expr| {
+                Some(ast::ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(expr),
+                })
+            })
+            .collect(),
+    })
+}
+
+fn object(props: impl IntoIterator<Item = (ast::PropName, ast::Expr)>) -> ast::Expr {
+    ast::Expr::Object(ast::ObjectLit {
+        span: DUMMY_SP,
+        props: props
+            .into_iter()
+            .map(|(key, value)| {
+                ast::PropOrSpread::Prop(Box::new(ast::Prop::KeyValue(ast::KeyValueProp {
+                    key,
+                    value: Box::new(value),
+                })))
+            })
+            .collect(),
+    })
+}
+
+fn prop_name(key: &str) -> ast::PropName {
+    ast::PropName::Ident(ident(key))
+}
+
+fn external(expr: &Maybe<ast::Expr>) -> ast::Expr {
+    match expr {
+        Maybe::Present(expr) => expr.clone(),
+        Maybe::Placeholder(_) => undefined(),
+    }
+}
+
+fn variable_declaration(decl: &VarDecl) -> Option<ast::Stmt> {
+    let Maybe::Present(pat) = &decl.pat else {
+        return None;
+    };
+
+    Some(ast::Stmt::Decl(ast::Decl::Var(Box::new(ast::VarDecl {
+        span: DUMMY_SP,
+        kind: decl.ty,
+        declare: false,
+        decls: vec![ast::VarDeclarator {
+            span: DUMMY_SP,
+            name: pat.clone(),
+            init: Some(Box::new(external(&decl.init))),
+            definite: false,
+        }],
+    }))))
+}
+
+///
+/// `() => [...]`, see [children_with].
+///
+fn children(nodes: &[Node<TypeScript>]) -> ast::Expr {
+    children_with([], nodes)
+}
+
+///
+/// `(<params>) => [...]`, or `(<params>) => { <decls>; return [...]; }`
+/// if there are any `{@let}`, or `{@const}` statements among `nodes`.
+///
+fn children_with(
+    params: impl IntoIterator<Item = ast::Pat>,
+    nodes: &[Node<TypeScript>],
+) -> ast::Expr {
+    let mut stmts = Vec::new();
+    let mut items = Vec::new();
+    let mut space = false;
+
+    for node in nodes {
+        match node {
+            Node::Statement(AtStatement::Let(decl) | AtStatement::Const(decl)) => {
+                stmts.extend(variable_declaration(decl))
+            }
+            // Whitespace between nodes is a single space,
+            // but none is kept at the start, or end.
+            Node::Text(text) if text.text.trim().is_empty() => space = !items.is_empty(),
+            node => {
+                if let Some(item) = compile_node(node) {
+                    if std::mem::take(&mut space) {
+                        items.push(str_lit(" "));
+                    }
+                    items.push(item);
+                }
+            }
+        }
+    }
+
+    if stmts.is_empty() {
+        return arrow(params, ast::BlockStmtOrExpr::Expr(Box::new(array(items))));
+    }
+
+    stmts.push(ast::Stmt::Return(ast::ReturnStmt {
+        span: DUMMY_SP,
+        arg: Some(Box::new(array(items))),
+    }));
+
+    arrow(
+        params,
+        ast::BlockStmtOrExpr::BlockStmt(ast::BlockStmt {
+            span: DUMMY_SP,
+            stmts,
+        }),
+    )
+}
+
+fn compile_node(node: &Node<TypeScript>) -> Option<ast::Expr> {
+    match node {
+        Node::Comment(_) => None,
+        Node::Text(text) => Some(str_lit(&collapse_whitespace(&text.text))),
+        Node::Entity(entity) => Some(str_lit(&entity.value)),
+        Node::Mustache(expr) => Some(call(runtime("expr"), [thunk(external(&expr.expr))])),
+        Node::Statement(AtStatement::Debug(debug)) => {
+            Some(call(runtime("debug"), [thunk(external(&debug.expr))]))
+        }
+        // Handled by `children_with`.
+        Node::Statement(AtStatement::Let(_) | AtStatement::Const(_)) => None,
+        Node::Block(block) => Some(compile_block(block)),
+        Node::Tag(tag) => Some(compile_tag(tag)),
+    }
+}
+
+///
+/// `text`, with each run of whitespace in it (including at its start, and end)
+/// as a single space, as in HTML.
+///
+fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut space = false;
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            space = true;
+            continue;
+        }
+
+        if std::mem::take(&mut space) {
+            collapsed.push(' ');
+        }
+        collapsed.push(c);
+    }
+
+    if space {
+        collapsed.push(' ');
+    }
+
+    collapsed
+}
+
+fn compile_block(block: &LogicBlock<TypeScript>) -> ast::Expr {
+    match block {
+        LogicBlock::If(block) => compile_if(block),
+        LogicBlock::For(block) => compile_for(block),
+        LogicBlock::Await(block) => compile_await(block),
+        LogicBlock::Key(block) => compile_key(block),
+        LogicBlock::Match(block) => compile_match(block),
+    }
+}
+
+fn compile_if(block: &IfBlock<TypeScript>) -> ast::Expr {
+    let mut cases = Vec::new();
+    let mut otherwise = None;
+
+    for branch in &block.branches {
+        match branch {
+            IfBranch::If(branch) => cases.push(array([
+                thunk(external(&branch.expr)),
+                children(&branch.contents),
+            ])),
+            IfBranch::ElseIf(branch) => cases.push(array([
+                thunk(external(&branch.expr)),
+                children(&branch.contents),
+            ])),
+            IfBranch::Else(branch) => otherwise = Some(children(&branch.contents)),
+        }
+    }
+
+    call(
+        runtime("branch"),
+        std::iter::once(array(cases)).chain(otherwise),
+    )
+}
+
+fn compile_for(block: &ForBlock<TypeScript>) -> ast::Expr {
+    let item = match &block.ident {
+        Maybe::Present(ident) => binding(&ident.value),
+        Maybe::Placeholder(_) => binding("_"),
+    };
+
+    let mut args = vec![
+        thunk(external(&block.iter)),
+        children_with([item.clone()], &block.children),
+    ];
+
+    if block.key.is_some() || block.empty_case.is_some() {
+        args.push(match &block.key {
+            Some(key) => arrow([item], ast::BlockStmtOrExpr::Expr(Box::new(external(key)))),
+            None => undefined(),
+        });
+    }
+
+    if let Some(empty) = &block.empty_case {
+        args.push(children(empty));
+    }
+
+    call(runtime("each"), args)
+}
+
+fn compile_await(block: &AwaitBlock<TypeScript>) -> ast::Expr {
+    let branches = block.branches.iter().map(|(branch, nodes)| match branch {
+        AwaitBranch::Pending => (prop_name("pending"), children(nodes)),
+        AwaitBranch::Success(ident) => (prop_name("then"), children_with(bound(ident), nodes)),
+        AwaitBranch::Failure(ident) => (prop_name("catch"), children_with(bound(ident), nodes)),
+    });
+
+    call(
+        runtime("awaiting"),
+        [thunk(external(&block.expr)), object(branches)],
+    )
+}
+
+fn compile_key(block: &KeyBlock<TypeScript>) -> ast::Expr {
+    call(
+        runtime("key"),
+        [thunk(external(&block.expr)), children(&block.children)],
+    )
+}
+
+fn compile_match(block: &MatchBlock<TypeScript>) -> ast::Expr {
+    let arms = block.arms.iter().filter_map(|arm| match arm {
+        Arm::When(arm) => Some(array([
+            match &arm.pattern {
+                Maybe::Present(pattern) => pattern.expr.clone(),
+                Maybe::Placeholder(_) => undefined(),
+            },
+            children(&arm.children),
+        ])),
+        Arm::Default(_) => None,
+    });
+
+    let default = block.default_arm().map(|arm| {
+        children_with(
+            arm.binding.iter().map(|ident| binding(&ident.value)),
+            &arm.children,
+        )
+    });
+
+    call(
+        runtime("match"),
+        [thunk(external(&block.expr)), array(arms)]
+            .into_iter()
+            .chain(default),
+    )
+}
+
+///
+/// Parameters binding an optional identifier.
+///
+fn bound(ident: &Maybe<lexical::Identifier>) -> Vec<ast::Pat> {
+    match ident {
+        Maybe::Present(ident) => vec![binding(&ident.value)],
+        Maybe::Placeholder(_) => vec![],
+    }
+}
+
+fn compile_tag(tag: &Tag<TypeScript>) -> ast::Expr {
+    let (name, attributes, nodes) = match tag {
+        Tag::SelfClosing(tag) => (&tag.name, &tag.attributes, None),
+        Tag::Enclosing(tag) => (&tag.name, &tag.attributes, Some(&tag.children)),
+    };
+
+    let props = object(attributes.iter().map(|attribute| match attribute {
+        Attribute::Key(key) => (
+            attribute_key(key),
+            ast::Expr::Lit(ast::Lit::Bool(true.into())),
+        ),
+        Attribute::KeyValue(assignment) => (
+            attribute_key(&assignment.key),
+            match &assignment.value {
+                Maybe::Present(value) => compile_solo_expr(value),
+                Maybe::Placeholder(_) => undefined(),
+            },
+        ),
+    }));
+
+    call(
+        runtime("element"),
+        [tag_name(name), props]
+            .into_iter()
+            .chain(nodes.map(|nodes| children(nodes))),
+    )
+}
+
+///
+/// `Path.To.Component` as a member expression.
+///
+fn tag_name(name: &TagName<TypeScript>) -> ast::Expr {
+    let mut path = name.path.iter();
+    let root = path
+        .next()
+        .map(|segment| ast::Expr::Ident(ident(&segment.value)))
+        .unwrap_or_else(undefined);
+
+    path.fold(root, |obj, segment| {
+        ast::Expr::Member(ast::MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(obj),
+            prop: ast::MemberProp::Ident(ident(&segment.value)),
+        })
+    })
+}
+
+fn attribute_key(key: &AttributeKey) -> ast::PropName {
+    match key {
+        AttributeKey::Named(name) => prop_name(&name.value),
+        AttributeKey::Directive(directive) => {
+            let director = match &directive.director {
+                Maybe::Present(director) => director.value.as_str(),
+                Maybe::Placeholder(_) => "",
+            };
+
+            ast::PropName::Str(ast::Str {
+                span: DUMMY_SP,
+                value: format!("{}:{director}", directive.base.value).into(),
+                raw: None,
+            })
+        }
+    }
+}
+
+fn compile_solo_expr(expr: &SoloExpr<TypeScript>) -> ast::Expr {
+    compile_expr(&expr.clone().into())
+}
+
+fn compile_maybe_expr(expr: &Maybe<Expr<TypeScript>>) -> ast::Expr {
+    match expr {
+        Maybe::Present(expr) => compile_expr(expr),
+        Maybe::Placeholder(_) => undefined(),
+    }
+}
+
+fn compile_expr(expr: &Expr<TypeScript>) -> ast::Expr {
+    match expr {
+        Expr::Literal(literal) => compile_literal(literal),
+        Expr::Identifier(ident) => ast::Expr::Ident(self::ident(&ident.value)),
+        Expr::UnaryOp(op) => {
+            let UnaryOperator::Symbols(symbol) = &op.operator;
+            call(
+                runtime("unary"),
+                [str_lit(&symbol.value), compile_expr(&op.operand)],
+            )
+        }
+        Expr::Array(array) => self::array(array.contents.iter().map(compile_expr)),
+        Expr::Map(map) => object(map.fields.iter().filter_map(|field| match field {
+            Field::Key(field) => match &field.ident {
+                Maybe::Present(key) => Some((
+                    prop_name(&key.value),
+                    ast::Expr::Lit(ast::Lit::Bool(true.into())),
+                )),
+                Maybe::Placeholder(_) => None,
+            },
+            Field::KeyValue(field) => match &field.key {
+                Maybe::Present(key) => {
+                    Some((prop_name(&key.value), compile_maybe_expr(&field.value)))
+                }
+                Maybe::Placeholder(_) => None,
+            },
+        })),
+        Expr::Tuple(tuple) => call(runtime("tuple"), tuple.items.iter().map(compile_expr)),
+        Expr::Parenthesised(paren) => compile_expr(&paren.inner),
+        Expr::External(expr) => external(&expr.expr),
+        Expr::MemberAccess(access) => match &access.member {
+            Maybe::Present(member) => ast::Expr::Member(ast::MemberExpr {
+                span: DUMMY_SP,
+                obj: Box::new(compile_expr(&access.receiver)),
+                prop: ast::MemberProp::Ident(ident(&member.value)),
+            }),
+            Maybe::Placeholder(_) => undefined(),
+        },
+        Expr::Indexing(indexing) => ast::Expr::Member(ast::MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::new(compile_expr(&indexing.receiver)),
+            prop: ast::MemberProp::Computed(ast::ComputedPropName {
+                span: DUMMY_SP,
+                expr: Box::new(compile_maybe_expr(&indexing.index)),
+            }),
+        }),
+        Expr::BinaryOp(op) => {
+            let operator = match &op.operator {
+                BinaryOperator::Symbols(symbol) => symbol.value.as_str(),
+                BinaryOperator::Named(named) => named.ident.value.as_str(),
+            };
+
+            call(
+                runtime("binary"),
+                [
+                    str_lit(operator),
+                    compile_expr(&op.operands.0),
+                    compile_maybe_expr(&op.operands.1),
+                ],
+            )
+        }
+        Expr::Application(application) => call(
+            runtime("apply"),
+            [
+                compile_expr(&application.function),
+                compile_expr(&application.argument),
+            ],
+        ),
+    }
+}
+
+fn compile_literal(literal: &Literal) -> ast::Expr {
+    let number = |value: f64| {
+        ast::Expr::Lit(ast::Lit::Num(ast::Number {
+            span: DUMMY_SP,
+            value,
+            raw: None,
+        }))
+    };
+
+    match literal {
+        Literal::Number(NumberLit::Integer(int)) => number(int.value.into()),
+        Literal::Number(NumberLit::Float(float)) => number(float.value),
+        Literal::String(string) => str_lit(&string.value),
+        Literal::Boolean(boolean) => ast::Expr::Lit(ast::Lit::Bool(
+            matches!(boolean, BooleanLit::True(_)).into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Parseable, SourceFile},
+    };
+
+    use super::{compile, emit};
+
+    #[test]
+    fn compile_file() {
+        let (source, _) = SourceFile::test_file(
+            r#"
+        import { Button } from "@avdan/ui";
+        let i = 0;
+        ---
+        <Button primary on:click={() => i++}>
+            {#if i > 0}
+                Clicked {i} times!
+            {:else}
+                Click me!
+            {/if}
+        </Button>
+        "#,
+        );
+
+        let file = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_result()
+            .expect("file should parse");
+
+        let out = emit(&compile(&file), &source);
+
+        assert!(out.starts_with(r#"import * as $ from "@avpony/runtime";"#));
+        assert!(out.contains(r#"import { Button } from "@avdan/ui";"#));
+        assert!(out.contains("export default function component($props)"));
+        assert!(out.contains("$.element(Button"));
+        assert!(out.contains(r#""on:click""#));
+        assert!(out.contains("$.branch("));
+    }
+
+    #[test]
+    fn exports() {
+        let (source, _) = SourceFile::test_file(
+            "let count = 0;\nexport function increment() {\n    count++;\n}\nexport { count };\n---\n<Text>{count}</Text>\n",
+        );
+
+        let file = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_result()
+            .expect("file should parse");
+
+        let out = emit(&compile(&file), &source);
+        let component = out.find("export default function component").unwrap();
+
+        // `count` is declared in the same scope as the exports referring to it.
+        let declared = out.find("let count = 0;").unwrap();
+        assert!(declared < out.find("export function increment").unwrap());
+        assert!(declared < component);
+        assert!(out[..component].contains("export { count };"));
+        assert!(!out[component..].contains("let count"));
+    }
+
+    #[test]
+    fn whitespace() {
+        let compile_src = |src| {
+            let (source, _) = SourceFile::test_file(src);
+            let file = File::<TypeScript>::parser()
+                .parse(source.stream())
+                .into_result()
+                .expect("file should parse");
+
+            emit(&compile(&file), &source)
+        };
+
+        assert!(compile_src("let a = 1;\n---\n<Row>{a} {a}</Row>").contains(r#"" ""#));
+        assert!(!compile_src("let a = 1;\n---\n<Row> {a}\n</Row>").contains(r#"" ""#));
+
+        let text = compile_src("---\n<Row>\n    Clicked\n        many   times!\n</Row>");
+        assert!(text.contains(r#"" Clicked many times! ""#));
+    }
+}
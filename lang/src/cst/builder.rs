@@ -0,0 +1,349 @@
+//!
+//! Builds a [SyntaxNode] tree from the spans of AST nodes.
+//!
+//! Nodes are collected as (kind, byte range) pairs, then nested by
+//! their ranges, with any uncovered source becoming tokens.
+//!
+
+use std::{cmp::Reverse, iter::Peekable, ops::Range};
+
+use chumsky::span::Span as _;
+
+use crate::{
+    lexical::Identifier,
+    ponyx::{
+        blocks::{
+            await_block::Branch as AwaitBranch, if_block::Branch as IfBranch, match_block::Arm,
+            LogicBlock,
+        },
+        statement::AtStatement,
+        tag::{
+            attribute::{Attribute, AttributeKey},
+            name::TagName,
+            Tag,
+        },
+        Node,
+    },
+    syntax::{external::External, file::File, map::Field, Expr, SoloExpr},
+    utils::{
+        placeholder::{HasPlaceholder, Maybe},
+        FileId, SourceFile, Span, Spanned,
+    },
+};
+
+use super::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+#[derive(Debug, Default)]
+pub struct Builder {
+    nodes: Vec<(SyntaxKind, Range<usize>)>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, kind: SyntaxKind, spanned: &impl Spanned) {
+        let span = spanned.span();
+        self.nodes.push((kind, span.start()..span.end()));
+    }
+
+    fn maybe<T: HasPlaceholder>(&mut self, maybe: &Maybe<T>, f: impl FnOnce(&mut Self, &T)) {
+        match maybe {
+            Maybe::Present(t) => f(self, t),
+            Maybe::Placeholder(placeholder) => self.push(SyntaxKind::Placeholder, placeholder),
+        }
+    }
+
+    fn ident(&mut self, ident: &Identifier) {
+        self.push(SyntaxKind::Identifier, ident);
+    }
+
+    pub fn file<Ext: External>(&mut self, file: &File<Ext>) {
+        // The module runs up to the `---` fence.
+        self.nodes
+            .push((SyntaxKind::Module, file.span().start()..file.fence.start()));
+
+        self.node(&file.pony);
+    }
+
+    pub fn nodes<Ext: External>(&mut self, nodes: &[Node<Ext>]) {
+        nodes.iter().for_each(|node| self.node(node));
+    }
+
+    pub fn node<Ext: External>(&mut self, node: &Node<Ext>) {
+        match node {
+            Node::Comment(comment) => self.push(SyntaxKind::Comment, comment),
+            Node::Text(text) => self.push(SyntaxKind::Text, text),
+            Node::Entity(entity) => self.push(SyntaxKind::Entity, entity),
+            Node::Mustache(expr) => self.push(SyntaxKind::ExternalExpr, expr),
+            Node::Statement(AtStatement::Let(decl)) => self.push(SyntaxKind::LetDeclaration, decl),
+            Node::Statement(AtStatement::Const(decl)) => {
+                self.push(SyntaxKind::ConstDeclaration, decl)
+            }
+            Node::Statement(AtStatement::Debug(debug)) => {
+                self.push(SyntaxKind::DebugStatement, debug)
+            }
+            Node::Block(block) => self.block(block),
+            Node::Tag(tag) => self.tag(tag),
+        }
+    }
+
+    fn block<Ext: External>(&mut self, block: &LogicBlock<Ext>) {
+        match block {
+            LogicBlock::If(block) => {
+                self.push(SyntaxKind::IfBlock, block);
+                for branch in &block.branches {
+                    match branch {
+                        IfBranch::If(branch) => {
+                            self.push(SyntaxKind::IfBranch, branch);
+                            self.nodes(&branch.contents);
+                        }
+                        IfBranch::ElseIf(branch) => {
+                            self.push(SyntaxKind::ElseIfBranch, branch);
+                            self.nodes(&branch.contents);
+                        }
+                        IfBranch::Else(branch) => {
+                            self.push(SyntaxKind::ElseBranch, branch);
+                            self.nodes(&branch.contents);
+                        }
+                    }
+                }
+            }
+            LogicBlock::For(block) => {
+                self.push(SyntaxKind::ForBlock, block);
+                self.maybe(&block.ident, Self::ident);
+                self.nodes(&block.children);
+                if let Some(empty) = &block.empty_case {
+                    self.nodes(empty);
+                }
+            }
+            LogicBlock::Await(block) => {
+                self.push(SyntaxKind::AwaitBlock, block);
+                for (branch, nodes) in &block.branches {
+                    match branch {
+                        AwaitBranch::Pending => (),
+                        AwaitBranch::Success(ident) | AwaitBranch::Failure(ident) => {
+                            self.maybe(ident, Self::ident)
+                        }
+                    }
+                    self.nodes(nodes);
+                }
+            }
+            LogicBlock::Key(block) => {
+                self.push(SyntaxKind::KeyBlock, block);
+                self.nodes(&block.children);
+            }
+            LogicBlock::Match(block) => {
+                self.push(SyntaxKind::MatchBlock, block);
+                for arm in &block.arms {
+                    match arm {
+                        Arm::When(arm) => {
+                            self.push(SyntaxKind::WhenArm, arm);
+                            self.nodes(&arm.children);
+                        }
+                        Arm::Default(arm) => {
+                            self.push(SyntaxKind::DefaultArm, arm);
+                            if let Some(binding) = &arm.binding {
+                                self.ident(binding);
+                            }
+                            self.nodes(&arm.children);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn tag<Ext: External>(&mut self, tag: &Tag<Ext>) {
+        self.push(SyntaxKind::Tag, tag);
+
+        let (name, attributes) = match tag {
+            Tag::SelfClosing(tag) => (&tag.name, &tag.attributes),
+            Tag::Enclosing(tag) => {
+                self.nodes(&tag.children);
+                (&tag.name, &tag.attributes)
+            }
+        };
+
+        self.tag_name(name);
+        for attribute in attributes {
+            self.push(SyntaxKind::Attribute, attribute);
+            match attribute {
+                Attribute::Key(key) => self.attribute_key(key),
+                Attribute::KeyValue(assignment) => {
+                    self.attribute_key(&assignment.key);
+                    self.maybe(&assignment.value, Self::solo_expr);
+                }
+            }
+        }
+    }
+
+    fn tag_name<Ext: External>(&mut self, name: &TagName<Ext>) {
+        self.push(SyntaxKind::TagName, name);
+        name.path.iter().for_each(|segment| self.ident(segment));
+        name.generics
+            .iter()
+            .for_each(|generic| self.tag_name(generic));
+    }
+
+    fn attribute_key(&mut self, key: &AttributeKey) {
+        match key {
+            AttributeKey::Named(name) => self.ident(name),
+            AttributeKey::Directive(directive) => {
+                self.push(SyntaxKind::Directive, directive);
+                self.ident(&directive.base);
+                self.maybe(&directive.director, Self::ident);
+            }
+        }
+    }
+
+    fn solo_expr<Ext: External>(&mut self, expr: &SoloExpr<Ext>) {
+        self.expr(&expr.clone().into());
+    }
+
+    fn expr<Ext: External>(&mut self, expr: &Expr<Ext>) {
+        match expr {
+            Expr::Literal(literal) => self.push(SyntaxKind::Literal, literal),
+            Expr::Identifier(ident) => self.ident(ident),
+            Expr::UnaryOp(op) => {
+                self.push(SyntaxKind::UnaryOperation, op);
+                self.push(SyntaxKind::Operator, &op.operator);
+                self.expr(&op.operand);
+            }
+            Expr::Array(array) => {
+                self.push(SyntaxKind::Array, array);
+                array.contents.iter().for_each(|item| self.expr(item));
+            }
+            Expr::Map(map) => {
+                self.push(SyntaxKind::Map, map);
+                for field in map.fields.iter() {
+                    self.push(SyntaxKind::Field, field);
+                    match field {
+                        Field::Key(field) => self.maybe(&field.ident, Self::ident),
+                        Field::KeyValue(field) => {
+                            self.maybe(&field.key, Self::ident);
+                            self.maybe(&*field.value, Self::expr);
+                        }
+                    }
+                }
+            }
+            Expr::Tuple(tuple) => {
+                self.push(SyntaxKind::Tuple, tuple);
+                tuple.items.iter().for_each(|item| self.expr(item));
+            }
+            Expr::Parenthesised(paren) => {
+                self.push(SyntaxKind::Parenthesised, paren);
+                self.expr(&paren.inner);
+            }
+            Expr::External(expr) => self.push(SyntaxKind::ExternalExpr, expr),
+            Expr::MemberAccess(access) => {
+                self.push(SyntaxKind::MemberAccess, access);
+                self.expr(&access.receiver);
+                self.maybe(&access.member, Self::ident);
+            }
+            Expr::Indexing(indexing) => {
+                self.push(SyntaxKind::Indexing, indexing);
+                self.expr(&indexing.receiver);
+                self.maybe(&*indexing.index, Self::expr);
+            }
+            Expr::BinaryOp(op) => {
+                self.push(SyntaxKind::BinaryOperation, op);
+                self.expr(&op.operands.0);
+                self.push(SyntaxKind::Operator, &op.operator);
+                self.maybe(&*op.operands.1, Self::expr);
+            }
+            Expr::Application(application) => {
+                self.push(SyntaxKind::Application, application);
+                self.expr(&application.function);
+                self.expr(&application.argument);
+            }
+        }
+    }
+
+    ///
+    /// Nest the collected nodes into a tree covering all of `source`.
+    ///
+    pub fn finish(mut self, source: &SourceFile) -> SyntaxNode {
+        // Parents before children: by start, then longest first.
+        // (The sort is stable, so equal ranges keep the order they were pushed in.)
+        self.nodes
+            .sort_by_key(|(_, range)| (range.start, Reverse(range.end)));
+
+        let src = source.contents();
+        let mut nodes = self.nodes.into_iter().peekable();
+        build_node(SyntaxKind::File, 0..src.len(), &mut nodes, src, source.id())
+    }
+}
+
+fn build_node(
+    kind: SyntaxKind,
+    range: Range<usize>,
+    nodes: &mut Peekable<impl Iterator<Item = (SyntaxKind, Range<usize>)>>,
+    src: &str,
+    file: FileId,
+) -> SyntaxNode {
+    let mut children = Vec::new();
+    let mut pos = range.start;
+
+    while let Some((_, child)) = nodes.peek() {
+        if child.start > range.end || (child.start == range.end && !child.is_empty()) {
+            break;
+        }
+
+        let (child_kind, child) = nodes.next().unwrap();
+
+        // Clamp children overlapping their siblings, or parent.
+        let start = child.start.max(pos);
+        let child = start..child.end.min(range.end).max(start);
+
+        push_tokens(&mut children, pos..child.start, src, file);
+        pos = child.end;
+        children.push(SyntaxElement::Node(build_node(
+            child_kind, child, nodes, src, file,
+        )));
+    }
+
+    push_tokens(&mut children, pos..range.end, src, file);
+
+    SyntaxNode {
+        kind,
+        span: Span::new(file, range),
+        children,
+    }
+}
+
+fn token_kind(ch: char) -> SyntaxKind {
+    match ch {
+        ch if ch.is_whitespace() => SyntaxKind::Whitespace,
+        ch if ch.is_alphanumeric() || ch == '_' => SyntaxKind::Word,
+        _ => SyntaxKind::Punct,
+    }
+}
+
+///
+/// Split source not covered by any node into
+/// runs of whitespace, words, and punctuation.
+///
+fn push_tokens(children: &mut Vec<SyntaxElement>, range: Range<usize>, src: &str, file: FileId) {
+    let text = &src[range.clone()];
+    let mut start = 0;
+
+    while let Some(first) = text[start..].chars().next() {
+        let kind = token_kind(first);
+        let len = text[start..]
+            .char_indices()
+            .find(|(_, ch)| token_kind(*ch) != kind)
+            .map(|(i, _)| i)
+            .unwrap_or(text.len() - start);
+
+        let token = (range.start + start)..(range.start + start + len);
+        children.push(SyntaxElement::Token(SyntaxToken {
+            kind,
+            text: src[token.clone()].to_string(),
+            span: Span::new(file, token),
+        }));
+
+        start += len;
+    }
+}
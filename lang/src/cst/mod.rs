@@ -0,0 +1,250 @@
+//!
+//! ## Concrete Syntax Tree
+//!
+//! A lossless view of a parsed file: unlike the AST, it keeps all
+//! whitespace, punctuation, and keywords, so it can reproduce the original
+//! source byte-for-byte:
+//!
+//! ```ignore
+//! let tree = cst::build(&file, &source);
+//! assert_eq!(tree.to_string(), source.contents());
+//! ```
+//!
+//! Every AST node becomes a [SyntaxNode] covering its span; any source
+//! between (or inside) nodes, which the AST drops, is kept as [SyntaxToken]s:
+//! * [SyntaxKind::Whitespace] -- trivia;
+//! * [SyntaxKind::Word] -- keywords, such as `if`, `in`, and `by`;
+//! * [SyntaxKind::Punct] -- delimiters, and separators, like `{#`, `=`, `,`.
+//!
+//! Parts of the tree written in the external language (the module, and
+//! expressions in blocks) are kept as text, as they're parsed by other tools.
+//!
+
+mod builder;
+
+use std::fmt::Display;
+
+use crate::{
+    syntax::{external::External, file::File},
+    utils::{SourceFile, Span},
+};
+
+pub use builder::Builder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxKind {
+    File,
+    Module,
+
+    // PonyX
+    Text,
+    Entity,
+    Comment,
+    ExternalExpr,
+    LetDeclaration,
+    ConstDeclaration,
+    DebugStatement,
+    IfBlock,
+    IfBranch,
+    ElseIfBranch,
+    ElseBranch,
+    ForBlock,
+    AwaitBlock,
+    KeyBlock,
+    MatchBlock,
+    WhenArm,
+    DefaultArm,
+    Tag,
+    TagName,
+    Attribute,
+    Directive,
+
+    // Pony expressions
+    Identifier,
+    Literal,
+    Array,
+    Map,
+    Field,
+    Tuple,
+    Parenthesised,
+    MemberAccess,
+    Indexing,
+    UnaryOperation,
+    BinaryOperation,
+    Operator,
+    Application,
+    Placeholder,
+
+    // Tokens
+    Whitespace,
+    Word,
+    Punct,
+}
+
+impl SyntaxKind {
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Self::Whitespace)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken {
+    pub kind: SyntaxKind,
+    pub span: Span,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    pub fn span(&self) -> &Span {
+        match self {
+            Self::Node(node) => &node.span,
+            Self::Token(token) => &token.span,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode {
+    pub kind: SyntaxKind,
+    pub span: Span,
+    pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    ///
+    /// All tokens of this node, in source order.
+    ///
+    pub fn tokens(&self) -> impl Iterator<Item = &SyntaxToken> + '_ {
+        let mut stack = vec![self.children.iter()];
+
+        std::iter::from_fn(move || loop {
+            let element = match stack.last_mut()?.next() {
+                Some(element) => element,
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+
+            match element {
+                SyntaxElement::Token(token) => return Some(token),
+                SyntaxElement::Node(node) => stack.push(node.children.iter()),
+            }
+        })
+    }
+
+    ///
+    /// All nodes in this tree (including itself), in pre-order.
+    ///
+    pub fn descendants(&self) -> impl Iterator<Item = &SyntaxNode> + '_ {
+        let mut stack = vec![self];
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev().filter_map(|child| match child {
+                SyntaxElement::Node(node) => Some(node),
+                SyntaxElement::Token(_) => None,
+            }));
+            Some(node)
+        })
+    }
+
+    ///
+    /// Trivia before the first non-trivia token of this node.
+    ///
+    pub fn leading_trivia(&self) -> impl Iterator<Item = &SyntaxToken> + '_ {
+        self.tokens().take_while(|token| token.kind.is_trivia())
+    }
+
+    ///
+    /// Trivia after the last non-trivia token of this node.
+    ///
+    pub fn trailing_trivia(&self) -> Vec<&SyntaxToken> {
+        let mut trivia = self
+            .tokens()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .take_while(|token| token.kind.is_trivia())
+            .collect::<Vec<_>>();
+        trivia.reverse();
+        trivia
+    }
+}
+
+impl Display for SyntaxNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.tokens()
+            .try_for_each(|token| write!(f, "{}", token.text))
+    }
+}
+
+///
+/// Build the concrete syntax tree of `file`, parsed from `source`.
+///
+pub fn build<Ext: External>(file: &File<Ext>, source: &SourceFile) -> SyntaxNode {
+    let mut builder = Builder::new();
+    builder.file(file);
+    builder.finish(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Parseable, SourceFile},
+    };
+
+    use super::{build, SyntaxKind};
+
+    #[test]
+    fn lossless() {
+        let src = r#"
+        // Comments in the module are kept.
+        let i = 0;
+        ---
+        <Button   primary
+            on:click={() => i++}  border=(5pt)
+        >
+            {#if i > 0 }
+                Clicked {i} times!  <!-- a comment -->
+            {:else}
+                Click me &amp; see!
+            {/if}
+        </Button>
+        "#;
+
+        let (source, _) = SourceFile::test_file(src);
+        let file = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_result()
+            .unwrap();
+
+        let tree = build(&file, &source);
+        assert_eq!(tree.to_string(), src);
+        assert_eq!(tree.kind, SyntaxKind::File);
+
+        let kinds = tree.descendants().map(|node| node.kind).collect::<Vec<_>>();
+        for kind in [
+            SyntaxKind::Module,
+            SyntaxKind::Tag,
+            SyntaxKind::TagName,
+            SyntaxKind::Directive,
+            SyntaxKind::Parenthesised,
+            SyntaxKind::IfBlock,
+            SyntaxKind::ElseBranch,
+            SyntaxKind::Comment,
+            SyntaxKind::Entity,
+        ] {
+            assert!(kinds.contains(&kind), "missing {kind:?}");
+        }
+    }
+}
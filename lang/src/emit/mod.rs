@@ -0,0 +1,169 @@
+//!
+//! ## Emitters
+//!
+//! [Diagnostic]s in machine-readable formats, for CI systems,
+//! and code-review bots:
+//! * JSON lines, one object per diagnostic ([json]);
+//! * [SARIF 2.1](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html),
+//!   one log of every file checked ([sarif]).
+//!
+//! Each line of JSON is:
+//! ```json
+//! {
+//!     "path": "src/Button.pony",
+//!     "code": "X201",
+//!     "severity": "warning",
+//!     "message": "Shadowed identifier",
+//!     "span": { "range": [10, 14], "start": { "line": 2, "column": 5 }, "end": { ... } },
+//!     "labels": [{ "span": { ... }, "message": "...", "primary": true }],
+//!     "notes": ["..."],
+//!     "help": ["..."],
+//!     "fixes": [{ "span": { ... }, "replacement": "...", "message": "..." }]
+//! }
+//! ```
+//! with byte offsets, and 1-based lines, and columns (counted in characters),
+//! as in `avpony parse --json`.
+//!
+
+pub mod sarif;
+
+use serde_json::{json, Value};
+
+use crate::utils::{diagnostic::Diagnostic, Encoding, LineCol, SourceFile, Span};
+
+///
+/// Line, and column (both from 1) of byte `offset` in `source`.
+///
+fn position(source: &SourceFile, offset: usize) -> Value {
+    let LineCol { line, col } = source.lines().line_col(offset, Encoding::Char);
+
+    json!({ "line": line + 1, "column": col + 1 })
+}
+
+fn span(source: &SourceFile, span: Span) -> Value {
+    let range = span.range();
+
+    json!({
+        "range": [range.start, range.end],
+        "start": position(source, range.start),
+        "end": position(source, range.end),
+    })
+}
+
+///
+/// `diagnostic` of `source` as a single JSON object (see [the module](self)),
+/// to be written on its own line.
+///
+pub fn json(diagnostic: &Diagnostic, source: &SourceFile) -> Value {
+    let diagnostic = diagnostic.clone().plain();
+
+    let labels = diagnostic
+        .labels
+        .iter()
+        .enumerate()
+        .map(|(i, label)| {
+            json!({
+                "span": span(source, label.span),
+                "message": label.message,
+                "primary": i == 0,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let fixes = diagnostic
+        .fixes
+        .iter()
+        .map(|fix| {
+            json!({
+                "span": span(source, fix.span),
+                "replacement": fix.replacement,
+                "message": fix.message,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "path": source.path().as_ref(),
+        "code": diagnostic.code,
+        "severity": diagnostic.severity.as_str(),
+        "message": diagnostic.message,
+        "span": span(source, diagnostic.span),
+        "labels": labels,
+        "notes": diagnostic.notes,
+        "help": diagnostic.help,
+        "fixes": fixes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::span::Span as _;
+    use serde_json::json;
+
+    use crate::utils::{
+        diagnostic::{Diagnostic, Fix, Label, Severity},
+        SourceFile, Span,
+    };
+
+    use super::json;
+
+    #[test]
+    fn json_lines() {
+        let (source, _) = SourceFile::test_file("let a = 1;\n---\n<Row>{ä}</Row>\n");
+        let at = |range| Span::new(source.id(), range);
+
+        let diagnostic = Diagnostic::new(Severity::Warning, at(21..23))
+            .with_code("X200")
+            .with_message("Unresolved identifier")
+            .with_label(Label::new(at(21..23)).with_message("`ä` isn't in scope."))
+            .with_label(Label::new(at(15..20)))
+            .with_help("Declare it first.")
+            .with_fix(Fix::new(at(21..23), "a", "Use `a`."));
+
+        assert_eq!(
+            json(&diagnostic, &source),
+            json!({
+                "path": "TEST",
+                "code": "X200",
+                "severity": "warning",
+                "message": "Unresolved identifier",
+                "span": {
+                    "range": [21, 23],
+                    "start": { "line": 3, "column": 7 },
+                    "end": { "line": 3, "column": 8 },
+                },
+                "labels": [
+                    {
+                        "span": {
+                            "range": [21, 23],
+                            "start": { "line": 3, "column": 7 },
+                            "end": { "line": 3, "column": 8 },
+                        },
+                        "message": "`ä` isn't in scope.",
+                        "primary": true,
+                    },
+                    {
+                        "span": {
+                            "range": [15, 20],
+                            "start": { "line": 3, "column": 1 },
+                            "end": { "line": 3, "column": 6 },
+                        },
+                        "message": null,
+                        "primary": false,
+                    },
+                ],
+                "notes": [],
+                "help": ["Declare it first."],
+                "fixes": [{
+                    "span": {
+                        "range": [21, 23],
+                        "start": { "line": 3, "column": 7 },
+                        "end": { "line": 3, "column": 8 },
+                    },
+                    "replacement": "a",
+                    "message": "Use `a`.",
+                }],
+            })
+        );
+    }
+}
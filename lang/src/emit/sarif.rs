@@ -0,0 +1,368 @@
+//!
+//! ## SARIF
+//!
+//! A [SARIF 2.1](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log
+//! of the diagnostics of every file checked, in one run of `avpony`,
+//! with the [lint rules](crate::lint::Rule) it checked for.
+//!
+//! Rules are described at their default levels,
+//! and any levels a project configures otherwise
+//! are the run's `ruleConfigurationOverrides`.
+//!
+//! Every diagnostic is a result, at its primary label (or span),
+//! with its other labels as related locations,
+//! and its [fixes](crate::utils::diagnostic::Fix) as SARIF fixes.
+//!
+
+use serde_json::{json, Value};
+
+use crate::{
+    lint::{Level, Rule},
+    utils::{
+        diagnostic::{Diagnostic, Severity},
+        Encoding, SourceFile, Span,
+    },
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const VERSION: &str = "2.1.0";
+
+///
+/// A SARIF log, built up a file at a time.
+///
+pub struct Log {
+    rules: Vec<Rule>,
+    levels: Vec<Level>,
+    results: Vec<Value>,
+}
+
+impl Log {
+    ///
+    /// A log of `rules`, at their default levels.
+    ///
+    pub fn new(rules: &[Rule]) -> Self {
+        Self {
+            rules: rules.to_vec(),
+            levels: rules.iter().map(|rule| rule.level).collect(),
+            results: Vec::new(),
+        }
+    }
+
+    ///
+    /// Report the levels of `configured` rules
+    /// which differ from their defaults as overrides.
+    ///
+    pub fn with_levels(mut self, configured: &[Rule]) -> Self {
+        for (rule, level) in self.rules.iter().zip(&mut self.levels) {
+            if let Some(configured) = configured.iter().find(|c| c.code == rule.code) {
+                *level = configured.level;
+            }
+        }
+
+        self
+    }
+
+    ///
+    /// Add `diagnostic` of `source` to the log.
+    ///
+    pub fn add(&mut self, diagnostic: &Diagnostic, source: &SourceFile) {
+        let diagnostic = diagnostic.clone().plain();
+        let primary = diagnostic.primary();
+
+        let text = std::iter::once(diagnostic.message.as_str())
+            .chain(primary.and_then(|label| label.message.as_deref()))
+            .chain(diagnostic.notes.iter().map(String::as_str))
+            .chain(diagnostic.help.iter().map(String::as_str))
+            .filter(|text| !text.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let related = diagnostic
+            .labels
+            .iter()
+            .skip(1)
+            .enumerate()
+            .map(|(id, label)| {
+                let mut location = location(source, label.span);
+                location["id"] = json!(id);
+                if let Some(message) = &label.message {
+                    location["message"] = json!({ "text": message });
+                }
+
+                location
+            })
+            .collect::<Vec<_>>();
+
+        let fixes = diagnostic
+            .fixes
+            .iter()
+            .map(|fix| {
+                json!({
+                    "description": { "text": fix.message },
+                    "artifactChanges": [{
+                        "artifactLocation": { "uri": uri(source) },
+                        "replacements": [{
+                            "deletedRegion": region(source, fix.span),
+                            "insertedContent": { "text": fix.replacement },
+                        }],
+                    }],
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let level = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Advice => "note",
+        };
+        let span = primary.map_or(diagnostic.span, |label| label.span);
+
+        let mut result = json!({
+            "level": level,
+            "message": { "text": text },
+            "locations": [location(source, span)],
+        });
+
+        if let Some(code) = &diagnostic.code {
+            result["ruleId"] = json!(code);
+            if let Some(index) = self
+                .rules
+                .iter()
+                .position(|rule| rule.code == code.as_str())
+            {
+                result["ruleIndex"] = json!(index);
+            }
+        }
+        if !related.is_empty() {
+            result["relatedLocations"] = json!(related);
+        }
+        if !fixes.is_empty() {
+            result["fixes"] = json!(fixes);
+        }
+
+        self.results.push(result);
+    }
+
+    ///
+    /// The log, as JSON.
+    ///
+    pub fn finish(self) -> Value {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| {
+                json!({
+                    "id": rule.code,
+                    "name": rule.name,
+                    "defaultConfiguration": { "level": level(rule.level) },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let overrides = self
+            .rules
+            .iter()
+            .zip(&self.levels)
+            .enumerate()
+            .filter(|(_, (rule, configured))| rule.level != **configured)
+            .map(|(index, (rule, configured))| {
+                json!({
+                    "descriptor": { "id": rule.code, "index": index },
+                    "configuration": { "level": level(*configured) },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "$schema": SCHEMA,
+            "version": VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "avpony",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    },
+                },
+                "invocations": [{
+                    "executionSuccessful": true,
+                    "ruleConfigurationOverrides": overrides,
+                }],
+                "columnKind": "unicodeCodePoints",
+                "results": self.results,
+            }],
+        })
+    }
+}
+
+///
+/// The SARIF level of rules at `level`.
+///
+fn level(level: Level) -> &'static str {
+    match level {
+        Level::Allow => "none",
+        Level::Warn => "warning",
+        Level::Deny => "error",
+    }
+}
+
+///
+/// The path of `source`, as a (relative) URI.
+///
+fn uri(source: &SourceFile) -> String {
+    source.path().replace('\\', "/")
+}
+
+///
+/// Lines, and columns (from 1, with the end exclusive) of `span`.
+///
+fn region(source: &SourceFile, span: Span) -> Value {
+    let range = span.range();
+    let lines = source.lines().range(range.clone(), Encoding::Char);
+
+    json!({
+        "startLine": lines.start.line + 1,
+        "startColumn": lines.start.col + 1,
+        "endLine": lines.end.line + 1,
+        "endColumn": lines.end.col + 1,
+        "byteOffset": range.start,
+        "byteLength": range.len(),
+    })
+}
+
+fn location(source: &SourceFile, span: Span) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": uri(source) },
+            "region": region(source, span),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::span::Span as _;
+    use serde_json::json;
+
+    use crate::{
+        lint::{Level, Rule},
+        utils::{
+            diagnostic::{Diagnostic, Fix, Label, Severity},
+            SourceFile, Span,
+        },
+    };
+
+    use super::Log;
+
+    #[test]
+    fn log() {
+        let (source, _) = SourceFile::test_file("---\n<Row>\n    {item}\n</Row>\n");
+        let at = |range| Span::new(source.id(), range);
+
+        let mut log = Log::new(&[
+            Rule::new("X200", "unresolved-identifier", Level::Deny),
+            Rule::new("X201", "shadowed-identifier", Level::Allow),
+        ])
+        .with_levels(&[
+            Rule::new("X200", "unresolved-identifier", Level::Deny),
+            Rule::new("X201", "shadowed-identifier", Level::Warn),
+        ]);
+        log.add(
+            &Diagnostic::new(Severity::Error, at(15..19))
+                .with_code("X200")
+                .with_message("Unresolved identifier")
+                .with_label(Label::new(at(15..19)).with_message("`item` isn't in scope."))
+                .with_label(Label::new(at(4..9)).with_message("In this tag."))
+                .with_fix(Fix::new(at(14..20), "", "Remove it.")),
+            &source,
+        );
+        log.add(
+            &Diagnostic::new(Severity::Warning, at(4..9)).with_code("S000"),
+            &source,
+        );
+
+        let log = log.finish();
+        assert_eq!(log["version"], "2.1.0");
+
+        let run = &log["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([
+                {
+                    "id": "X200",
+                    "name": "unresolved-identifier",
+                    "defaultConfiguration": { "level": "error" },
+                },
+                {
+                    "id": "X201",
+                    "name": "shadowed-identifier",
+                    "defaultConfiguration": { "level": "none" },
+                },
+            ])
+        );
+        assert_eq!(
+            run["invocations"][0]["ruleConfigurationOverrides"],
+            json!([{
+                "descriptor": { "id": "X201", "index": 1 },
+                "configuration": { "level": "warning" },
+            }])
+        );
+
+        let region = |start_line, start_column, end_line, end_column, offset, length| {
+            json!({
+                "startLine": start_line,
+                "startColumn": start_column,
+                "endLine": end_line,
+                "endColumn": end_column,
+                "byteOffset": offset,
+                "byteLength": length,
+            })
+        };
+        assert_eq!(
+            run["results"],
+            json!([
+                {
+                    "ruleId": "X200",
+                    "ruleIndex": 0,
+                    "level": "error",
+                    "message": { "text": "Unresolved identifier\n`item` isn't in scope." },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "TEST" },
+                            "region": region(3, 6, 3, 10, 15, 4),
+                        },
+                    }],
+                    "relatedLocations": [{
+                        "id": 0,
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "TEST" },
+                            "region": region(2, 1, 2, 6, 4, 5),
+                        },
+                        "message": { "text": "In this tag." },
+                    }],
+                    "fixes": [{
+                        "description": { "text": "Remove it." },
+                        "artifactChanges": [{
+                            "artifactLocation": { "uri": "TEST" },
+                            "replacements": [{
+                                "deletedRegion": region(3, 5, 3, 11, 14, 6),
+                                "insertedContent": { "text": "" },
+                            }],
+                        }],
+                    }],
+                },
+                {
+                    "ruleId": "S000",
+                    "level": "warning",
+                    "message": { "text": "" },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "TEST" },
+                            "region": region(2, 1, 2, 6, 4, 5),
+                        },
+                    }],
+                },
+            ])
+        );
+    }
+}
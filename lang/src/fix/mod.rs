@@ -0,0 +1,163 @@
+//!
+//! ## Fixes
+//!
+//! Many errors have an obvious repair, which their [Diagnostic](crate::utils::diagnostic::Diagnostic)
+//! suggests as a [Fix] (a replacement of a span of the file), like renaming the closing tag
+//! of an unclosed tag, or removing a stray `_` from a number.
+//!
+//! [apply] makes these edits to a file's source, skipping any that overlap an earlier one,
+//! and [fix] keeps applying the first fix of every error, parsing the file again after
+//! each round to check it's better off (with fewer errors) than before.
+//!
+
+use chumsky::Parser;
+
+use crate::{
+    lint::Linter,
+    syntax::{external::External, file::File},
+    utils::{diagnostic::Fix, Error, Parseable, SourceFile},
+};
+
+///
+/// How many rounds of fixes [fix] tries, since fixing
+/// some errors can uncover others.
+///
+const ROUNDS: usize = 8;
+
+///
+/// Apply `fixes` to `src`, returning the new source, and the fixes applied.
+///
+/// Fixes that overlap one before them (in the order of their spans) are skipped.
+///
+pub fn apply(src: &str, fixes: impl IntoIterator<Item = Fix>) -> (String, Vec<Fix>) {
+    let mut fixes = fixes.into_iter().collect::<Vec<_>>();
+    fixes.sort_by_key(|fix| (fix.span.range().start, fix.span.range().end));
+
+    let mut out = String::with_capacity(src.len());
+    let mut applied = Vec::new();
+    let mut end = 0;
+
+    for fix in fixes {
+        let range = fix.span.range();
+        if range.start < end || range.end > src.len() {
+            continue;
+        }
+
+        out.push_str(&src[end..range.start]);
+        out.push_str(&fix.replacement);
+        end = range.end;
+        applied.push(fix);
+    }
+
+    out.push_str(&src[end..]);
+    (out, applied)
+}
+
+///
+/// A file, after [fix]ing it.
+///
+pub struct Fixed {
+    pub source: SourceFile,
+
+    ///
+    /// Every fix applied, with the spans of the source
+    /// of the round that applied it.
+    ///
+    pub applied: Vec<Fix>,
+
+    ///
+    /// The errors left, linted by the [Linter].
+    ///
+    pub errors: Vec<Error>,
+}
+
+///
+/// Parse, and lint `source`, returning its errors.
+///
+fn errors<Ext: External + 'static>(source: &SourceFile, linter: &Linter<Ext>) -> Vec<Error> {
+    let (file, errors) = File::<Ext>::parser()
+        .parse(source.stream())
+        .into_output_errors();
+
+    linter.check(file.as_ref(), errors)
+}
+
+///
+/// Apply the first fix of every error in `source` (that `linter` doesn't allow),
+/// until none are left, or a round of them doesn't leave fewer errors.
+///
+pub fn fix<Ext: External + 'static>(source: SourceFile, linter: &Linter<Ext>) -> Fixed {
+    let mut fixed = Fixed {
+        errors: errors(&source, linter),
+        source,
+        applied: Vec::new(),
+    };
+
+    for _ in 0..ROUNDS {
+        let fixes = fixed
+            .errors
+            .iter()
+            .filter_map(|error| linter.diagnostic(error.clone()).fixes.into_iter().next());
+
+        let (contents, applied) = apply(fixed.source.contents(), fixes);
+        if applied.is_empty() {
+            break;
+        }
+
+        let source = SourceFile::new(fixed.source.path(), contents);
+        let errors = errors(&source, linter);
+        if errors.len() >= fixed.errors.len() {
+            break;
+        }
+
+        fixed.source = source;
+        fixed.errors = errors;
+        fixed.applied.extend(applied);
+    }
+
+    fixed
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::span::Span as _;
+
+    use crate::{
+        lint::Linter,
+        syntax::external::typescript::TypeScript,
+        utils::{diagnostic::Fix, SourceFile, Span},
+    };
+
+    use super::{apply, fix};
+
+    #[test]
+    fn overlapping() {
+        let (source, _) = SourceFile::test_file("<Row>1__000</Row>");
+        let at = |range| Span::new(source.id(), range);
+
+        let (out, applied) = apply(
+            source.contents(),
+            [
+                Fix::new(at(13..16), "Column", "Rename it."),
+                Fix::new(at(7..9), "", "Remove the underscores."),
+                Fix::new(at(7..8), "", "Remove the excess underscores."),
+                Fix::new(at(1..4), "Column", "Rename it."),
+            ],
+        );
+
+        assert_eq!(out, "<Column>1_000</Column>");
+        assert_eq!(applied.len(), 3);
+    }
+
+    #[test]
+    fn fix_file() {
+        let src = "let a = 1;\n---\n<Row>{a}</Rwo>\n";
+        let (source, _) = SourceFile::test_file(src);
+
+        let fixed = fix(source, &Linter::<TypeScript>::new());
+
+        assert_eq!(fixed.source.contents(), "let a = 1;\n---\n<Row>{a}</Row>\n");
+        assert!(fixed.errors.is_empty());
+        assert_eq!(fixed.applied.len(), 1);
+    }
+}
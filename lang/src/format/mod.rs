@@ -0,0 +1,306 @@
+//!
+//! ## Formatting
+//!
+//! An opinionated pretty-printer for AvPony files.
+//!
+//! The module (above the `---` fence) is printed by its external language
+//! (see [External::format_module]); PonyX below it is laid out as follows:
+//! * Children are indented one level inside their tag, or block,
+//!   with block leaves (`{:else}`, `{:then v}`, ...) level with the opening.
+//! * Runs of text, entities, and `{expr}`s share a line, with whitespace collapsed;
+//!   everything else (tags, blocks, comments, and statements) gets its own line.
+//! * Tags with only text inside stay on one line, if it fits.
+//! * Attributes are put one per line, if the opening tag doesn't fit.
+//! * Arrays, maps, and tuples are put on one line (`[1, 2]`, `(.a = 1)`),
+//!   or one item per line, with trailing commas, if they don't fit.
+//! * Single blank lines between nodes are kept.
+//!
+//! External expressions, and comments are kept as written.
+//!
+//! Formatting is idempotent: formatting formatted source changes nothing.
+//!
+
+mod printer;
+pub mod typescript;
+
+use std::ops::Range;
+
+use chumsky::span::Span as _;
+
+use crate::{
+    ponyx::Node,
+    syntax::{external::External, file::File},
+    utils::{SourceFile, Spanned},
+};
+
+use printer::Printer;
+
+///
+/// Separates the module, and PonyX sections of a file.
+///
+const FENCE: &str = "---";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    ///
+    /// Spaces per level of indentation.
+    ///
+    pub indent: usize,
+
+    ///
+    /// Width lines are kept within, where possible.
+    ///
+    pub max_width: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            max_width: 100,
+        }
+    }
+}
+
+///
+/// A replacement of `range` (byte offsets) in a file with `text`.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+///
+/// Format `file`, parsed (without errors) from `source`.
+///
+pub fn format<Ext: External>(file: &File<Ext>, source: &SourceFile, config: &Config) -> String {
+    let src = source.contents();
+    let start = file.span().start();
+    let fence = start + src[start..].find(FENCE).unwrap_or(0);
+
+    let module = &src[start..fence];
+    let module = Ext::format_module(module).unwrap_or_else(|| module.to_string());
+    let module = module.trim_start_matches(['\r', '\n']).trim_end();
+
+    let mut out = String::new();
+    if !module.is_empty() {
+        out.push_str(module);
+        out.push('\n');
+    }
+    out.push_str(FENCE);
+    out.push('\n');
+
+    let mut printer = Printer::new(src, config);
+    printer.node(&file.pony, 0);
+    out.push_str(&printer.finish());
+    out
+}
+
+///
+/// Format the smallest tag, block, or other node around `range`
+/// (for editors' "format selection"), or the whole file if there's none.
+///
+/// Returns `None` if it's already formatted.
+///
+pub fn format_range<Ext: External>(
+    file: &File<Ext>,
+    source: &SourceFile,
+    range: Range<usize>,
+    config: &Config,
+) -> Option<TextEdit> {
+    let src = source.contents();
+    let printer = Printer::new(src, config);
+
+    // The node must start its line, so we know its indentation.
+    let node = enclosing(std::slice::from_ref(&file.pony), &range, &printer).filter(|node| {
+        let start = printer.range(*node).start;
+        let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+        src[line_start..start].chars().all(char::is_whitespace)
+    });
+
+    let edit = match node {
+        Some(node) => {
+            let range = printer.range(node);
+            let line_start = src[..range.start].rfind('\n').map_or(0, |i| i + 1);
+            let indent = (range.start - line_start) / config.indent.max(1);
+
+            let mut printer = Printer::new(src, config);
+            printer.node(node, indent);
+            let text = printer.finish().trim().to_string();
+
+            TextEdit { range, text }
+        }
+        None => TextEdit {
+            range: 0..src.len(),
+            text: format(file, source, config),
+        },
+    };
+
+    (src[edit.range.clone()] != edit.text).then_some(edit)
+}
+
+///
+/// The innermost node (that isn't text) in `nodes` around `range`.
+///
+fn enclosing<'a, Ext: External>(
+    nodes: &'a [Node<Ext>],
+    range: &Range<usize>,
+    printer: &Printer,
+) -> Option<&'a Node<Ext>> {
+    nodes
+        .iter()
+        .filter(|node| !matches!(node, Node::Text(_) | Node::Entity(_) | Node::Mustache(_)))
+        .find(|node| {
+            let span = printer.range(*node);
+            span.start <= range.start && range.end <= span.end
+        })
+        .map(|node| {
+            node.children()
+                .into_iter()
+                .find_map(|children| enclosing(children, range, printer))
+                .unwrap_or(node)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Parseable, SourceFile},
+    };
+
+    use super::{format, format_range, Config};
+
+    fn format_str(src: &str, config: &Config) -> String {
+        let (source, _) = SourceFile::test_file(src);
+        let file = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_result()
+            .unwrap();
+
+        format(&file, &source, config)
+    }
+
+    #[test]
+    fn format_file() {
+        let src = r#"let count = 0;
+---
+<Button   primary
+    on:click={() => count++}  style=( .width = 5pt,.height=10pt )>
+{#if count > 0}
+Clicked {count} times!  <!-- a comment -->
+{:else}
+      Click me &amp; see!
+
+
+  <Icon name="star"/>
+{/if}
+</Button>
+"#;
+
+        let expected = r#"let count = 0;
+---
+<Button primary on:click={() => count++} style=(.width = 5pt, .height = 10pt)>
+    {#if count > 0}
+        Clicked {count} times!
+        <!-- a comment -->
+    {:else}
+        Click me &amp; see!
+
+        <Icon name="star" />
+    {/if}
+</Button>
+"#;
+
+        let config = Config::default();
+        let formatted = format_str(src, &config);
+        assert_eq!(formatted, expected);
+        assert_eq!(format_str(&formatted, &config), formatted);
+    }
+
+    #[test]
+    fn match_block() {
+        let src = "let v = 1;\n---\n<Column>\n{#match v}\n{:when 1}\nOne\n      {:default n}\n  {n}\n{/match}\n</Column>\n";
+        let expected = "let v = 1;\n---\n<Column>\n    {#match v}\n    {:when 1}\n        One\n    {:default n}\n        {n}\n    {/match}\n</Column>\n";
+
+        let config = Config::default();
+        let formatted = format_str(src, &config);
+        assert_eq!(formatted, expected);
+        assert_eq!(format_str(&formatted, &config), formatted);
+    }
+
+    #[test]
+    fn await_block() {
+        let src = "let p = 1;\n---\n<Column>\n{#await p}\nLoading\n  {:then value}\n{value}\n{:catch   err}\n  Failed\n{/await}\n</Column>\n";
+        let expected = "let p = 1;\n---\n<Column>\n    {#await p}\n        Loading\n    {:then value}\n        {value}\n    {:catch err}\n        Failed\n    {/await}\n</Column>\n";
+
+        let config = Config::default();
+        let formatted = format_str(src, &config);
+        assert_eq!(formatted, expected);
+        assert_eq!(format_str(&formatted, &config), formatted);
+
+        // A missing binding leaves no space in the leaf.
+        let (source, _) = SourceFile::test_file(
+            "let p = 1;\n---\n{#await p}\nLoading\n{:then }\nDone\n{/await}\n",
+        );
+        let (file, errors) = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+        assert!(!errors.is_empty());
+
+        let formatted = format(&file.unwrap(), &source, &config);
+        assert!(formatted.contains("\n{:then}\n"), "{formatted}");
+    }
+
+    #[test]
+    fn wrap_attributes() {
+        let src = r#"const x = 1;
+---
+<Tag alpha=1 beta=[1,2,3] gamma="long string value" delta=(.a = 1, .b = 2, .c = 3) />
+"#;
+
+        let expected = r#"const x = 1;
+---
+<Tag
+    alpha=1
+    beta=[1, 2, 3]
+    gamma="long string value"
+    delta=(
+        .a = 1,
+        .b = 2,
+        .c = 3,
+    )
+/>
+"#;
+
+        let config = Config {
+            max_width: 24,
+            ..Default::default()
+        };
+        let formatted = format_str(src, &config);
+        assert_eq!(formatted, expected);
+        assert_eq!(format_str(&formatted, &config), formatted);
+    }
+
+    #[test]
+    fn format_selection() {
+        let src = "let a = 1;\n---\n<Column>\n    <Row   gap=2><Text>hi</Text></Row>\n</Column>\n";
+        let (source, _) = SourceFile::test_file(src);
+        let file = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_result()
+            .unwrap();
+
+        let gap = src.find("gap").unwrap();
+        let edit = format_range(&file, &source, gap..gap + 3, &Config::default()).unwrap();
+
+        assert_eq!(&src[edit.range], "<Row   gap=2><Text>hi</Text></Row>");
+        assert_eq!(
+            edit.text,
+            "<Row gap=2>\n        <Text>hi</Text>\n    </Row>"
+        );
+    }
+}
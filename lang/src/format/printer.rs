@@ -0,0 +1,523 @@
+//!
+//! Pretty-printer for PonyX nodes, and Pony expressions.
+//!
+//! External expressions (and patterns, declarations) are printed as written;
+//! only the PonyX around them is re-laid out.
+//!
+
+use std::ops::Range;
+
+use chumsky::span::Span as _;
+
+use crate::{
+    ponyx::{
+        blocks::{
+            await_block::Branch as AwaitBranch, if_block::Branch as IfBranch, match_block::Arm,
+            LogicBlock,
+        },
+        tag::{
+            attribute::{Attribute, AttributeKey},
+            Tag,
+        },
+        Node,
+    },
+    syntax::{external::External, map::Field, Expr},
+    utils::{
+        placeholder::{HasPlaceholder, Maybe},
+        Spanned,
+    },
+};
+
+use super::Config;
+
+pub struct Printer<'a> {
+    src: &'a str,
+    config: &'a Config,
+    out: String,
+}
+
+///
+/// Whether `node` can be laid out in a line of text.
+///
+fn is_inline<Ext: External>(node: &Node<Ext>) -> bool {
+    matches!(node, Node::Text(_) | Node::Entity(_) | Node::Mustache(_))
+}
+
+///
+/// Byte offset of the `}` closing the brace opened at `open`.
+///
+/// String literals are skipped over, as are character literals
+/// (but not Rust lifetimes, which never close on the same line).
+///
+fn closing_brace(src: &str, open: usize) -> usize {
+    let mut depth = 0usize;
+    let mut chars = src[open..].char_indices().map(|(i, ch)| (open + i, ch));
+
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            '"' | '`' | '\'' => {
+                let line_end = src[i + 1..].find('\n').map_or(src.len(), |end| i + 1 + end);
+                let is_literal = ch != '\'' || src[i + 1..line_end].contains('\'');
+                if !is_literal {
+                    continue;
+                }
+
+                while let Some((_, next)) = chars.next() {
+                    match next {
+                        '\\' => {
+                            chars.next();
+                        }
+                        next if next == ch => break,
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    src.len()
+}
+
+impl<'a> Printer<'a> {
+    pub fn new(src: &'a str, config: &'a Config) -> Self {
+        Self {
+            src,
+            config,
+            out: String::new(),
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn slice(&self, spanned: &impl Spanned) -> &'a str {
+        &self.src[self.range(spanned)]
+    }
+
+    ///
+    /// The span of `spanned`, without any surrounding whitespace.
+    ///
+    pub fn range(&self, spanned: &impl Spanned) -> Range<usize> {
+        let span = spanned.span();
+        let text = &self.src[span.start()..span.end()];
+        let start = span.start() + (text.len() - text.trim_start().len());
+        start..start.max(span.start() + text.trim_end().len())
+    }
+
+    fn fits(&self, indent: usize, line: &str) -> bool {
+        indent * self.config.indent + line.len() <= self.config.max_width && !line.contains('\n')
+    }
+
+    fn line(&mut self, indent: usize, text: &str) {
+        self.out.push_str(&" ".repeat(indent * self.config.indent));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    ///
+    /// The block header (e.g. `{#if cond}`) at, or after `from`,
+    /// trimmed of whitespace inside its braces.
+    ///
+    fn header(&self, from: usize) -> String {
+        let open = from + self.src[from..].find('{').unwrap_or(0);
+        let close = closing_brace(self.src, open);
+        format!("{{{}}}", self.src[open + 1..close].trim())
+    }
+
+    ///
+    /// Lay out a run of inline nodes as a line, collapsing whitespace.
+    ///
+    fn inline<Ext: External>(&self, nodes: &[Node<Ext>]) -> String {
+        let mut line = String::new();
+        let mut space = false;
+
+        for node in nodes {
+            match node {
+                Node::Text(text) => {
+                    space |= text.text.starts_with(char::is_whitespace);
+                    for word in text.text.split_whitespace() {
+                        if space && !line.is_empty() {
+                            line.push(' ');
+                        }
+                        line.push_str(word);
+                        space = true;
+                    }
+                    space = text.text.ends_with(char::is_whitespace);
+                }
+                node => {
+                    if space && !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(self.slice(node));
+                    space = false;
+                }
+            }
+        }
+
+        line
+    }
+
+    ///
+    /// Print `nodes` at `indent`, one per line (with runs of inline
+    /// nodes sharing a line), keeping single blank lines between them.
+    ///
+    pub fn nodes<Ext: External>(&mut self, nodes: &[Node<Ext>], indent: usize) {
+        let mut last_end = None;
+        let mut rest = nodes;
+
+        while let Some(first) = rest.first() {
+            let len = match is_inline(first) {
+                true => rest.iter().take_while(|node| is_inline(*node)).count(),
+                false => 1,
+            };
+            let (group, after) = rest.split_at(len);
+            rest = after;
+
+            let start = self.range(&group[0]).start;
+            let end = self.range(&group[len - 1]).end;
+            let line = match is_inline(first) {
+                true => self.inline(group),
+                false => String::new(),
+            };
+
+            if is_inline(first) && line.is_empty() {
+                continue;
+            }
+
+            let blank = last_end.is_some_and(|last_end: usize| {
+                self.src[last_end.min(start)..start].matches('\n').count() > 1
+            });
+            if blank {
+                self.out.push('\n');
+            }
+            last_end = Some(end);
+
+            match is_inline(first) {
+                true => self.line(indent, &line),
+                false => self.node(first, indent),
+            }
+        }
+    }
+
+    pub fn node<Ext: External>(&mut self, node: &Node<Ext>, indent: usize) {
+        match node {
+            Node::Text(_) | Node::Entity(_) | Node::Mustache(_) => {
+                let line = self.inline(std::slice::from_ref(node));
+                self.line(indent, &line);
+            }
+            Node::Comment(comment) => self.line(indent, self.slice(comment)),
+            Node::Statement(statement) => {
+                let line = format!("{{@{}}}", self.slice(statement));
+                self.line(indent, &line);
+            }
+            Node::Block(block) => self.block(block, indent),
+            Node::Tag(tag) => self.tag(tag, indent),
+        }
+    }
+
+    fn block<Ext: External>(&mut self, block: &LogicBlock<Ext>, indent: usize) {
+        match block {
+            LogicBlock::If(block) => {
+                for branch in &block.branches {
+                    let contents = match branch {
+                        IfBranch::If(branch) => {
+                            self.line(indent, &self.header(branch.span().start()));
+                            &branch.contents
+                        }
+                        IfBranch::ElseIf(branch) => {
+                            self.line(indent, &self.header(branch.span().start()));
+                            &branch.contents
+                        }
+                        IfBranch::Else(branch) => {
+                            self.line(indent, "{:else}");
+                            &branch.contents
+                        }
+                    };
+                    self.nodes(contents, indent + 1);
+                }
+                self.line(indent, "{/if}");
+            }
+            LogicBlock::For(block) => {
+                self.line(indent, &self.header(block.span().start()));
+                self.nodes(&block.children, indent + 1);
+                if let Some(empty) = &block.empty_case {
+                    self.line(indent, "{:else}");
+                    self.nodes(empty, indent + 1);
+                }
+                self.line(indent, "{/for}");
+            }
+            LogicBlock::Await(block) => {
+                // The first branch is part of the opening: `{#await p then v}`.
+                self.line(indent, &self.header(block.span().start()));
+                for (i, (branch, nodes)) in block.branches.iter().enumerate() {
+                    let leaf = match branch {
+                        AwaitBranch::Pending => None,
+                        AwaitBranch::Success(ident) => Some(("then", ident)),
+                        AwaitBranch::Failure(ident) => Some(("catch", ident)),
+                    };
+
+                    if let Some((keyword, ident)) = leaf.filter(|_| i > 0) {
+                        let leaf = match ident {
+                            Maybe::Present(ident) => format!("{{:{keyword} {}}}", ident.value),
+                            Maybe::Placeholder(_) => format!("{{:{keyword}}}"),
+                        };
+                        self.line(indent, &leaf);
+                    }
+                    self.nodes(nodes, indent + 1);
+                }
+                self.line(indent, "{/await}");
+            }
+            LogicBlock::Key(block) => {
+                self.line(indent, &self.header(block.span().start()));
+                self.nodes(&block.children, indent + 1);
+                self.line(indent, "{/key}");
+            }
+            LogicBlock::Match(block) => {
+                self.line(indent, &self.header(block.span().start()));
+                for arm in &block.arms {
+                    let children = match arm {
+                        Arm::When(arm) => {
+                            self.line(indent, &self.header(arm.span().start()));
+                            &arm.children
+                        }
+                        Arm::Default(arm) => {
+                            let leaf = match &arm.binding {
+                                Some(binding) => format!("{{:default {}}}", binding.value),
+                                None => "{:default}".to_string(),
+                            };
+                            self.line(indent, &leaf);
+                            &arm.children
+                        }
+                    };
+                    self.nodes(children, indent + 1);
+                }
+                self.line(indent, "{/match}");
+            }
+        }
+    }
+
+    fn tag<Ext: External>(&mut self, tag: &Tag<Ext>, indent: usize) {
+        let (name, attributes, children) = match tag {
+            Tag::SelfClosing(tag) => (&tag.name, &tag.attributes, None),
+            Tag::Enclosing(tag) => (&tag.name, &tag.attributes, Some(&tag.children)),
+        };
+        let name = name.to_string();
+
+        let mut opening = format!("<{name}");
+        for attribute in attributes {
+            let column = indent * self.config.indent + opening.len() + 1;
+            opening.push(' ');
+            opening.push_str(&self.attribute(attribute, indent, column));
+        }
+
+        let Some(children) = children else {
+            let line = format!("{opening} />");
+            match self.fits(indent, &line) {
+                true => self.line(indent, &line),
+                false => self.attributes(&name, attributes, "/>", indent),
+            }
+            return;
+        };
+
+        // Short tags with only text inside stay on one line.
+        if children.iter().all(is_inline) {
+            let line = format!("{opening}>{}</{name}>", self.inline(children));
+            if self.fits(indent, &line) {
+                self.line(indent, &line);
+                return;
+            }
+        }
+
+        let line = format!("{opening}>");
+        match self.fits(indent, &line) {
+            true => self.line(indent, &line),
+            false => self.attributes(&name, attributes, ">", indent),
+        }
+        self.nodes(children, indent + 1);
+        self.line(indent, &format!("</{name}>"));
+    }
+
+    ///
+    /// An opening tag, with one attribute per line.
+    ///
+    fn attributes<Ext: External>(
+        &mut self,
+        name: &str,
+        attributes: &[Attribute<Ext>],
+        end: &str,
+        indent: usize,
+    ) {
+        self.line(indent, &format!("<{name}"));
+        for attribute in attributes {
+            let column = (indent + 1) * self.config.indent;
+            let attribute = self.attribute(attribute, indent + 1, column);
+            self.line(indent + 1, &attribute);
+        }
+        self.line(indent, end);
+    }
+
+    fn attribute<Ext: External>(
+        &self,
+        attribute: &Attribute<Ext>,
+        indent: usize,
+        column: usize,
+    ) -> String {
+        match attribute {
+            Attribute::Key(key) => self.attribute_key(key),
+            Attribute::KeyValue(assignment) => {
+                let key = self.attribute_key(&assignment.key);
+                let value = self.maybe(&assignment.value, |printer, value| {
+                    printer.expr(&Expr::from(value.clone()), indent, column + key.len() + 1)
+                });
+                format!("{key}={value}")
+            }
+        }
+    }
+
+    fn attribute_key(&self, key: &AttributeKey) -> String {
+        match key {
+            AttributeKey::Named(name) => name.value.clone(),
+            AttributeKey::Directive(directive) => {
+                let director = self.maybe(&directive.director, |_, ident| ident.value.clone());
+                format!("{}:{director}", directive.base.value)
+            }
+        }
+    }
+
+    fn maybe<T: HasPlaceholder>(
+        &self,
+        maybe: &Maybe<T>,
+        f: impl FnOnce(&Self, &T) -> String,
+    ) -> String {
+        match maybe {
+            Maybe::Present(t) => f(self, t),
+            Maybe::Placeholder(placeholder) => self.slice(placeholder).to_string(),
+        }
+    }
+
+    ///
+    /// Print a Pony expression starting at `column`,
+    /// breaking arrays, maps, and tuples over lines if they're too wide.
+    ///
+    pub fn expr<Ext: External>(&self, expr: &Expr<Ext>, indent: usize, column: usize) -> String {
+        match expr {
+            Expr::Literal(literal) => self.slice(literal).to_string(),
+            Expr::Identifier(ident) => ident.value.clone(),
+            Expr::External(expr) => self.slice(expr).to_string(),
+            Expr::UnaryOp(op) => {
+                let operator = self.slice(&op.operator);
+                let operand = self.expr(&op.operand, indent, column + operator.len());
+                format!("{operator}{operand}")
+            }
+            Expr::Array(array) => {
+                let items = array.contents.iter().collect::<Vec<_>>();
+                self.list(("[", "]"), &items, false, indent, column, Self::expr)
+            }
+            Expr::Map(map) => {
+                let fields = map.fields.iter().collect::<Vec<_>>();
+                self.list(("(", ")"), &fields, false, indent, column, Self::field)
+            }
+            Expr::Tuple(tuple) => {
+                let items = tuple.items.iter().collect::<Vec<_>>();
+                self.list(("(", ")"), &items, true, indent, column, Self::expr)
+            }
+            Expr::Parenthesised(paren) => {
+                format!("({})", self.expr(&paren.inner, indent, column + 1))
+            }
+            Expr::MemberAccess(access) => {
+                let receiver = self.expr(&access.receiver, indent, column);
+                let member = self.maybe(&access.member, |_, ident| ident.value.clone());
+                format!("{receiver}.{member}")
+            }
+            Expr::Indexing(indexing) => {
+                let receiver = self.expr(&indexing.receiver, indent, column);
+                let index = self.maybe(&*indexing.index, |printer, index| {
+                    printer.expr(index, indent, column + receiver.len() + 1)
+                });
+                format!("{receiver}[{index}]")
+            }
+            Expr::BinaryOp(op) => {
+                let lhs = self.expr(&op.operands.0, indent, column);
+                let operator = self.slice(&op.operator);
+                let rhs = self.maybe(&*op.operands.1, |printer, rhs| {
+                    printer.expr(rhs, indent, column + lhs.len() + operator.len() + 2)
+                });
+                format!("{lhs} {operator} {rhs}")
+            }
+            Expr::Application(application) => {
+                let function = self.expr(&application.function, indent, column);
+                let argument =
+                    self.expr(&application.argument, indent, column + function.len() + 1);
+                format!("{function} {argument}")
+            }
+        }
+    }
+
+    fn field<Ext: External>(&self, field: &Field<Ext>, indent: usize, column: usize) -> String {
+        match field {
+            Field::Key(field) => {
+                let ident = self.maybe(&field.ident, |_, ident| ident.value.clone());
+                format!(".{ident}")
+            }
+            Field::KeyValue(field) => {
+                let key = self.maybe(&field.key, |_, ident| ident.value.clone());
+                let value = self.maybe(&*field.value, |printer, value| {
+                    printer.expr(value, indent, column + key.len() + 4)
+                });
+                format!(".{key} = {value}")
+            }
+        }
+    }
+
+    ///
+    /// A delimited, comma-separated list: on one line if it fits,
+    /// otherwise with one item per line (and a trailing comma).
+    ///
+    /// Single-item tuples always keep their trailing comma: `(a,)`.
+    ///
+    fn list<T>(
+        &self,
+        (open, close): (&str, &str),
+        items: &[&T],
+        tuple: bool,
+        indent: usize,
+        column: usize,
+        item: impl Fn(&Self, &T, usize, usize) -> String,
+    ) -> String {
+        let mut flat = open.to_string();
+        for (i, t) in items.iter().enumerate() {
+            if i > 0 {
+                flat.push_str(", ");
+            }
+            let column = column + flat.len();
+            flat.push_str(&item(self, *t, indent, column));
+        }
+        if tuple && items.len() == 1 {
+            flat.push(',');
+        }
+        flat.push_str(close);
+
+        if column + flat.len() <= self.config.max_width && !flat.contains('\n') {
+            return flat;
+        }
+
+        let padding = " ".repeat((indent + 1) * self.config.indent);
+        let mut broken = format!("{open}\n");
+        for t in items {
+            let t = item(self, *t, indent + 1, padding.len());
+            broken.push_str(&format!("{padding}{t},\n"));
+        }
+        broken.push_str(&" ".repeat(indent * self.config.indent));
+        broken.push_str(close);
+        broken
+    }
+}
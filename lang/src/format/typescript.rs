@@ -0,0 +1,46 @@
+//!
+//! ## TypeScript Modules
+//!
+//! Modules are re-parsed with their comments, and printed by swc's codegen.
+//!
+
+use swc_common::{comments::SingleThreadedComments, sync::Lrc, FileName, SourceMap};
+use swc_ecma_codegen::{text_writer::JsWriter, Config, Emitter};
+use swc_ecma_parser::{parse_file_as_module, Syntax};
+
+///
+/// Pretty-print the TypeScript module `src`,
+/// or `None` if it doesn't parse cleanly.
+///
+pub fn module(src: &str) -> Option<String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(FileName::Anon, src.to_string());
+
+    let comments = SingleThreadedComments::default();
+    let mut recovered = Vec::new();
+    let module = parse_file_as_module(
+        &fm,
+        Syntax::Typescript(Default::default()),
+        Default::default(),
+        Some(&comments),
+        &mut recovered,
+    )
+    .ok()
+    .filter(|_| recovered.is_empty())?;
+
+    let mut buf = Vec::new();
+    {
+        let mut emitter = Emitter {
+            cfg: Config::default(),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+
+        emitter
+            .emit_module(&module)
+            .expect("writing to a `Vec` cannot fail");
+    }
+
+    String::from_utf8(buf).ok()
+}
@@ -0,0 +1,364 @@
+//!
+//! ## Incremental Parsing
+//!
+//! Editors change a file a few characters at a time, so parsing all of it
+//! (including the module, with the external language's parser) on every
+//! change is wasteful. A [Parse] can instead be [edited](Parse::edit),
+//! reusing what the edit didn't touch:
+//! * Edits after the fence `---` reuse the module;
+//! * Edits inside a single child of a tag also reuse every other node,
+//!   moving the spans of those after the edit.
+//!
+//! Anything else (or any edit that would change the shape of the tree
+//! around it) falls back to parsing the whole file again, so an edited
+//! parse is always the same as parsing the new source from scratch.
+//!
+
+use std::ops::Range;
+
+use chumsky::{
+    primitive::{any, just},
+    span::Span as _,
+    Parser,
+};
+
+use crate::{
+    format::TextEdit,
+    ponyx::{tag::Tag, Node},
+    syntax::{
+        external::{External, ExternalNode},
+        file::File,
+    },
+    utils::{Error, Parseable, PonyParser, SourceFile, Span, Spanned},
+    visit::{VisitMut, Visitable},
+};
+
+const FENCE: &str = "---";
+
+///
+/// What was parsed again after an edit.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reparsed {
+    ///
+    /// The whole file.
+    ///
+    File,
+
+    ///
+    /// The PonyX nodes, reusing the module.
+    ///
+    Pony,
+
+    ///
+    /// A single node, reusing the module, and every other node.
+    ///
+    Node,
+}
+
+///
+/// A file, parsed from `source`.
+///
+pub struct Parse<Ext: External> {
+    pub source: SourceFile,
+    pub file: Option<File<Ext>>,
+    pub errors: Vec<Error>,
+
+    ///
+    /// What was parsed again to get here, after the last edit.
+    ///
+    pub reparsed: Reparsed,
+}
+
+impl<Ext: External + 'static> Parse<Ext> {
+    ///
+    /// Parse all of `source`.
+    ///
+    pub fn new(source: SourceFile) -> Self {
+        let (file, errors) = File::<Ext>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+
+        Self {
+            source,
+            file,
+            errors,
+            reparsed: Reparsed::File,
+        }
+    }
+
+    ///
+    /// Apply `edit` to the source, and parse it again,
+    /// reusing as much of this parse as possible.
+    ///
+    pub fn edit(&self, edit: &TextEdit) -> Self {
+        let mut text = self.source.contents().to_string();
+        text.replace_range(edit.range.clone(), &edit.text);
+        let source = SourceFile::new(self.source.path(), text);
+
+        let Some(file) = &self.file else {
+            return Self::new(source);
+        };
+
+        let fence = file.fence.start();
+
+        // Errors in the module are kept; any others might be
+        // gone after the edit, or have moved.
+        if edit.range.start < fence + FENCE.len()
+            || self.errors.iter().any(|error| error.span().end() > fence)
+        {
+            return Self::new(source);
+        }
+
+        let delta = edit.text.len() as isize - edit.range.len() as isize;
+
+        if let Some(file) = reparse_node(file, &source, &edit.range, delta) {
+            return Self {
+                source,
+                file: Some(file),
+                errors: self.errors.clone(),
+                reparsed: Reparsed::Node,
+            };
+        }
+
+        // Errors are only reported by the parser that found them,
+        // so leave reporting new ones to a full parse.
+        let Some((span, pony)) = reparse_pony::<Ext>(&source, fence) else {
+            return Self::new(source);
+        };
+
+        Self {
+            file: Some(File::from_parts(
+                span,
+                file.module.clone(),
+                file.fence,
+                pony,
+            )),
+            source,
+            errors: self.errors.clone(),
+            reparsed: Reparsed::Pony,
+        }
+    }
+}
+
+///
+/// Skip the first `chars` of the source, before `parser`.
+///
+fn skip<'src, O>(chars: usize, parser: impl PonyParser<'src, O>) -> impl PonyParser<'src, O> {
+    any().repeated().exactly(chars).ignore_then(parser)
+}
+
+///
+/// Parse the PonyX section of `source`, after the fence at byte `fence`,
+/// as [File::parser] would, with the span of the whole file.
+///
+/// Returns `None` if there were any errors.
+///
+fn reparse_pony<Ext: External + 'static>(
+    source: &SourceFile,
+    fence: usize,
+) -> Option<(Span, Node<Ext>)> {
+    let chars = source.contents()[..fence].chars().count();
+
+    let (pony, errors) = skip(
+        chars,
+        just(FENCE)
+            .padded()
+            .ignore_then(Node::<Ext>::parser().padded()),
+    )
+    .map_with(|pony, ctx| (ctx.span(), pony))
+    .parse(source.stream())
+    .into_output_errors();
+
+    pony.filter(|_| errors.is_empty())
+}
+
+///
+/// Parse again only the child of a tag that `edit` is inside of,
+/// reusing the rest of `file`.
+///
+/// Returns `None` if there's no such child, or if it doesn't parse
+/// (without errors) into a node ending where the old one did.
+///
+fn reparse_node<Ext: External + 'static>(
+    file: &File<Ext>,
+    source: &SourceFile,
+    edit: &Range<usize>,
+    delta: isize,
+) -> Option<File<Ext>> {
+    let path = path_to(&file.pony, edit)?;
+    let old = child(&file.pony, &path)?.span();
+
+    let start = old.start();
+    let end = (old.end() as isize + delta) as usize;
+    let chars = source.contents()[..start].chars().count();
+
+    let (node, errors) = skip(chars, Node::<Ext>::parser())
+        .then_ignore(any().repeated())
+        .parse(source.stream())
+        .into_output_errors();
+    let node = node.filter(|node| errors.is_empty() && node.span().end() == end)?;
+
+    let mut file = file.clone();
+    file.visit_mut(&mut Shift {
+        from: edit.end,
+        delta,
+    });
+    *child_mut(&mut file.pony, &path)? = node;
+
+    Some(file)
+}
+
+///
+/// Indices of the innermost child (of tags, from `node`),
+/// that's around `edit`, but doesn't start, or end with it.
+///
+fn path_to<Ext: External>(node: &Node<Ext>, edit: &Range<usize>) -> Option<Vec<usize>> {
+    let Node::Tag(Tag::Enclosing(tag)) = node else {
+        return None;
+    };
+
+    tag.children.iter().enumerate().find_map(|(i, child)| {
+        let span = child.span();
+        if !(span.start() < edit.start && edit.end < span.end()) {
+            return None;
+        }
+
+        let mut path = vec![i];
+        path.extend(path_to(child, edit).unwrap_or_default());
+        Some(path)
+    })
+}
+
+fn child<'a, Ext: External>(node: &'a Node<Ext>, path: &[usize]) -> Option<&'a Node<Ext>> {
+    match (node, path) {
+        (_, []) => Some(node),
+        (Node::Tag(Tag::Enclosing(tag)), [i, rest @ ..]) => child(tag.children.get(*i)?, rest),
+        _ => None,
+    }
+}
+
+fn child_mut<'a, Ext: External>(
+    node: &'a mut Node<Ext>,
+    path: &[usize],
+) -> Option<&'a mut Node<Ext>> {
+    match (node, path) {
+        (node, []) => Some(node),
+        (Node::Tag(Tag::Enclosing(tag)), [i, rest @ ..]) => {
+            child_mut(tag.children.get_mut(*i)?, rest)
+        }
+        _ => None,
+    }
+}
+
+///
+/// Moves every position after an edit, see [Span::shift].
+///
+struct Shift {
+    from: usize,
+    delta: isize,
+}
+
+impl<Ext: External> VisitMut<Ext> for Shift {
+    fn visit_span_mut(&mut self, span: &mut Span) {
+        span.shift(self.from, self.delta);
+    }
+
+    fn visit_external_mut(&mut self, node: &mut dyn ExternalNode) {
+        node.shift(self.from, self.delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        format::TextEdit,
+        syntax::external::{rust::Rust, typescript::TypeScript, External},
+        utils::SourceFile,
+    };
+
+    use super::{Parse, Reparsed};
+
+    const TS: &str = "let a = 1;\n---\n<Column gap=2>\n    <Text>Hello, {a}!</Text>\n    {#if a > 0}\n        <Text>{a + 1}</Text>\n    {/if}\n    <Row>é&amp;{a}</Row>\n</Column>\n";
+
+    ///
+    /// Replace the first `old` in the source with `new`,
+    /// checking the edited parse is the same as parsing from scratch.
+    ///
+    fn edit<Ext: External + 'static>(parse: &Parse<Ext>, old: &str, new: &str) -> Parse<Ext> {
+        let start = parse.source.contents().find(old).unwrap();
+        let edited = parse.edit(&TextEdit {
+            range: start..start + old.len(),
+            text: new.to_string(),
+        });
+
+        let full = Parse::<Ext>::new(SourceFile::new("TEST", edited.source.contents()));
+        assert_eq!(
+            edited.file, full.file,
+            "after replacing `{old}` with `{new}`"
+        );
+        assert_eq!(
+            edited.errors, full.errors,
+            "after replacing `{old}` with `{new}`"
+        );
+
+        edited
+    }
+
+    #[test]
+    fn reuses_nodes() {
+        let parse = Parse::<TypeScript>::new(SourceFile::new("TEST", TS));
+        assert!(parse.errors.is_empty());
+
+        let parse = edit(&parse, "Hello", "Goodbye");
+        assert_eq!(parse.reparsed, Reparsed::Node);
+
+        let parse = edit(&parse, "a + 1", "a * 2 + 100");
+        assert_eq!(parse.reparsed, Reparsed::Node);
+
+        let parse = edit(&parse, "é", "");
+        assert_eq!(parse.reparsed, Reparsed::Node);
+
+        let parse = edit(&parse, "Goodbye", "<Image /> Goodbye");
+        assert_eq!(parse.reparsed, Reparsed::Node);
+
+        let parse = edit(&parse, "a > 0", "a");
+        assert_eq!(parse.reparsed, Reparsed::Node);
+
+        // Splits `<Row>` into two, so its parent changes too.
+        let parse = edit(&parse, "&amp;", "</Row><Row>");
+        assert_eq!(parse.reparsed, Reparsed::Pony);
+    }
+
+    #[test]
+    fn reuses_module() {
+        let parse = Parse::<TypeScript>::new(SourceFile::new("TEST", TS));
+
+        let parse = edit(&parse, "gap=2", "gap=4");
+        assert_eq!(parse.reparsed, Reparsed::Pony);
+
+        let parse = edit(&parse, "</Column>", "</Colum>");
+        assert_eq!(parse.reparsed, Reparsed::File);
+        assert!(!parse.errors.is_empty());
+
+        let parse = edit(&parse, "</Colum>", "</Column>");
+        assert_eq!(parse.reparsed, Reparsed::File);
+        assert!(parse.errors.is_empty());
+
+        let parse = edit(&parse, "let a = 1", "let a = 2");
+        assert_eq!(parse.reparsed, Reparsed::File);
+    }
+
+    #[test]
+    fn rust() {
+        let src = "let a = 1;\n---\n<Column>\n    <Text>{a}</Text>\n    {@let b = a + 1}\n    <Text>{b}</Text>\n</Column>\n";
+        let parse = Parse::<Rust>::new(SourceFile::new("TEST", src));
+        assert!(parse.errors.is_empty());
+
+        let parse = edit(&parse, "{a}", "{a + 1}");
+        assert_eq!(parse.reparsed, Reparsed::Node);
+
+        let parse = edit(&parse, "a + 1}\n", "a + 2}\n");
+        assert_eq!(parse.reparsed, Reparsed::Node);
+    }
+}
@@ -4,19 +4,38 @@
 //! bool_lit = `true` | `false`
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::choice, Parser};
 
 use crate::utils::ParseableCloned;
 
 use super::keyword;
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
 pub enum BooleanLit {
     False(keyword::False),
     True(keyword::True),
 }
 
+impl BooleanLit {
+    pub fn value(&self) -> bool {
+        matches!(self, Self::True(_))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BooleanLit {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut lit = serializer.serialize_struct("BooleanLit", 3)?;
+        lit.serialize_field("kind", "BooleanLit")?;
+        lit.serialize_field("span", &crate::utils::Spanned::span(self))?;
+        lit.serialize_field("value", &self.value())?;
+        lit.end()
+    }
+}
+
 impl ParseableCloned for BooleanLit {
     fn parser<'src>() -> impl crate::utils::PonyParser<'src, Self> + Clone {
         choice((
@@ -17,7 +17,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{any, choice, just},
     IterParser, Parser,
@@ -63,7 +63,8 @@ impl ParseableCloned for UncheckedIdentifier {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct Identifier {
     span: Span,
     pub value: String,
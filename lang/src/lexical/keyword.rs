@@ -4,6 +4,11 @@
 //! All the keywords in AvPony.
 //!
 
+use crate::{
+    syntax::external::External,
+    visit::{Fold, Visit, VisitMut, Visitable},
+};
+
 pub fn is_keyword(st: &str) -> bool {
     KEYWORDS.iter().copied().any(|kw| st == kw)
 }
@@ -57,3 +62,31 @@ mod kw {
 }
 
 pub use kw::*;
+
+///
+/// Keywords used as nodes (like `true` in [super::boolean::BooleanLit])
+/// are visited by their span.
+///
+macro_rules! visitable {
+    ($($kw:ty),*) => {
+        $(
+            impl<Ext: External> Visitable<Ext> for $kw {
+                fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+                    Visitable::<Ext>::visit(&self.span, visitor);
+                }
+
+                fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+                    Visitable::<Ext>::visit_mut(&mut self.span, visitor);
+                }
+
+                fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+                    Self {
+                        span: Visitable::<Ext>::fold(self.span, folder),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+visitable!(True, False);
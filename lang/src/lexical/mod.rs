@@ -2,7 +2,7 @@
 //! ## Tokens
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::choice, Parser};
 
 use crate::utils::{ParseableCloned, PonyParser};
@@ -18,7 +18,8 @@ pub mod string;
 
 pub use identifier::Identifier;
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 pub enum Literal {
     Number(NumberLit),
     String(StringLit),
@@ -26,7 +26,7 @@
 
 use std::sync::OnceLock;
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{any, just},
     IterParser, Parser,
@@ -44,7 +44,8 @@ use crate::utils::{
 pub type IntType = i32;
 pub type FloatType = f64;
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 pub enum NumberLit {
     Integer(IntegerLit),
     Float(FloatLit),
@@ -57,7 +58,7 @@ impl ParseableCloned for NumberLit {
     fn parser<'src>() -> impl crate::utils::PonyParser<'src, Self> + Clone {
         let divider_train = MULTIPLE_NUMERIC_DIVIDERS.get_or_init(|| Regex::new(r"_(_+)").unwrap());
         let dividers_badly_placed = BADLY_PLACED_NUMERIC_DIVIDERS
-            .get_or_init(|| Regex::new(r"(^_)|(-_)|(_\.)|(\._)|(_$)").unwrap());
+            .get_or_init(|| Regex::new(r"^(_)|-(_)|(_)\.|\.(_)|(_)$").unwrap());
 
         let digits = any().filter(|ch: &char| ch.is_ascii_digit() || ch == &'_');
 
@@ -90,7 +91,9 @@ impl ParseableCloned for NumberLit {
                 }
 
                 if let Some(needle) = dividers_badly_placed.captures(&raw_value) {
-                    let span = span.relative_range(needle.get(0).unwrap().range());
+                    // Only the underscore, without what's beside it.
+                    let underscore = needle.iter().skip(1).flatten().next().unwrap();
+                    let span = span.relative_range(underscore.range());
                     return Err(Error::DivdersBadlyPlaced(DivdersBadlyPlaced::new(span)));
                 }
 
@@ -101,7 +104,7 @@ impl ParseableCloned for NumberLit {
                         .parse()
                         .map(|value| {
                             Self::Float(FloatLit {
-                                span: span.clone(),
+                                span,
                                 value,
                                 raw_value,
                             })
@@ -114,7 +117,7 @@ impl ParseableCloned for NumberLit {
                         .parse()
                         .map(|value| {
                             Self::Integer(IntegerLit {
-                                span: span.clone(),
+                                span,
                                 value,
                                 raw_value,
                             })
@@ -126,14 +129,16 @@ impl ParseableCloned for NumberLit {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct IntegerLit {
     pub span: Span,
     pub value: IntType,
     raw_value: String,
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct FloatLit {
     pub span: Span,
     pub value: FloatType,
@@ -17,7 +17,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{any, choice, just, one_of},
     IterParser, Parser,
@@ -74,7 +74,8 @@ where
         .sum()
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct StringLit {
     span: Span,
     pub value: String,
@@ -16,12 +16,37 @@
 //! `avpony-lang` features the definition of the language's grammar,
 //! a parser made using [chumsky], and syntax errors with [ariadne].
 //!
+//! It also compiles parsed files into modules of their external language
+//! (see [codegen]), and provides a lossless concrete syntax tree, keeping
+//! whitespace, and punctuation (see [cst]), and formats them (see [format]).
+//! Files can be parsed again after an edit, reusing what it didn't touch
+//! (see [incremental]), and checked beyond their syntax (see [semantic]),
+//! with rules each project can configure, or suppress (see [lint]).
+//! Errors are also described as structured diagnostics
+//! (see [utils::diagnostic]), emitted as JSON, or SARIF (see [emit]),
+//! with fixes that can be applied automatically (see [fix]).
+//! Syntax trees can be walked, and rewritten with visitors (see [visit]),
+//! served to editors by a language server (see [lsp]), and, with the `serde`
+//! feature, serialized as JSON (see `serialize`).
+//!
 //! ## Building
 //! We are nightly compiler only, so make sure you have the nightly compiler in
 //! your Rustup toolchain: `rustup install +nighlty`
 //!
 
+pub mod codegen;
+pub mod cst;
+pub mod emit;
+pub mod fix;
+pub mod format;
+pub mod incremental;
 pub mod lexical;
+pub mod lint;
+pub mod lsp;
 pub mod ponyx;
+pub mod semantic;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod syntax;
 pub mod utils;
+pub mod visit;
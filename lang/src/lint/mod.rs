@@ -0,0 +1,626 @@
+//!
+//! ## Lints
+//!
+//! Runs the checks beyond syntax (see [crate::semantic]) as [Lint]s,
+//! each reporting errors for some [Rule]s, with unique codes.
+//!
+//! Every rule has a [Level], which a project can change in its `avpony.json`,
+//! by the rule's code, or name:
+//! ```json
+//! {
+//!     "lints": {
+//!         "X201": "allow",
+//!         "unused-binding": "deny"
+//!     }
+//! }
+//! ```
+//!
+//! Rules can also be suppressed for a single node,
+//! with a comment before it (and only whitespace, or other comments between):
+//! ```avpony
+//! <!-- avpony-ignore X201 unused-binding -->
+//! {#for item in items}
+//!     ...
+//! {/for}
+//! ```
+//! Without any codes, or names, every rule is suppressed.
+//!
+//! Syntax errors aren't rules, so are always denied, and can't be suppressed.
+//!
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use chumsky::span::Span as _;
+use serde_json::Value;
+
+use crate::{
+    ponyx::Node,
+    semantic::{a11y, components, schema::Registry},
+    syntax::{external::External, file::File},
+    utils::{
+        diagnostic::{Diagnostic, Severity},
+        Error, ErrorI, Span, Spanned,
+    },
+};
+
+///
+/// Prefix of comments suppressing rules.
+///
+const IGNORE: &str = "avpony-ignore";
+
+///
+/// How the errors of a rule are reported.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    ///
+    /// Not reported at all.
+    ///
+    Allow,
+
+    ///
+    /// Reported, without making the file invalid.
+    ///
+    Warn,
+
+    ///
+    /// Reported, making the file invalid.
+    ///
+    Deny,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level {
+            "allow" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(format!(
+                "unknown level `{level}`, expected `allow`, `warn`, or `deny`"
+            )),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Allow => write!(f, "allow"),
+            Self::Warn => write!(f, "warn"),
+            Self::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+///
+/// A kind of error reported by a [Lint], e.g. `X201` for shadowed identifiers.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    ///
+    /// The code of its errors (see [ErrorI::code]).
+    ///
+    pub code: &'static str,
+    pub name: &'static str,
+
+    ///
+    /// Its level, unless [configured](Config) otherwise.
+    ///
+    pub level: Level,
+}
+
+impl Rule {
+    pub const fn new(code: &'static str, name: &'static str, level: Level) -> Self {
+        Self { code, name, level }
+    }
+
+    ///
+    /// If this rule is called `rule`, by its code, or name.
+    ///
+    fn is(&self, rule: &str) -> bool {
+        self.code == rule || self.name == rule
+    }
+}
+
+///
+/// A check of parsed files, reporting errors for its [Rule]s.
+///
+pub trait Lint<Ext: External> {
+    fn rules(&self) -> &'static [Rule];
+
+    fn check(&self, file: &File<Ext>) -> Vec<Error>;
+}
+
+///
+/// Rules reported while parsing, which aren't from any [Lint].
+///
+const PARSER: &[Rule] = &[
+    Rule::new("X100", "unreachable-branch", Level::Warn),
+    Rule::new("X102", "missing-default-arm", Level::Warn),
+];
+
+const SCOPE: &[Rule] = &[
+    Rule::new("X200", "unresolved-identifier", Level::Deny),
+    Rule::new("X201", "shadowed-identifier", Level::Warn),
+    Rule::new("X202", "unused-binding", Level::Warn),
+];
+
+const A11Y: &[Rule] = &[
+    Rule::new("X500", "missing-alt", Level::Warn),
+    Rule::new("X501", "unlabelled-interactive", Level::Warn),
+    Rule::new("X502", "duplicate-label", Level::Warn),
+    Rule::new("X503", "empty-button", Level::Warn),
+];
+
+const COMPONENTS: &[Rule] = &[
+    Rule::new("X300", "unknown-component", Level::Deny),
+    Rule::new("X301", "unknown-attribute", Level::Deny),
+    Rule::new("X302", "duplicate-attribute", Level::Deny),
+    Rule::new("X303", "missing-attribute", Level::Deny),
+    Rule::new("X304", "invalid-child", Level::Deny),
+    Rule::new("X305", "unknown-slot", Level::Deny),
+    Rule::new("X400", "type-mismatch", Level::Deny),
+    Rule::new("X401", "unknown-unit", Level::Deny),
+    Rule::new("X402", "unexpected-field", Level::Deny),
+    Rule::new("X403", "missing-field", Level::Deny),
+];
+
+///
+/// The built-in rule called `rule`, by its code, or name, at its default level.
+///
+pub fn builtin(rule: &str) -> Option<&'static Rule> {
+    [PARSER, SCOPE, A11Y, COMPONENTS]
+        .into_iter()
+        .flatten()
+        .find(|builtin| builtin.is(rule))
+}
+
+///
+/// The level `error` is reported at, unless configured otherwise:
+/// its built-in rule's, or if it isn't from a rule (like syntax errors), [Level::Deny].
+///
+pub fn default_level(error: &Error) -> Level {
+    builtin(&error.code()).map_or(Level::Deny, |rule| rule.level)
+}
+
+///
+/// Names which aren't in scope, shadowed, or unused (see [crate::semantic::scope]).
+///
+pub struct Scope;
+
+impl<Ext: External> Lint<Ext> for Scope {
+    fn rules(&self) -> &'static [Rule] {
+        SCOPE
+    }
+
+    fn check(&self, file: &File<Ext>) -> Vec<Error> {
+        Ext::check(file)
+    }
+}
+
+///
+/// Tags which aren't accessible (see [crate::semantic::a11y]).
+///
+pub struct A11y;
+
+impl<Ext: External> Lint<Ext> for A11y {
+    fn rules(&self) -> &'static [Rule] {
+        A11Y
+    }
+
+    fn check(&self, file: &File<Ext>) -> Vec<Error> {
+        a11y::lint(file)
+    }
+}
+
+///
+/// Tags which don't match the schemas of their components,
+/// including the types of their attributes (see [components::validate]).
+///
+pub struct Components(pub Registry);
+
+impl<Ext: External> Lint<Ext> for Components {
+    fn rules(&self) -> &'static [Rule] {
+        COMPONENTS
+    }
+
+    fn check(&self, file: &File<Ext>) -> Vec<Error> {
+        components::validate(file, &self.0)
+    }
+}
+
+///
+/// The levels of rules a project sets, by their code, or name.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    levels: BTreeMap<String, Level>,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn level(mut self, rule: impl Into<String>, level: Level) -> Self {
+        self.levels.insert(rule.into(), level);
+        self
+    }
+
+    ///
+    /// Read a project's configuration from JSON (see [the module](self)).
+    ///
+    pub fn from_json(src: &str) -> Result<Self, String> {
+        let config: Value = serde_json::from_str(src).map_err(|err| err.to_string())?;
+        let Some(lints) = config.get("lints") else {
+            return Ok(Self::new());
+        };
+
+        let lints = lints.as_object().ok_or("expected an object of `lints`")?;
+
+        let mut config = Self::new();
+        for (rule, level) in lints {
+            let level = level
+                .as_str()
+                .ok_or_else(|| format!("expected the level of `{rule}` to be a string"))?
+                .parse::<Level>()
+                .map_err(|err| format!("in `{rule}`: {err}"))?;
+
+            config = config.level(rule, level);
+        }
+
+        Ok(config)
+    }
+}
+
+///
+/// Runs [Lint]s over files, applying the levels of their rules,
+/// and any suppression comments.
+///
+pub struct Linter<Ext: External> {
+    lints: Vec<Box<dyn Lint<Ext>>>,
+    rules: Vec<Rule>,
+    defaults: Vec<Rule>,
+}
+
+impl<Ext: External> Default for Linter<Ext> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Ext: External> Linter<Ext> {
+    ///
+    /// A linter with the rules of the parser, and every [Lint]
+    /// not needing anything else ([Scope], and [A11y]).
+    ///
+    pub fn new() -> Self {
+        let mut linter = Self {
+            lints: Vec::new(),
+            rules: Vec::new(),
+            defaults: Vec::new(),
+        };
+
+        PARSER.iter().for_each(|rule| linter.add_rule(*rule));
+        linter.register(Scope).register(A11y);
+
+        linter
+    }
+
+    ///
+    /// A linter of every rule (validating components against `registry`, if given),
+    /// at the levels of a project's `config`.
+    ///
+    pub fn for_project(registry: Option<&Registry>, config: &Config) -> Result<Self, String> {
+        let mut linter = Self::new();
+        if let Some(registry) = registry {
+            linter.register(Components(registry.clone()));
+        }
+
+        linter.configure(config)?;
+        Ok(linter)
+    }
+
+    ///
+    /// Add a lint, and its rules.
+    ///
+    /// ### Panics
+    /// If any of its rules have the same code, or name as one already registered.
+    ///
+    pub fn register(&mut self, lint: impl Lint<Ext> + 'static) -> &mut Self {
+        lint.rules().iter().for_each(|rule| self.add_rule(*rule));
+        self.lints.push(Box::new(lint));
+        self
+    }
+
+    fn add_rule(&mut self, rule: Rule) {
+        if let Some(existing) = self
+            .rules
+            .iter()
+            .find(|existing| existing.is(rule.code) || existing.is(rule.name))
+        {
+            panic!(
+                "lint rule {} ({}) clashes with {} ({})",
+                rule.code, rule.name, existing.code, existing.name
+            );
+        }
+
+        self.rules.push(rule);
+        self.defaults.push(rule);
+    }
+
+    ///
+    /// Every rule registered, at its configured level.
+    ///
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    ///
+    /// Every rule registered, at the level it was registered with,
+    /// before any [configuration](Self::configure).
+    ///
+    pub fn defaults(&self) -> &[Rule] {
+        &self.defaults
+    }
+
+    ///
+    /// The rule called `rule`, by its code, or name.
+    ///
+    pub fn rule(&self, rule: &str) -> Option<&Rule> {
+        self.rules.iter().find(|existing| existing.is(rule))
+    }
+
+    ///
+    /// Set the levels of rules from a project's `config`,
+    /// failing if it names any rule that isn't registered.
+    ///
+    pub fn configure(&mut self, config: &Config) -> Result<(), String> {
+        for (name, level) in &config.levels {
+            let rule = self
+                .rules
+                .iter_mut()
+                .find(|rule| rule.is(name))
+                .ok_or_else(|| format!("unknown lint rule `{name}`"))?;
+
+            rule.level = *level;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// The level `error` is reported at: its rule's,
+    /// or if its rule isn't registered, its [default level](default_level).
+    ///
+    pub fn level(&self, error: &Error) -> Level {
+        match self.rule(&error.code()) {
+            Some(rule) => rule.level,
+            None => default_level(error),
+        }
+    }
+
+    ///
+    /// Describe `error`, as an error if it's denied,
+    /// or otherwise a warning (see [Linter::level]).
+    ///
+    pub fn diagnostic(&self, error: Error) -> Diagnostic {
+        let severity = match self.level(&error) {
+            Level::Deny => Severity::Error,
+            Level::Warn | Level::Allow => Severity::Warning,
+        };
+
+        Diagnostic {
+            severity,
+            ..error.to_diagnostic()
+        }
+    }
+
+    ///
+    /// Lint `file` (if it was parsed), returning its `errors` from parsing,
+    /// and any from its lints, without those allowed, or suppressed.
+    ///
+    pub fn check(&self, file: Option<&File<Ext>>, mut errors: Vec<Error>) -> Vec<Error> {
+        let Some(file) = file else {
+            errors.retain(|error| self.level(error) != Level::Allow);
+            return errors;
+        };
+
+        errors.extend(self.lints.iter().flat_map(|lint| lint.check(file)));
+
+        let mut suppressions = Vec::new();
+        suppressed(std::slice::from_ref(&file.pony), &mut suppressions);
+
+        errors.retain(|error| {
+            let code = error.code();
+            let Some(rule) = self.rule(&code) else {
+                return true;
+            };
+
+            rule.level != Level::Allow
+                && !suppressions.iter().any(|(span, rules)| {
+                    within(error.span(), *span)
+                        && (rules.is_empty() || rules.iter().any(|name| rule.is(name)))
+                })
+        });
+
+        errors
+    }
+}
+
+///
+/// If `span` lies within `outer`.
+///
+fn within(span: Span, outer: Span) -> bool {
+    outer.start() <= span.start() && span.end() <= outer.end()
+}
+
+///
+/// The rules a comment suppresses, if it's an `avpony-ignore` comment.
+///
+fn ignored<Ext: External>(node: &Node<Ext>) -> Option<Vec<&str>> {
+    let Node::Comment(comment) = node else {
+        return None;
+    };
+
+    let rules = comment.content.trim().strip_prefix(IGNORE)?;
+    if !rules.is_empty() && !rules.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    Some(rules.split_whitespace().collect())
+}
+
+///
+/// Find every node of `nodes` (and their descendants) with rules
+/// suppressed by a comment, with the names of those rules.
+///
+fn suppressed<'a, Ext: External>(nodes: &'a [Node<Ext>], found: &mut Vec<(Span, Vec<&'a str>)>) {
+    for (i, node) in nodes.iter().enumerate() {
+        if let Some(rules) = ignored(node) {
+            let next = nodes[i + 1..].iter().find(|node| match node {
+                Node::Text(text) => !text.text.trim().is_empty(),
+                Node::Comment(_) => false,
+                _ => true,
+            });
+
+            if let Some(next) = next {
+                found.push((next.span(), rules));
+            }
+        }
+
+        for children in node.children() {
+            suppressed(children, found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{ErrorI, Parseable, SourceFile},
+    };
+
+    use super::{A11y, Config, Level, Lint, Linter, Rule};
+
+    ///
+    /// The code of each error of `src`, linted with `config`.
+    ///
+    fn check(src: &str, config: Config) -> Vec<String> {
+        let (source, _) = SourceFile::test_file(src);
+        let (file, errors) = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+
+        let mut linter = Linter::new();
+        linter.configure(&config).unwrap();
+
+        linter
+            .check(file.as_ref(), errors)
+            .iter()
+            .map(ErrorI::code)
+            .collect()
+    }
+
+    const SRC: &str = r#"let items = [1, 2];
+---
+<Column>
+    {#for item in items}
+        <Image />
+    {/for}
+    <Row>
+        <!-- avpony-ignore X202 -->
+        <!-- Unused, for now. -->
+        {#for item in items}
+            <Image />
+        {/for}
+    </Row>
+    <!-- avpony-ignore -->
+    {#for item in items}
+        <Image />
+    {/for}
+</Column>
+"#;
+
+    #[test]
+    fn levels() {
+        assert_eq!(check(SRC, Config::new()), ["X202", "X500", "X500"]);
+        assert_eq!(
+            check(
+                SRC,
+                Config::new()
+                    .level("missing-alt", Level::Allow)
+                    .level("X201", Level::Allow)
+            ),
+            ["X202"]
+        );
+
+        let mut linter = Linter::<TypeScript>::new();
+        linter
+            .configure(&Config::new().level("unused-binding", Level::Deny))
+            .unwrap();
+        assert_eq!(
+            linter.rule("X202").map(|rule| rule.level),
+            Some(Level::Deny)
+        );
+        assert_eq!(
+            linter.rule("X201").map(|rule| rule.level),
+            Some(Level::Warn)
+        );
+        assert_eq!(
+            linter
+                .defaults()
+                .iter()
+                .find(|rule| rule.code == "X202")
+                .map(|rule| rule.level),
+            Some(Level::Warn)
+        );
+
+        assert!(linter
+            .configure(&Config::new().level("X999", Level::Allow))
+            .is_err());
+    }
+
+    #[test]
+    fn config() {
+        assert_eq!(
+            Config::from_json(r#"{ "lints": { "X201": "allow", "unused-binding": "deny" } }"#),
+            Ok(Config::new()
+                .level("X201", Level::Allow)
+                .level("unused-binding", Level::Deny))
+        );
+        assert_eq!(Config::from_json("{}"), Ok(Config::new()));
+        assert!(Config::from_json(r#"{ "lints": ["X201"] }"#).is_err());
+        assert!(Config::from_json(r#"{ "lints": { "X201": "error" } }"#).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "clashes")]
+    fn duplicate_codes() {
+        struct Clashing;
+
+        impl Lint<TypeScript> for Clashing {
+            fn rules(&self) -> &'static [Rule] {
+                &[Rule::new("X201", "shadowing", Level::Warn)]
+            }
+
+            fn check(&self, _: &File<TypeScript>) -> Vec<crate::utils::Error> {
+                Vec::new()
+            }
+        }
+
+        let mut linter = Linter::<TypeScript>::new();
+        linter.register(Clashing);
+    }
+
+    #[test]
+    #[should_panic(expected = "clashes")]
+    fn duplicate_lints() {
+        Linter::<TypeScript>::new().register(A11y);
+    }
+}
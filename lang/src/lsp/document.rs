@@ -0,0 +1,119 @@
+//!
+//! Open documents, and conversion between byte offsets,
+//! and LSP positions (lines, and UTF-16 code units).
+//!
+
+use std::ops::Range;
+
+use chumsky::span::Span as _;
+use serde_json::{json, Value};
+
+use crate::{
+    format::TextEdit,
+    incremental::{Parse, Reparsed},
+    syntax::{external::External, file::File},
+    utils::{Encoding, Error, LineCol, SourceFile, Spanned},
+};
+
+pub struct Document<Ext: External> {
+    pub source: SourceFile,
+    pub file: Option<File<Ext>>,
+    pub errors: Vec<Error>,
+}
+
+impl<Ext: External + 'static> Document<Ext> {
+    pub fn new(uri: &str, text: String) -> Self {
+        Parse::new(SourceFile::new(uri, text)).into()
+    }
+
+    ///
+    /// Apply one of the `contentChanges` of `textDocument/didChange`,
+    /// to a range of the document, or (without one) all of it.
+    ///
+    /// Invalid changes are ignored.
+    ///
+    pub fn change(self, change: &Value) -> Self {
+        let Some(edit) = self.text_edit(change) else {
+            return self;
+        };
+
+        let parse = Parse {
+            source: self.source,
+            file: self.file,
+            errors: self.errors,
+            reparsed: Reparsed::File,
+        };
+
+        parse.edit(&edit).into()
+    }
+
+    fn text_edit(&self, change: &Value) -> Option<TextEdit> {
+        let text = change["text"].as_str()?.to_string();
+        let range = match change.get("range") {
+            Some(range) => self.offset(&range["start"])?..self.offset(&range["end"])?,
+            None => 0..self.source.contents().len(),
+        };
+
+        (range.start <= range.end).then_some(TextEdit { range, text })
+    }
+}
+
+impl<Ext: External> From<Parse<Ext>> for Document<Ext> {
+    fn from(parse: Parse<Ext>) -> Self {
+        Self {
+            source: parse.source,
+            file: parse.file,
+            errors: parse.errors,
+        }
+    }
+}
+
+impl<Ext: External> Document<Ext> {
+    ///
+    /// The (zero-based) line of byte `offset`.
+    ///
+    pub fn line(&self, offset: usize) -> usize {
+        self.source.lines().line(offset)
+    }
+
+    ///
+    /// The LSP position of byte `offset`.
+    ///
+    pub fn position(&self, offset: usize) -> Value {
+        let LineCol { line, col } = self.source.lines().line_col(offset, Encoding::Utf16);
+        json!({ "line": line, "character": col })
+    }
+
+    ///
+    /// The LSP range of byte `range`.
+    ///
+    pub fn range(&self, range: Range<usize>) -> Value {
+        json!({ "start": self.position(range.start), "end": self.position(range.end) })
+    }
+
+    ///
+    /// The byte offset of an LSP `position`,
+    /// clamped to the end of its line.
+    ///
+    pub fn offset(&self, position: &Value) -> Option<usize> {
+        let position = LineCol {
+            line: position["line"].as_u64()? as usize,
+            col: position["character"].as_u64()? as usize,
+        };
+
+        self.source.lines().offset(position, Encoding::Utf16)
+    }
+
+    ///
+    /// The byte range of `node`, without surrounding whitespace
+    /// (which blocks' spans may include).
+    ///
+    pub fn trimmed(&self, node: &impl Spanned) -> Range<usize> {
+        let span = node.span();
+        let text = &self.source.contents()[span.start()..span.end()];
+        let start = span.start() + (text.len() - text.trim_start().len());
+        let end = span.end() - (text.len() - text.trim_end().len());
+
+        start..end.max(start)
+    }
+}
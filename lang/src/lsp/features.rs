@@ -0,0 +1,289 @@
+//!
+//! Language features, computed from a parsed [Document].
+//!
+
+use std::ops::Range;
+
+use chumsky::span::Span as _;
+use serde_json::{json, Value};
+
+use crate::{
+    lint::{Level, Linter},
+    ponyx::{
+        blocks::{
+            await_block::AwaitBlock, for_block::ForBlock, if_block::IfBlock, key_block::KeyBlock,
+            match_block::MatchBlock,
+        },
+        entity::Entity,
+        tag::{EnclosingTag, SelfClosingTag},
+    },
+    syntax::external::External,
+    utils::Spanned,
+    visit::{Visit, Visitable},
+};
+
+use super::document::Document;
+
+///
+/// [SymbolKind](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#symbolKind)s:
+/// tags are fields (as HTML elements usually are), and logic blocks namespaces.
+///
+const TAG_SYMBOL: u32 = 8;
+const BLOCK_SYMBOL: u32 = 3;
+
+pub fn diagnostics<Ext: External>(doc: &Document<Ext>, linter: &Linter<Ext>) -> Value {
+    let diagnostics = linter
+        .check(doc.file.as_ref(), doc.errors.clone())
+        .iter()
+        .map(|error| {
+            let span = error.span();
+            let severity = match linter.level(error) {
+                Level::Deny => 1,
+                _ => 2,
+            };
+            let (code, message) = error.clone().summary();
+
+            json!({
+                "range": doc.range(span.start()..span.end()),
+                "severity": severity,
+                "code": code,
+                "source": "avpony",
+                "message": message,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Value::Array(diagnostics)
+}
+
+///
+/// Tags, and logic blocks, nested as in the document.
+///
+pub fn document_symbols<Ext: External>(doc: &Document<Ext>) -> Value {
+    let mut symbols = Symbols {
+        doc,
+        stack: vec![Vec::new()],
+    };
+
+    if let Some(file) = &doc.file {
+        file.visit(&mut symbols);
+    }
+
+    Value::Array(symbols.stack.pop().unwrap_or_default())
+}
+
+pub fn folding_ranges<Ext: External>(doc: &Document<Ext>) -> Value {
+    let mut folding = Folding {
+        doc,
+        ranges: Vec::new(),
+    };
+
+    if let Some(file) = &doc.file {
+        file.visit(&mut folding);
+    }
+
+    Value::Array(folding.ranges)
+}
+
+///
+/// The character an HTML entity at byte `offset` refers to.
+///
+pub fn hover<Ext: External>(doc: &Document<Ext>, offset: usize) -> Value {
+    let mut hover = Hover {
+        doc,
+        offset,
+        found: None,
+    };
+
+    if let Some(file) = &doc.file {
+        file.visit(&mut hover);
+    }
+
+    hover.found.unwrap_or(Value::Null)
+}
+
+struct Symbols<'a, Ext: External> {
+    doc: &'a Document<Ext>,
+
+    ///
+    /// Symbols of each node being visited, innermost last.
+    ///
+    stack: Vec<Vec<Value>>,
+}
+
+impl<Ext: External> Symbols<'_, Ext> {
+    fn push(
+        &mut self,
+        name: String,
+        kind: u32,
+        range: Range<usize>,
+        selection: Range<usize>,
+        children: impl FnOnce(&mut Self),
+    ) {
+        self.stack.push(Vec::new());
+        children(self);
+        let children = self.stack.pop().unwrap_or_default();
+
+        let symbol = json!({
+            "name": name,
+            "kind": kind,
+            "range": self.doc.range(range),
+            "selectionRange": self.doc.range(selection),
+            "children": children,
+        });
+
+        if let Some(parent) = self.stack.last_mut() {
+            parent.push(symbol);
+        }
+    }
+
+    ///
+    /// `{#if cond}`, for a block.
+    ///
+    fn block(&mut self, block: &impl Spanned, children: impl FnOnce(&mut Self)) {
+        let range = self.doc.trimmed(block);
+        let header = header(self.doc, range.clone());
+        let name = self.doc.source.contents()[header.clone()].to_string();
+
+        self.push(name, BLOCK_SYMBOL, range, header, children);
+    }
+}
+
+///
+/// The opening `{...}` of a block at `range`
+/// (or its first line, if that isn't closed).
+///
+fn header<Ext: External>(doc: &Document<Ext>, range: Range<usize>) -> Range<usize> {
+    let text = &doc.source.contents()[range.clone()];
+    let line = text.find('\n').unwrap_or(text.len());
+    let end = text[..line].find('}').map_or(line, |brace| brace + 1);
+
+    range.start..range.start + end
+}
+
+impl<Ext: External> Visit<Ext> for Symbols<'_, Ext> {
+    fn visit_self_closing_tag(&mut self, tag: &SelfClosingTag<Ext>) {
+        let selection = self.doc.trimmed(&tag.name);
+        let range = self.doc.trimmed(tag);
+        self.push(tag.name.to_string(), TAG_SYMBOL, range, selection, |this| {
+            tag.visit_children(this)
+        });
+    }
+
+    fn visit_enclosing_tag(&mut self, tag: &EnclosingTag<Ext>) {
+        let selection = self.doc.trimmed(&tag.name);
+        let range = self.doc.trimmed(tag);
+        self.push(tag.name.to_string(), TAG_SYMBOL, range, selection, |this| {
+            tag.visit_children(this)
+        });
+    }
+
+    fn visit_if_block(&mut self, block: &IfBlock<Ext>) {
+        self.block(block, |this| block.visit_children(this));
+    }
+
+    fn visit_for_block(&mut self, block: &ForBlock<Ext>) {
+        self.block(block, |this| block.visit_children(this));
+    }
+
+    fn visit_await_block(&mut self, block: &AwaitBlock<Ext>) {
+        self.block(block, |this| block.visit_children(this));
+    }
+
+    fn visit_key_block(&mut self, block: &KeyBlock<Ext>) {
+        self.block(block, |this| block.visit_children(this));
+    }
+
+    fn visit_match_block(&mut self, block: &MatchBlock<Ext>) {
+        self.block(block, |this| block.visit_children(this));
+    }
+}
+
+struct Folding<'a, Ext: External> {
+    doc: &'a Document<Ext>,
+    ranges: Vec<Value>,
+}
+
+impl<Ext: External> Folding<'_, Ext> {
+    ///
+    /// Fold `node` up to (but not including) its closing line, e.g. `</Tag>`.
+    ///
+    fn fold(&mut self, node: &impl Spanned) {
+        let range = self.doc.trimmed(node);
+        let start = self.doc.line(range.start);
+        let end = self.doc.line(range.end);
+
+        if end > start + 1 {
+            self.ranges.push(json!({
+                "startLine": start,
+                "endLine": end - 1,
+            }));
+        }
+    }
+}
+
+impl<Ext: External> Visit<Ext> for Folding<'_, Ext> {
+    fn visit_enclosing_tag(&mut self, tag: &EnclosingTag<Ext>) {
+        self.fold(tag);
+        tag.visit_children(self);
+    }
+
+    fn visit_if_block(&mut self, block: &IfBlock<Ext>) {
+        self.fold(block);
+        block.visit_children(self);
+    }
+
+    fn visit_for_block(&mut self, block: &ForBlock<Ext>) {
+        self.fold(block);
+        block.visit_children(self);
+    }
+
+    fn visit_await_block(&mut self, block: &AwaitBlock<Ext>) {
+        self.fold(block);
+        block.visit_children(self);
+    }
+
+    fn visit_key_block(&mut self, block: &KeyBlock<Ext>) {
+        self.fold(block);
+        block.visit_children(self);
+    }
+
+    fn visit_match_block(&mut self, block: &MatchBlock<Ext>) {
+        self.fold(block);
+        block.visit_children(self);
+    }
+}
+
+struct Hover<'a, Ext: External> {
+    doc: &'a Document<Ext>,
+    offset: usize,
+    found: Option<Value>,
+}
+
+impl<Ext: External> Visit<Ext> for Hover<'_, Ext> {
+    fn visit_entity(&mut self, entity: &Entity) {
+        let range = self.doc.trimmed(entity);
+        if !(range.start <= self.offset && self.offset < range.end) {
+            return;
+        }
+
+        let code_points = entity
+            .value
+            .chars()
+            .map(|ch| format!("U+{:04X}", ch as u32))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        self.found = Some(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!(
+                    "`` {} `` → `` {} ``\n\n{code_points}",
+                    &self.doc.source.contents()[range.clone()],
+                    entity.value,
+                ),
+            },
+            "range": self.doc.range(range),
+        }));
+    }
+}
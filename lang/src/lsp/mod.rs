@@ -0,0 +1,382 @@
+//!
+//! ## Language Server
+//!
+//! A [Language Server Protocol](https://microsoft.github.io/language-server-protocol/)
+//! server for AvPony files, over stdio (see `avpony lsp`).
+//!
+//! Supports:
+//! * Diagnostics -- every [Error](crate::utils::Error) of a document
+//!   (see also [crate::semantic]), published when it's opened, or changed;
+//! * Document symbols -- tags, and logic blocks, nested;
+//! * Folding ranges -- logic blocks, and enclosing tags;
+//! * Hover -- the character an HTML entity refers to.
+//!
+//! Documents are synced incrementally, and reparsed on every change,
+//! reusing what it didn't touch (see [crate::incremental]).
+//!
+
+mod document;
+mod features;
+mod transport;
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    lint::{Config, Linter},
+    semantic::schema::Registry,
+    syntax::external::External,
+};
+
+use document::Document;
+
+///
+/// [Error codes](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#errorCodes)
+/// of responses.
+///
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+///
+/// Serve requests read from `input`, writing responses to `output`,
+/// until the client sends `exit`.
+///
+/// Documents are linted as by `avpony check`: validating components
+/// against `registry` (if given), at the levels of the project's `config`.
+///
+/// Returns if the client exited without shutting the server down first.
+///
+pub fn run<Ext: External + 'static>(
+    mut input: impl BufRead,
+    output: impl Write,
+    registry: Option<&Registry>,
+    config: &Config,
+) -> io::Result<bool> {
+    let linter = Linter::for_project(registry, config)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let mut server = Server::<Ext, _> {
+        output,
+        linter,
+        documents: HashMap::new(),
+        shut_down: false,
+    };
+
+    while let Some(message) = transport::read(&mut input)? {
+        if message["method"] == "exit" {
+            break;
+        }
+
+        server.handle(message)?;
+    }
+
+    Ok(!server.shut_down)
+}
+
+struct Server<Ext: External, W: Write> {
+    output: W,
+    linter: Linter<Ext>,
+    documents: HashMap<String, Document<Ext>>,
+    shut_down: bool,
+}
+
+impl<Ext: External + 'static, W: Write> Server<Ext, W> {
+    fn handle(&mut self, message: Value) -> io::Result<()> {
+        let Some(method) = message["method"].as_str() else {
+            // A response to a request from us; we don't make any.
+            return Ok(());
+        };
+        let params = &message["params"];
+
+        let Some(id) = message.get("id") else {
+            return self.notification(method, params);
+        };
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 2,
+                    "documentSymbolProvider": true,
+                    "foldingRangeProvider": true,
+                    "hoverProvider": true,
+                },
+                "serverInfo": {
+                    "name": "avpony",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/documentSymbol" => {
+                self.with_document(params, |doc| Ok(features::document_symbols(doc)))
+            }
+            "textDocument/foldingRange" => {
+                self.with_document(params, |doc| Ok(features::folding_ranges(doc)))
+            }
+            "textDocument/hover" => self.with_document(params, |doc| {
+                let offset = doc
+                    .offset(&params["position"])
+                    .ok_or((INVALID_PARAMS, "position out of range"))?;
+                Ok(features::hover(doc, offset))
+            }),
+            _ => Err((METHOD_NOT_FOUND, "method not found")),
+        };
+
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        };
+
+        transport::write(&mut self.output, &response)
+    }
+
+    ///
+    /// Run `f` on the document of a request, or return `null` if it isn't open.
+    ///
+    fn with_document(
+        &self,
+        params: &Value,
+        f: impl FnOnce(&Document<Ext>) -> Result<Value, (i32, &'static str)>,
+    ) -> Result<Value, (i32, &'static str)> {
+        params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|uri| self.documents.get(uri))
+            .map_or(Ok(Value::Null), f)
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> io::Result<()> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.open(uri, text.to_string())
+            }
+            "textDocument/didChange" => {
+                let Some(mut doc) = self.documents.remove(uri) else {
+                    return Ok(());
+                };
+
+                let changes = params["contentChanges"].as_array();
+                for change in changes.into_iter().flatten() {
+                    doc = doc.change(change);
+                }
+
+                self.update(uri, doc)
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                self.publish(uri, Value::Array(Vec::new()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: String) -> io::Result<()> {
+        self.update(uri, Document::new(uri, text))
+    }
+
+    fn update(&mut self, uri: &str, doc: Document<Ext>) -> io::Result<()> {
+        let diagnostics = features::diagnostics(&doc, &self.linter);
+        self.documents.insert(uri.to_string(), doc);
+
+        self.publish(uri, diagnostics)
+    }
+
+    fn publish(&mut self, uri: &str, diagnostics: Value) -> io::Result<()> {
+        transport::write(
+            &mut self.output,
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/publishDiagnostics",
+                "params": { "uri": uri, "diagnostics": diagnostics },
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        lint::{Config, Level},
+        syntax::external::typescript::TypeScript,
+    };
+
+    use super::{run, transport};
+
+    const URI: &str = "file:///Test.pony";
+
+    ///
+    /// Run the server on `messages` from a client,
+    /// returning everything it sent back.
+    ///
+    fn session(messages: &[Value]) -> Vec<Value> {
+        session_with(&Config::new(), messages)
+    }
+
+    ///
+    /// [session], with the levels of `config`.
+    ///
+    fn session_with(config: &Config, messages: &[Value]) -> Vec<Value> {
+        let mut input = Vec::new();
+        for message in messages {
+            transport::write(&mut input, message).unwrap();
+        }
+
+        let mut output = Vec::new();
+        let failed = run::<TypeScript>(Cursor::new(input), &mut output, None, config).unwrap();
+        assert!(!failed, "the server should've been shut down");
+
+        let mut output = Cursor::new(output);
+        std::iter::from_fn(|| transport::read(&mut output).unwrap()).collect()
+    }
+
+    fn request(id: u32, method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+    }
+
+    fn notification(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "method": method, "params": params })
+    }
+
+    fn response(responses: &[Value], id: u32) -> &Value {
+        &responses
+            .iter()
+            .find(|message| message["id"] == id)
+            .unwrap()["result"]
+    }
+
+    #[test]
+    fn scripted_session() {
+        let text = "let a = 1;\n---\n<Column>\n    {#if a}\n        <Text>&amp;</Text>\n    {/if}\n</Column>\n";
+        let document = json!({ "textDocument": { "uri": URI } });
+
+        let responses = session(&[
+            request(1, "initialize", json!({ "capabilities": {} })),
+            notification("initialized", json!({})),
+            notification(
+                "textDocument/didOpen",
+                json!({ "textDocument": { "uri": URI, "languageId": "avpony", "version": 1, "text": text } }),
+            ),
+            request(2, "textDocument/documentSymbol", document.clone()),
+            request(3, "textDocument/foldingRange", document.clone()),
+            request(
+                4,
+                "textDocument/hover",
+                json!({ "textDocument": { "uri": URI }, "position": { "line": 4, "character": 16 } }),
+            ),
+            notification(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": URI, "version": 2 },
+                    "contentChanges": [{
+                        "range": { "start": { "line": 4, "character": 14 }, "end": { "line": 4, "character": 19 } },
+                        "text": "&lt;",
+                    }],
+                }),
+            ),
+            request(
+                7,
+                "textDocument/hover",
+                json!({ "textDocument": { "uri": URI }, "position": { "line": 4, "character": 16 } }),
+            ),
+            notification(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": URI, "version": 3 },
+                    "contentChanges": [{ "text": "let a = 1;\n---\n<Column>\n" }],
+                }),
+            ),
+            request(5, "textDocument/unknown", document),
+            request(6, "shutdown", Value::Null),
+            notification("exit", Value::Null),
+        ]);
+
+        let capabilities = &response(&responses, 1)["capabilities"];
+        assert_eq!(capabilities["hoverProvider"], true);
+
+        let diagnostics = responses
+            .iter()
+            .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+            .map(|message| &message["params"]["diagnostics"])
+            .collect::<Vec<_>>();
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0], &json!([]));
+        assert_eq!(diagnostics[1], &json!([]));
+        assert_eq!(diagnostics[2][0]["severity"], 1);
+
+        let column = &response(&responses, 2)[0];
+        assert_eq!(column["name"], "Column");
+        let block = &column["children"][0];
+        assert_eq!(block["name"], "{#if a}");
+        assert_eq!(
+            block["range"],
+            json!({ "start": { "line": 3, "character": 4 }, "end": { "line": 5, "character": 9 } })
+        );
+        assert_eq!(block["children"][0]["name"], "Text");
+
+        assert_eq!(
+            response(&responses, 3),
+            &json!([{ "startLine": 2, "endLine": 5 }, { "startLine": 3, "endLine": 4 }])
+        );
+
+        let hover = response(&responses, 4);
+        assert_eq!(
+            hover["range"],
+            json!({ "start": { "line": 4, "character": 14 }, "end": { "line": 4, "character": 19 } })
+        );
+        assert!(hover["contents"]["value"]
+            .as_str()
+            .unwrap()
+            .contains("U+0026"));
+
+        let hover = response(&responses, 7);
+        assert!(hover["contents"]["value"]
+            .as_str()
+            .unwrap()
+            .contains("U+003C"));
+
+        let unknown = responses.iter().find(|message| message["id"] == 5).unwrap();
+        assert_eq!(unknown["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn configured_levels() {
+        let text = "let a = 1;\n---\n<Text>{b}</Text>\n";
+        let config = Config::new().level("unresolved-identifier", Level::Warn);
+
+        let responses = session_with(
+            &config,
+            &[
+                request(1, "initialize", json!({ "capabilities": {} })),
+                notification(
+                    "textDocument/didOpen",
+                    json!({ "textDocument": { "uri": URI, "languageId": "avpony", "version": 1, "text": text } }),
+                ),
+                request(2, "shutdown", Value::Null),
+                notification("exit", Value::Null),
+            ],
+        );
+
+        let diagnostics = &responses
+            .iter()
+            .find(|message| message["method"] == "textDocument/publishDiagnostics")
+            .unwrap()["params"]["diagnostics"];
+        assert_eq!(diagnostics[0]["code"], "X200");
+        assert_eq!(diagnostics[0]["severity"], 2);
+    }
+}
@@ -1,3 +1,484 @@
-fn main() {
-    println!("Hello world!")
+//!
+//! # `avpony`
+//!
+//! Command-line tool for AvPony files.
+//!
+//! * `avpony check <paths>... [--format human|json|sarif]` -- Check files
+//!   (or directories of files) for errors, including semantic ones, like unresolved
+//!   identifiers, or inaccessible tags (see [avpony_lang::semantic]), printed for
+//!   people, or as JSON lines, or a SARIF log on stdout (see [avpony_lang::emit]).
+//! * `avpony parse <path> [--format json|debug] [--json]` -- Print a file's syntax tree,
+//!   as JSON (see [avpony_lang::serialize]), or Rust's debug format.
+//! * `avpony schema` -- Print the JSON Schema of `avpony parse --json`.
+//! * `avpony fmt <paths>... [--check]` -- Format files in place,
+//!   or with `--check`, list the files that aren't formatted.
+//! * `avpony fix <paths>...` -- Apply the suggested fixes of errors to files in place,
+//!   and print the errors left (see [avpony_lang::fix]).
+//! * `avpony lsp` -- Run a language server over stdio (see [avpony_lang::lsp]).
+//!
+//! With `--components <manifest>`, `check`, `parse`, and `fix` also validate the tags of files
+//! against the components of a JSON manifest (see [avpony_lang::semantic::schema]).
+//!
+//! The levels of lint rules are read from `--config <path>`, or `avpony.json`
+//! in the current directory, if there is one (see [avpony_lang::lint]).
+//!
+//! Exits with a non-zero code if any file has errors (or denied lints; warnings are fine),
+//! or with `--check`, isn't formatted, and `avpony lsp` if the client
+//! exits without shutting it down first.
+//!
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use avpony_lang::{
+    emit::{self, sarif},
+    fix,
+    format::{self, Config},
+    lint::{self, Level, Linter},
+    lsp,
+    semantic::schema::Registry,
+    serialize,
+    syntax::{
+        external::{rust::Rust, typescript::TypeScript, External},
+        file::File,
+    },
+    utils::{Error, Parseable, SourceFile},
+};
+use chumsky::Parser as _;
+use clap::{Parser, Subcommand, ValueEnum};
+
+///
+/// File extensions of AvPony files.
+///
+const EXTENSIONS: &[&str] = &["pony", "avpony"];
+
+///
+/// Configuration of a project, read from the current directory
+/// unless given with `--config`.
+///
+const CONFIG: &str = "avpony.json";
+
+#[derive(Parser)]
+#[command(name = "avpony", version, about = "Tools for AvPony files.")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    ///
+    /// External language of the files.
+    ///
+    #[arg(long, global = true, value_enum, default_value_t = Lang::Ts)]
+    lang: Lang,
+
+    ///
+    /// JSON manifest of components, to validate tags against.
+    ///
+    #[arg(long, global = true, value_name = "MANIFEST")]
+    components: Option<PathBuf>,
+
+    ///
+    /// Project configuration, with the levels of lint rules
+    /// (by default, `avpony.json`, if it exists).
+    ///
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    ///
+    /// Check files, or directories of files for errors.
+    ///
+    Check {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+        format: MessageFormat,
+    },
+
+    ///
+    /// Print the syntax tree of a file.
+    ///
+    Parse {
+        path: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = Format::Debug)]
+        format: Format,
+
+        ///
+        /// Print the syntax tree as JSON (same as `--format json`).
+        ///
+        #[arg(long)]
+        json: bool,
+    },
+
+    ///
+    /// Print the JSON Schema of `avpony parse --json`.
+    ///
+    Schema,
+
+    ///
+    /// Format files, or directories of files in place.
+    ///
+    Fmt {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        ///
+        /// Don't write any files, only fail if any aren't formatted.
+        ///
+        #[arg(long)]
+        check: bool,
+    },
+
+    ///
+    /// Apply the fixes suggested for errors to files,
+    /// or directories of files in place.
+    ///
+    Fix {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+
+    ///
+    /// Run a language server over stdio,
+    /// linting with the same components, and config as `check`.
+    ///
+    Lsp,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Lang {
+    Ts,
+    Rs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Debug,
+}
+
+///
+/// How `avpony check` prints errors.
+///
+#[derive(Clone, Copy, ValueEnum)]
+enum MessageFormat {
+    ///
+    /// Reports for people, on stderr.
+    ///
+    Human,
+
+    ///
+    /// One JSON object per error, on stdout.
+    ///
+    Json,
+
+    ///
+    /// A SARIF 2.1 log of every file, on stdout.
+    ///
+    Sarif,
+}
+
+///
+/// All AvPony files in `paths`, searching directories recursively.
+///
+fn collect_files(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    fn visit(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        if !path.is_dir() {
+            files.push(path.to_path_buf());
+            return Ok(());
+        }
+
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+
+        for entry in entries {
+            let is_pony = entry
+                .extension()
+                .is_some_and(|ext| EXTENSIONS.iter().any(|pony| ext == *pony));
+
+            if entry.is_dir() || is_pony {
+                visit(&entry, files)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        visit(path, &mut files)?;
+    }
+
+    Ok(files)
+}
+
+///
+/// Read the component registry from the manifest at `path`.
+///
+fn read_registry(path: &Path) -> io::Result<Registry> {
+    let manifest = fs::read_to_string(path)?;
+    Registry::from_json(&manifest).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
+
+///
+/// Read the project configuration at `path`,
+/// or `avpony.json`, if it exists (otherwise, the default).
+///
+fn read_config(path: Option<&Path>) -> io::Result<lint::Config> {
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(CONFIG).is_file() => Path::new(CONFIG),
+        None => return Ok(lint::Config::default()),
+    };
+
+    let config = fs::read_to_string(path)?;
+    lint::Config::from_json(&config).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
+
+///
+/// A linter of every rule (validating components against `registry`, if given),
+/// at the levels of `config`.
+///
+fn linter<Ext: External + 'static>(
+    registry: Option<&Registry>,
+    config: &lint::Config,
+) -> io::Result<Linter<Ext>> {
+    Linter::for_project(registry, config)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+///
+/// Parse `source`, and lint it, returning all errors found.
+///
+fn check_file<Ext: External + 'static>(
+    source: &SourceFile,
+    linter: &Linter<Ext>,
+) -> (Option<File<Ext>>, Vec<Error>) {
+    let (file, errors) = File::<Ext>::parser()
+        .parse(source.stream())
+        .into_output_errors();
+    let errors = linter.check(file.as_ref(), errors);
+
+    (file, errors)
+}
+
+///
+/// Print `errors` to stderr, returning if there were any denied.
+///
+fn report<Ext: External>(source: &SourceFile, errors: Vec<Error>, linter: &Linter<Ext>) -> bool {
+    let mut failed = false;
+
+    for error in errors {
+        failed |= linter.level(&error) == Level::Deny;
+        let _ = linter.diagnostic(error).to_report().eprint(source);
+    }
+
+    failed
+}
+
+fn check<Ext: External + 'static>(
+    paths: &[PathBuf],
+    linter: &Linter<Ext>,
+    format: MessageFormat,
+) -> io::Result<bool> {
+    let mut failed = false;
+    let mut log = sarif::Log::new(linter.defaults()).with_levels(linter.rules());
+
+    for path in collect_files(paths)? {
+        let source = SourceFile::read(&path)?;
+        let (_, errors) = check_file(&source, linter);
+
+        if let MessageFormat::Human = format {
+            failed |= report(&source, errors, linter);
+            continue;
+        }
+
+        for error in errors {
+            failed |= linter.level(&error) == Level::Deny;
+            let diagnostic = linter.diagnostic(error);
+
+            match format {
+                MessageFormat::Json => println!("{}", emit::json(&diagnostic, &source)),
+                _ => log.add(&diagnostic, &source),
+            }
+        }
+    }
+
+    if let MessageFormat::Sarif = format {
+        println!("{:#}", log.finish());
+    }
+
+    Ok(failed)
+}
+
+fn parse<Ext: External + 'static>(
+    path: &Path,
+    format: Format,
+    linter: &Linter<Ext>,
+) -> io::Result<bool> {
+    let source = SourceFile::read(path)?;
+    let (file, errors) = check_file(&source, linter);
+
+    match format {
+        Format::Debug => {
+            if let Some(file) = &file {
+                println!("{file:#?}");
+            }
+        }
+        Format::Json => {
+            let json = serialize::document(file.as_ref(), &errors, &source);
+            println!("{json:#}");
+        }
+    }
+
+    Ok(report(&source, errors, linter))
+}
+
+fn fmt<Ext: External + 'static>(
+    paths: &[PathBuf],
+    check: bool,
+    linter: &Linter<Ext>,
+) -> io::Result<bool> {
+    let mut failed = false;
+
+    for path in collect_files(paths)? {
+        let source = SourceFile::read(&path)?;
+        let (file, errors) = File::<Ext>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+
+        // Only format files that parsed cleanly, so nothing is lost.
+        let has_errors = !errors.is_empty();
+        let errors = linter.check(None, errors);
+        failed |= report(&source, errors, linter);
+        let Some(file) = file.filter(|_| !has_errors) else {
+            eprintln!(
+                "avpony: not formatting {}, as it has errors",
+                path.display()
+            );
+            failed = true;
+            continue;
+        };
+
+        let formatted = format::format(&file, &source, &Config::default());
+        if formatted == source.contents() {
+            continue;
+        }
+
+        match check {
+            true => {
+                eprintln!("avpony: {} is not formatted", path.display());
+                failed = true;
+            }
+            false => fs::write(&path, formatted)?,
+        }
+    }
+
+    Ok(failed)
+}
+
+fn fix<Ext: External + 'static>(paths: &[PathBuf], linter: &Linter<Ext>) -> io::Result<bool> {
+    let mut failed = false;
+
+    for path in collect_files(paths)? {
+        let fixed = fix::fix(SourceFile::read(&path)?, linter);
+
+        if !fixed.applied.is_empty() {
+            fs::write(&path, fixed.source.contents())?;
+            eprintln!(
+                "avpony: applied {} fix(es) to {}",
+                fixed.applied.len(),
+                path.display()
+            );
+        }
+        failed |= report(&fixed.source, fixed.errors, linter);
+    }
+
+    Ok(failed)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let registry = match cli.components.as_deref().map(read_registry).transpose() {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("avpony: {err}");
+            return ExitCode::from(2);
+        }
+    };
+    let registry = registry.as_ref();
+
+    let config = match read_config(cli.config.as_deref()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("avpony: {err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let res = match (cli.command, cli.lang) {
+        (Command::Check { paths, format }, Lang::Ts) => linter::<TypeScript>(registry, &config)
+            .and_then(|linter| check(&paths, &linter, format)),
+        (Command::Check { paths, format }, Lang::Rs) => {
+            linter::<Rust>(registry, &config).and_then(|linter| check(&paths, &linter, format))
+        }
+        (Command::Parse { path, format, json }, Lang::Ts) => {
+            let format = if json { Format::Json } else { format };
+            linter::<TypeScript>(registry, &config).and_then(|linter| parse(&path, format, &linter))
+        }
+        (Command::Parse { path, format, json }, Lang::Rs) => {
+            let format = if json { Format::Json } else { format };
+            linter::<Rust>(registry, &config).and_then(|linter| parse(&path, format, &linter))
+        }
+        (Command::Schema, _) => {
+            println!("{}", serialize::SCHEMA.trim_end());
+            Ok(false)
+        }
+        (Command::Fmt { paths, check }, Lang::Ts) => {
+            linter::<TypeScript>(None, &config).and_then(|linter| fmt(&paths, check, &linter))
+        }
+        (Command::Fmt { paths, check }, Lang::Rs) => {
+            linter::<Rust>(None, &config).and_then(|linter| fmt(&paths, check, &linter))
+        }
+        (Command::Fix { paths }, Lang::Ts) => {
+            linter::<TypeScript>(registry, &config).and_then(|linter| fix(&paths, &linter))
+        }
+        (Command::Fix { paths }, Lang::Rs) => {
+            linter::<Rust>(registry, &config).and_then(|linter| fix(&paths, &linter))
+        }
+        (Command::Lsp, Lang::Ts) => {
+            lsp::run::<TypeScript>(io::stdin().lock(), io::stdout().lock(), registry, &config)
+        }
+        (Command::Lsp, Lang::Rs) => {
+            lsp::run::<Rust>(io::stdin().lock(), io::stdout().lock(), registry, &config)
+        }
+    };
+
+    match res {
+        Ok(false) => ExitCode::SUCCESS,
+        Ok(true) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("avpony: {err}");
+            ExitCode::from(2)
+        }
+    }
 }
@@ -16,9 +16,7 @@
 //! * `{:catch <ident>}`
 //!
 
-use std::iter::once;
-
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{choice, just},
     IterParser, Parser,
@@ -26,7 +24,10 @@ use chumsky::{
 
 use crate::{
     lexical,
-    ponyx::Node,
+    ponyx::{
+        blocks::{branches, closing, stray},
+        Node,
+    },
     syntax::external::External,
     utils::{
         placeholder::{Maybe, MaybeParser},
@@ -34,9 +35,18 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct AwaitBlock<Ext: External> {
     span: Span,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
     pub expr: Maybe<Ext::Expression>,
     pub branches: Vec<(Branch, Vec<Node<Ext>>)>,
 }
@@ -60,39 +70,60 @@ impl<Ext: External + 'static> AwaitBlock<Ext> {
         let with_leaves = base
             .clone()
             .delimited_by(just("{"), just("}"))
+            .map_with(|expr, ctx| (expr, ctx.span()))
             .padded()
             .then(pending)
-            .then(success_or_failure.repeated().collect::<Vec<_>>())
-            .map_with(|((expr, pending), other_branches), ctx| Self {
-                span: ctx.span(),
-                expr,
-                branches: once(pending).chain(other_branches).collect::<Vec<_>>(),
-            });
+            .then(
+                choice((
+                    success_or_failure.map(Ok),
+                    stray(node, &["then", "catch"]).map(Err),
+                ))
+                .repeated()
+                .collect::<Vec<_>>(),
+            )
+            .map(|((opening, pending), leaves)| (opening, pending, leaves));
 
         let inline = base
             .then(Branch::parser())
             .delimited_by(just("{"), just("}"))
+            .map_with(|(expr, branch), ctx| ((expr, ctx.span()), branch))
             .padded()
             .then(nodes)
-            .map_with(|((expr, branch), children), ctx| Self {
-                span: ctx.span(),
-                expr,
-                branches: Vec::from([(branch, children)]),
-            });
-
-        choice((with_leaves, inline)).then_ignore(
-            just("/await")
-                .padded()
-                .delimited_by(just("{"), just("}"))
-                .padded(),
-        )
+            .map(|((opening, branch), children)| (opening, (branch, children), Vec::new()));
+
+        choice((with_leaves, inline))
+            .map_with(|block, ctx| (block, ctx.span()))
+            .then(closing("await"))
+            .validate(
+                |((((expr, opener), first, leaves), span), closing), _, emitter| Self {
+                    span,
+                    expr,
+                    branches: branches(
+                        opener,
+                        "await",
+                        vec![first],
+                        leaves,
+                        |(_, nodes)| nodes,
+                        closing,
+                        emitter,
+                    ),
+                },
+            )
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Visit)]
+#[visit(name = await_branch)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", content = "binding")
+)]
 pub enum Branch {
     Pending,
+    #[cfg_attr(feature = "serde", serde(rename = "Then"))]
     Success(Maybe<lexical::Identifier>),
+    #[cfg_attr(feature = "serde", serde(rename = "Catch"))]
     Failure(Maybe<lexical::Identifier>),
 }
 
@@ -14,12 +14,17 @@
 //!
 //!
 
-use avpony_macros::Spanned;
+use std::iter::once;
+
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, IterParser, Parser};
 
 use crate::{
     lexical,
-    ponyx::Node,
+    ponyx::{
+        blocks::{branches, closing, stray},
+        Node,
+    },
     syntax::external::External,
     utils::{
         placeholder::{Maybe, MaybeParser},
@@ -27,11 +32,24 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct ForBlock<Ext: External> {
     span: Span,
     pub ident: Maybe<lexical::Identifier>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
     pub iter: Maybe<Ext::Expression>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
     pub key: Option<Maybe<Ext::Expression>>,
     pub children: Vec<Node<Ext>>,
     pub empty_case: Option<Vec<Node<Ext>>>,
@@ -47,6 +65,7 @@ impl<Ext: External + 'static> ForBlock<Ext> {
             .then(Ext::expression().padded())
             .then(just("by").ignore_then(Ext::expression().padded()).or_not())
             .delimited_by(just("{"), just("}"))
+            .map_with(|opening, ctx| (opening, ctx.span()))
             .padded()
             .then(node.clone().repeated().collect::<Vec<_>>());
 
@@ -54,25 +73,36 @@ impl<Ext: External + 'static> ForBlock<Ext> {
             .padded()
             .delimited_by(just("{"), just("}"))
             .padded()
-            .ignore_then(node.repeated().collect::<Vec<_>>())
+            .ignore_then(node.clone().repeated().collect::<Vec<_>>())
             .or_not();
 
-        let closing = just("/for")
-            .padded()
-            .delimited_by(just("{"), just("}"))
-            .padded()
-            .ignored();
-
-        opening.then(opt_empty_case).then_ignore(closing).map_with(
-            |((((ident, iter), key), children), empty_case), ctx| Self {
-                span: ctx.span(),
-                ident,
-                iter,
-                key,
-                children,
-                empty_case,
-            },
-        )
+        opening
+            .then(opt_empty_case)
+            .then(stray(node, &["else"]).repeated().collect::<Vec<_>>())
+            .then(closing("for"))
+            .validate(|(((opening, empty_case), strays), closing), ctx, emitter| {
+                let ((((ident, iter), key), opener), children) = opening;
+
+                let mut cases = branches(
+                    opener,
+                    "for",
+                    once(children).chain(empty_case).collect::<Vec<_>>(),
+                    strays.into_iter().map(Err),
+                    |nodes| nodes,
+                    closing,
+                    emitter,
+                )
+                .into_iter();
+
+                Self {
+                    span: ctx.span(),
+                    ident,
+                    iter,
+                    key,
+                    children: cases.next().unwrap_or_default(),
+                    empty_case: cases.next(),
+                }
+            })
     }
 }
 
@@ -110,7 +140,7 @@ mod tests {
         );
 
         let (source, _) =
-            SourceFile::test_file(r#"{#for dog in kennel by dog.name}{item.id}{/for}"#);
+            SourceFile::test_file(r#"{#for dog in kennel by dog.name}{dog.id}{/for}"#);
         let res = Node::<TypeScript>::parser().parse(source.stream());
 
         assert!(!res.has_errors() && res.has_output());
@@ -123,7 +153,7 @@ mod tests {
             ]) && ident == "dog"
         );
         let (source, _) =
-            SourceFile::test_file(r#"{#for dog in kennel by dog.name}{item.id}{:else} A {/for}"#);
+            SourceFile::test_file(r#"{#for dog in kennel by dog.name}{dog.id}{:else} A {/for}"#);
         let res = Node::<TypeScript>::parser().parse(source.stream());
 
         assert!(!res.has_errors() && res.has_output());
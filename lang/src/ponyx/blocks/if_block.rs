@@ -18,46 +18,75 @@
 //!
 use std::iter::once;
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{choice, just},
     text, IterParser, Parser,
 };
 
 use crate::{
-    ponyx::Node,
+    ponyx::{
+        blocks::{branches, closing, stray},
+        Node,
+    },
     syntax::external::External,
     utils::{error::blocks::UnreachableBranch, placeholder::Maybe, PonyParser, Span, Spanned},
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct IfBranch<Ext: External> {
     span: Span,
-    expr: Maybe<Ext::Expression>,
-    contents: Vec<Node<Ext>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub expr: Maybe<Ext::Expression>,
+    pub contents: Vec<Node<Ext>>,
 }
 
 impl<Ext: External + 'static> IfBranch<Ext> {
+    ///
+    /// This branch, and the span of its `{#if ...}`.
+    ///
     fn parse_with<'src>(
         node: impl PonyParser<'src, Node<Ext>> + Clone,
-    ) -> impl PonyParser<'src, Self> + Clone {
+    ) -> impl PonyParser<'src, (Self, Span)> + Clone {
         just("#if ")
             .ignore_then(Ext::expression().padded())
             .delimited_by(just("{"), just("}"))
+            .map_with(|expr, ctx| (expr, ctx.span()))
             .then(node.repeated().collect().padded())
-            .map_with(|(expr, contents), ctx| Self {
-                span: ctx.span(),
-                expr,
-                contents,
+            .map_with(|((expr, opener), contents), ctx| {
+                let branch = Self {
+                    span: ctx.span(),
+                    expr,
+                    contents,
+                };
+
+                (branch, opener)
             })
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct ElseIfBranch<Ext: External> {
     span: Span,
-    expr: Maybe<Ext::Expression>,
-    contents: Vec<Node<Ext>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub expr: Maybe<Ext::Expression>,
+    pub contents: Vec<Node<Ext>>,
 }
 
 impl<Ext: External + 'static> ElseIfBranch<Ext> {
@@ -76,10 +105,15 @@ impl<Ext: External + 'static> ElseIfBranch<Ext> {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct ElseBranch<Ext: External> {
     span: Span,
-    contents: Vec<Node<Ext>>,
+    pub contents: Vec<Node<Ext>>,
 }
 
 impl<Ext: External + 'static> ElseBranch<Ext> {
@@ -98,17 +132,38 @@ impl<Ext: External + 'static> ElseBranch<Ext> {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[visit(name = if_block_branch)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum Branch<Ext: External> {
     If(IfBranch<Ext>),
     ElseIf(ElseIfBranch<Ext>),
     Else(ElseBranch<Ext>),
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+impl<Ext: External> Branch<Ext> {
+    fn contents_mut(&mut self) -> &mut Vec<Node<Ext>> {
+        match self {
+            Self::If(branch) => &mut branch.contents,
+            Self::ElseIf(branch) => &mut branch.contents,
+            Self::Else(branch) => &mut branch.contents,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct IfBlock<Ext: External> {
     span: Span,
-    branches: Vec<Branch<Ext>>,
+    pub branches: Vec<Branch<Ext>>,
 }
 
 impl<Ext: External + 'static> IfBlock<Ext> {
@@ -117,23 +172,37 @@ impl<Ext: External + 'static> IfBlock<Ext> {
     ) -> impl PonyParser<'src, Self> + Clone {
         IfBranch::parse_with(node.clone())
             .padded()
-            .map(Branch::If)
             .then(
                 choice((
                     ElseIfBranch::parse_with(node.clone())
                         .padded()
-                        .map(Branch::ElseIf),
-                    ElseBranch::parse_with(node).padded().map(Branch::Else),
+                        .map(Branch::ElseIf)
+                        .map(Ok),
+                    ElseBranch::parse_with(node.clone())
+                        .padded()
+                        .map(Branch::Else)
+                        .map(Ok),
+                    stray(node, &["else"]).map(Err),
                 ))
                 .repeated()
-                .collect::<Vec<Branch<Ext>>>(),
-            )
-            .then_ignore(
-                just("/if")
-                    .padded()
-                    .delimited_by(just("{"), just("}"))
-                    .padded(),
+                .collect::<Vec<_>>(),
             )
+            .then(closing("if"))
+            .validate(|(((start, opener), leaves), closing), _, emitter| {
+                let start = vec![Branch::If(start)];
+                let mut all = branches(
+                    opener,
+                    "if",
+                    start,
+                    leaves,
+                    Branch::contents_mut,
+                    closing,
+                    emitter,
+                );
+
+                let start = all.remove(0);
+                (start, all)
+            })
             .validate(|(start, branches): (_, Vec<Branch<Ext>>), ctx, emitter| {
                 match branches.as_slice() {
                     [] => (),
@@ -153,7 +222,7 @@ impl<Ext: External + 'static> IfBlock<Ext> {
                                     .reduce(|acc, b| acc.combine(b).unwrap())
                                     .unwrap();
 
-                                emitter.emit(UnreachableBranch::new(span, ctx.span()).into());
+                                emitter.emit(UnreachableBranch::new(span, ctx.span(), "if").into());
 
                                 // Get all branches before (and including) the `{:else}`.
                                 let branches = branches[..=before.len()].to_owned();
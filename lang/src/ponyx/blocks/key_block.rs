@@ -7,20 +7,32 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, IterParser, Parser};
 
 use crate::{
-    ponyx::Node,
+    ponyx::{
+        blocks::{branches, closing, stray},
+        Node,
+    },
     syntax::external::External,
     utils::{placeholder::Maybe, PonyParser, Span},
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct KeyBlock<Ext: External> {
     span: Span,
-    expr: Maybe<Ext::Expression>,
-    children: Vec<Node<Ext>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub expr: Maybe<Ext::Expression>,
+    pub children: Vec<Node<Ext>>,
 }
 
 impl<Ext: External + 'static> KeyBlock<Ext> {
@@ -30,14 +42,32 @@ impl<Ext: External + 'static> KeyBlock<Ext> {
         just("#key ")
             .ignore_then(Ext::expression().padded())
             .delimited_by(just("{"), just("}"))
+            .map_with(|expr, ctx| (expr, ctx.span()))
             .padded()
-            .then(node.repeated().collect::<Vec<_>>())
-            .then_ignore(just("/key").delimited_by(just("{"), just("}")).padded())
-            .map_with(|(expr, children), ctx| Self {
-                span: ctx.span(),
-                expr,
-                children,
-            })
+            .then(node.clone().repeated().collect::<Vec<_>>())
+            .then(stray(node, &[]).repeated().collect::<Vec<_>>())
+            .then(closing("key"))
+            .validate(
+                |((((expr, opener), children), strays), closing), ctx, emitter| {
+                    let children = branches(
+                        opener,
+                        "key",
+                        vec![children],
+                        strays.into_iter().map(Err),
+                        |nodes| nodes,
+                        closing,
+                        emitter,
+                    )
+                    .pop()
+                    .unwrap_or_default();
+
+                    Self {
+                        span: ctx.span(),
+                        expr,
+                        children,
+                    }
+                },
+            )
     }
 }
 
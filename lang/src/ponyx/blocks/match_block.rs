@@ -0,0 +1,299 @@
+//!
+//! ## Match Blocks
+//!
+//! ```avpony
+//! {#match <external_expr>}
+//!     {:when <external_pat>}
+//!         A
+//!     {:default <ident>?}
+//!         B
+//! {/match}
+//! ```
+//!
+//! Patterns are refutable, and provided by the external language
+//! (see [External::pattern]).
+//!
+//! ### Leaves
+//! * `{:when <external_pat>} <node*>`
+//! * `{:default <ident>?} <node*>` -- When no other leaf matched.
+//!
+
+use avpony_macros::{Spanned, Visit};
+use chumsky::{
+    primitive::{choice, just},
+    text, IterParser, Parser,
+};
+
+use crate::{
+    lexical,
+    ponyx::{
+        blocks::{branches, closing, stray},
+        Node,
+    },
+    syntax::external::External,
+    utils::{
+        error::blocks::{MissingDefaultArm, UnreachableBranch},
+        placeholder::Maybe,
+        ParseableCloned, PonyParser, Span, Spanned,
+    },
+};
+
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
+pub struct WhenArm<Ext: External> {
+    span: Span,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub pattern: Maybe<Ext::Pattern>,
+    pub children: Vec<Node<Ext>>,
+}
+
+impl<Ext: External + 'static> WhenArm<Ext> {
+    fn parse_with<'src>(
+        node: impl PonyParser<'src, Node<Ext>> + Clone,
+    ) -> impl PonyParser<'src, Self> + Clone {
+        just(":when ")
+            .ignore_then(Ext::pattern().padded())
+            .delimited_by(just("{"), just("}"))
+            .then(node.repeated().collect().padded())
+            .map_with(|(pattern, children), ctx| Self {
+                span: ctx.span(),
+                pattern,
+                children,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
+pub struct DefaultArm<Ext: External> {
+    span: Span,
+    pub binding: Option<lexical::Identifier>,
+    pub children: Vec<Node<Ext>>,
+}
+
+impl<Ext: External + 'static> DefaultArm<Ext> {
+    fn parse_with<'src>(
+        node: impl PonyParser<'src, Node<Ext>> + Clone,
+    ) -> impl PonyParser<'src, Self> + Clone {
+        just(":default")
+            .ignore_then(
+                text::whitespace()
+                    .at_least(1)
+                    .ignore_then(lexical::Identifier::parser())
+                    .or_not(),
+            )
+            .then_ignore(text::whitespace())
+            .delimited_by(just("{"), just("}"))
+            .then(node.repeated().collect().padded())
+            .map_with(|(binding, children), ctx| Self {
+                span: ctx.span(),
+                binding,
+                children,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
+pub enum Arm<Ext: External> {
+    When(WhenArm<Ext>),
+    Default(DefaultArm<Ext>),
+}
+
+impl<Ext: External> Arm<Ext> {
+    fn children_mut(&mut self) -> &mut Vec<Node<Ext>> {
+        match self {
+            Self::When(arm) => &mut arm.children,
+            Self::Default(arm) => &mut arm.children,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
+pub struct MatchBlock<Ext: External> {
+    span: Span,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub expr: Maybe<Ext::Expression>,
+    pub arms: Vec<Arm<Ext>>,
+}
+
+impl<Ext: External + 'static> MatchBlock<Ext> {
+    pub fn parse_with<'src>(
+        node: impl PonyParser<'src, Node<Ext>> + Clone,
+    ) -> impl PonyParser<'src, Self> + Clone {
+        just("#match ")
+            .ignore_then(Ext::expression().padded())
+            .delimited_by(just("{"), just("}"))
+            .map_with(|expr, ctx| (expr, ctx.span()))
+            .padded()
+            .then(
+                choice((
+                    WhenArm::parse_with(node.clone())
+                        .padded()
+                        .map(Arm::When)
+                        .map(Ok),
+                    DefaultArm::parse_with(node.clone())
+                        .padded()
+                        .map(Arm::Default)
+                        .map(Ok),
+                    stray(node, &["when", "default"]).map(Err),
+                ))
+                .repeated()
+                .collect::<Vec<_>>(),
+            )
+            .then(closing("match"))
+            .validate(|(((expr, opener), leaves), closing), _, emitter| {
+                let arms = branches(
+                    opener,
+                    "match",
+                    Vec::new(),
+                    leaves,
+                    Arm::children_mut,
+                    closing,
+                    emitter,
+                );
+
+                (expr, arms)
+            })
+            .validate(|(expr, arms): (_, Vec<Arm<Ext>>), ctx, emitter| {
+                let Some((before, after)) = arms.split_once(|arm| matches!(arm, Arm::Default(_)))
+                else {
+                    emitter.emit(MissingDefaultArm::new(ctx.span()).into());
+                    return (expr, arms);
+                };
+
+                if after.is_empty() {
+                    return (expr, arms);
+                }
+
+                // Give a warning for arms after the `{:default}`,
+                // and drop them, like with `{:else}` in an if block.
+                let span = after
+                    .iter()
+                    .map(|arm| arm.span())
+                    .reduce(|acc, b| acc.combine(b).unwrap())
+                    .unwrap();
+
+                emitter.emit(UnreachableBranch::new(span, ctx.span(), "match").into());
+
+                let arms = arms[..=before.len()].to_owned();
+                (expr, arms)
+            })
+            .map_with(|(expr, arms), ctx| Self {
+                span: ctx.span(),
+                expr,
+                arms,
+            })
+    }
+
+    ///
+    /// The `{:default}` leaf of this block, if any.
+    ///
+    pub fn default_arm(&self) -> Option<&DefaultArm<Ext>> {
+        self.arms.iter().find_map(|arm| match arm {
+            Arm::Default(default) => Some(default),
+            Arm::When(_) => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use chumsky::Parser;
+
+    use crate::{
+        ponyx::{
+            blocks::{
+                match_block::{Arm, DefaultArm, MatchBlock},
+                LogicBlock,
+            },
+            Node,
+        },
+        syntax::external::typescript::TypeScript,
+        utils::{Error, Parseable, SourceFile},
+    };
+
+    #[test]
+    fn match_block() {
+        let (source, _) = SourceFile::test_file(
+            r#"{#match friends.length}
+    {:when 1}
+        You have a new friend.
+    {:when 0}
+        No friends yet.
+    {:default f}
+        You have {f} friends.
+{/match}"#,
+        );
+        let res = Node::<TypeScript>::parser().parse(source.stream());
+        assert!(res.has_output() && !res.has_errors());
+
+        assert_matches!(
+            res.output(),
+            Some(Node::Block(LogicBlock::Match(MatchBlock { arms, .. }))) if matches!(arms.as_slice(), [
+                Arm::When(_),
+                Arm::When(_),
+                Arm::Default(DefaultArm { binding: Some(ident), .. }),
+            ] if ident == "f")
+        );
+    }
+
+    #[test]
+    fn missing_default() {
+        let (source, _) = SourceFile::test_file(
+            r#"{#match status}
+    {:when "ok"}
+        All good.
+{/match}"#,
+        );
+        let res = Node::<TypeScript>::parser().parse(source.stream());
+        assert!(res.has_output());
+        assert_matches!(res.errors().next(), Some(Error::MissingDefaultArm(_)));
+    }
+
+    #[test]
+    fn unreachable_after_default() {
+        let (source, _) = SourceFile::test_file(
+            r#"{#match status}
+    {:default}
+        Anything.
+    {:when "ok"}
+        Never shown.
+{/match}"#,
+        );
+        let res = Node::<TypeScript>::parser().parse(source.stream());
+        assert!(res.has_output());
+
+        assert_matches!(
+            res.output(),
+            Some(Node::Block(LogicBlock::Match(MatchBlock { arms, .. })))
+                if matches!(arms.as_slice(), [Arm::Default(DefaultArm { binding: None, .. })])
+        );
+        assert_matches!(res.errors().next(), Some(Error::UnreachableBranch(_)));
+    }
+}
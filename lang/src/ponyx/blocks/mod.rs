@@ -44,8 +44,8 @@
 //! {/key}
 //! ```
 //!
-//! ### Match blocks (Proposal)
-//! For TypeScript, potentially look at using
+//! ### Match blocks
+//! Patterns come from the external language: for TypeScript, these follow the
 //! [TC39 Pattern Matching Proposal](https://tc39.es/proposal-pattern-matching/#sec-pattern-matching).
 //!
 //! ```avpony
@@ -59,31 +59,69 @@
 //!
 //! Leaves: `{:when <refutable pat>}`, `{:default <non-refutable pat>}`
 //!
+//! ### Unknown blocks
+//! Any other `{#KEYWORD ...}`, or `{:KEYWORD ...}` is an error ([UnknownBlock]),
+//! suggesting the closest keyword (if it's likely misspelt), e.g. `{#for}` for `{#fro}`.
+//!
+//! ### Recovery
+//! Blocks are still parsed, with an error pointing at their opening `{#KEYWORD ...}`, when:
+//! * They aren't closed before their parent tag is, a block enclosing them is,
+//!   or the file ends ([UnclosedBlock]);
+//! * They're closed by the `{/KEYWORD}` of another block, not enclosing them ([MismatchedBlock]);
+//! * They have a leaf of another block, like `{:then}` in an `{#if}` ([StrayLeaf]),
+//!   whose nodes are kept in the branch before it.
+//!
 
 pub mod await_block;
 pub mod for_block;
 pub mod if_block;
 pub mod key_block;
+pub mod match_block;
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use await_block::AwaitBlock;
-use chumsky::{primitive::choice, Parser};
+use chumsky::{
+    input::Emitter,
+    primitive::{choice, custom, end, just, none_of},
+    text, IterParser, Parser,
+};
 use for_block::ForBlock;
 use if_block::IfBlock;
 use key_block::KeyBlock;
+use match_block::MatchBlock;
 
-use crate::{syntax::external::External, utils::PonyParser};
+use crate::{
+    syntax::external::External,
+    utils::{
+        error::blocks::{MismatchedBlock, StrayLeaf, UnclosedBlock, UnknownBlock},
+        suggest, Error, PonyParser, Span,
+    },
+};
 
 use super::Node;
 
-pub const KEYWORDS: &[&str] = &["if", "match", "await"];
+///
+/// Keywords of blocks, after `{#`.
+///
+pub const KEYWORDS: &[&str] = &["if", "for", "await", "key", "match"];
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+///
+/// Keywords of leaves, after `{:`.
+///
+pub const LEAVES: &[&str] = &["else", "then", "catch", "when", "default"];
+
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum LogicBlock<Ext: External> {
     If(IfBlock<Ext>),
     For(ForBlock<Ext>),
     Await(AwaitBlock<Ext>),
     Key(KeyBlock<Ext>),
+    Match(MatchBlock<Ext>),
 }
 
 impl<Ext: External + 'static> LogicBlock<Ext> {
@@ -91,10 +129,273 @@ impl<Ext: External + 'static> LogicBlock<Ext> {
         node: impl PonyParser<'src, Node<Ext>> + Clone,
     ) -> impl PonyParser<'src, Self> + Clone {
         choice((
-            ForBlock::parse_with(node.clone()).map(Self::For),
-            IfBlock::parse_with(node.clone()).map(Self::If),
-            AwaitBlock::parse_with(node.clone()).map(Self::Await),
-            KeyBlock::parse_with(node.clone()).map(Self::Key),
+            ForBlock::parse_with(within("for", node.clone())).map(Self::For),
+            IfBlock::parse_with(within("if", node.clone())).map(Self::If),
+            AwaitBlock::parse_with(within("await", node.clone())).map(Self::Await),
+            KeyBlock::parse_with(within("key", node.clone())).map(Self::Key),
+            MatchBlock::parse_with(within("match", node.clone())).map(Self::Match),
+            Self::unknown(),
         ))
     }
+
+    ///
+    /// Fails on blocks, and leaves with a keyword
+    /// not in [KEYWORDS], or [LEAVES].
+    ///
+    fn unknown<'src>() -> impl PonyParser<'src, Self> + Clone {
+        let keywords = |sigil: char| match sigil {
+            '#' => KEYWORDS,
+            _ => LEAVES,
+        };
+
+        choice((just("{#").to('#'), just("{:").to(':')))
+            .then(
+                text::ident()
+                    .to_slice()
+                    .map_with(|keyword: &str, ctx| (keyword, ctx.span())),
+            )
+            // Known blocks that failed to parse have their own errors.
+            .filter(move |(sigil, (keyword, _))| !keywords(*sigil).contains(keyword))
+            .try_map(move |(sigil, (keyword, span)): (char, (&str, Span)), _| {
+                let suggestion = suggest::closest(keyword, keywords(sigil).iter().copied());
+                Err::<Self, Error>(
+                    UnknownBlock::new(span, sigil, keyword.to_string(), suggestion).into(),
+                )
+            })
+    }
+}
+
+///
+/// `node`, inside a block of `keyword` (see [crate::utils::PonyContext::blocks]).
+///
+fn within<'src, Ext: External + 'static>(
+    keyword: &'static str,
+    node: impl PonyParser<'src, Node<Ext>> + Clone,
+) -> impl PonyParser<'src, Node<Ext>> + Clone {
+    custom(move |stream| {
+        let ctx = stream.ctx().in_block(keyword);
+        stream.parse(node.clone().with_ctx(ctx))
+    })
+}
+
+///
+/// How a block was closed (see [closing]).
+///
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Closing {
+    Closed,
+
+    ///
+    /// By the `{/KEYWORD}` of another block.
+    ///
+    Mismatched(Span, String),
+
+    ///
+    /// Not at all, before its parent tag, or a block enclosing it is closed, or the file ends.
+    ///
+    Missing(Span),
+}
+
+impl Closing {
+    ///
+    /// The error for a block of `keyword`, opened at `opener`, if it wasn't closed properly.
+    ///
+    pub(crate) fn error(self, opener: Span, keyword: &'static str) -> Option<Error> {
+        match self {
+            Self::Closed => None,
+            Self::Mismatched(span, closing) => {
+                Some(MismatchedBlock::new(span, opener, keyword, closing).into())
+            }
+            Self::Missing(span) => Some(UnclosedBlock::new(span, opener, keyword).into()),
+        }
+    }
+}
+
+///
+/// The `{/keyword}` closing a block, recovering
+/// from another block's, or a missing one.
+///
+/// The `{/KEYWORD}` of a block enclosing this one is left for it to close,
+/// with this one unclosed.
+///
+pub(crate) fn closing<'src>(keyword: &'static str) -> impl PonyParser<'src, Closing> + Clone {
+    let closed = just("/")
+        .ignore_then(just(keyword))
+        .padded()
+        .delimited_by(just("{"), just("}"))
+        .to(Closing::Closed);
+
+    let mismatched = just("/")
+        .ignore_then(text::ident().to_slice().padded())
+        .delimited_by(just("{"), just("}"))
+        .map_with(|closing: &str, ctx| {
+            let enclosing = ctx.ctx().blocks.iter().any(|block| *block == closing);
+            (closing, ctx.span(), enclosing)
+        })
+        .filter(|(_, _, enclosing)| !enclosing)
+        .map(|(closing, span, _)| Closing::Mismatched(span, closing.to_string()));
+
+    let missing = choice((end(), just("</").ignored(), just("{/").ignored()))
+        .rewind()
+        .map_with(|_, ctx| Closing::Missing(ctx.span()));
+
+    choice((closed, mismatched, missing)).padded()
+}
+
+///
+/// A leaf in a block it doesn't belong to, and the nodes after it.
+///
+#[derive(Debug, Clone)]
+pub(crate) struct Stray<Ext: External> {
+    span: Span,
+    leaf: String,
+    pub nodes: Vec<Node<Ext>>,
+}
+
+impl<Ext: External> Stray<Ext> {
+    ///
+    /// The error for this leaf, in a block of `keyword`, opened at `opener`.
+    ///
+    pub(crate) fn error(&self, opener: Span, keyword: &'static str) -> Error {
+        StrayLeaf::new(self.span, opener, keyword, self.leaf.clone()).into()
+    }
+}
+
+///
+/// A leaf (and the nodes after it) of a block,
+/// other than one of `leaves`.
+///
+pub(crate) fn stray<'src, Ext: External + 'static>(
+    node: impl PonyParser<'src, Node<Ext>> + Clone,
+    leaves: &'static [&'static str],
+) -> impl PonyParser<'src, Stray<Ext>> + Clone {
+    just("{:")
+        .ignore_then(text::ident().to_slice())
+        .filter(move |leaf: &&str| LEAVES.contains(leaf) && !leaves.contains(leaf))
+        .then_ignore(none_of("}").repeated())
+        .then_ignore(just("}"))
+        .map_with(|leaf: &str, ctx| (leaf.to_string(), ctx.span()))
+        .padded()
+        .then(node.repeated().collect::<Vec<_>>())
+        .map(|((leaf, span), nodes)| Stray { span, leaf, nodes })
+}
+
+///
+/// The branches of a block of `keyword`, opened at `opener`: `branches`,
+/// then those of its `leaves`, emitting errors for any stray leaves,
+/// and for how it was `closing`.
+///
+/// The nodes after a stray leaf are kept in the `contents` of the branch before it
+/// (if there is one).
+///
+pub(crate) fn branches<B, Ext: External>(
+    opener: Span,
+    keyword: &'static str,
+    mut branches: Vec<B>,
+    leaves: impl IntoIterator<Item = Result<B, Stray<Ext>>>,
+    contents: impl Fn(&mut B) -> &mut Vec<Node<Ext>>,
+    closing: Closing,
+    emitter: &mut Emitter<Error>,
+) -> Vec<B> {
+    for leaf in leaves {
+        match leaf {
+            Ok(branch) => branches.push(branch),
+            Err(stray) => {
+                emitter.emit(stray.error(opener, keyword));
+                if let Some(branch) = branches.last_mut() {
+                    contents(branch).extend(stray.nodes);
+                }
+            }
+        }
+    }
+
+    if let Some(error) = closing.error(opener, keyword) {
+        emitter.emit(error);
+    }
+
+    branches
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use std::assert_matches::assert_matches;
+
+    use crate::{
+        ponyx::{
+            blocks::{if_block::Branch, LogicBlock},
+            tag::Tag,
+            Node,
+        },
+        syntax::external::typescript::TypeScript,
+        utils::{Error, ErrorI, Parseable, SourceFile, Spanned},
+    };
+
+    #[test]
+    fn unknown_block() {
+        let (source, _) = SourceFile::test_file("{#fro item in items}{item}{/for}");
+        let errors = Node::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_errors();
+
+        let notes = errors
+            .into_iter()
+            .filter(|error| matches!(error, Error::UnknownBlock(_)))
+            .flat_map(|error| error.to_diagnostic().notes)
+            .collect::<Vec<_>>();
+        assert_eq!(notes, ["Did you mean `{#for}`?"]);
+    }
+
+    #[test]
+    fn unclosed_block() {
+        let src = "<Row>{#if a}A</Row>";
+        let (source, _) = SourceFile::test_file(src);
+        let (node, errors) = Node::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+
+        assert_matches!(node, Some(Node::Tag(Tag::Enclosing(tag))) if tag.children.len() == 1);
+        assert_matches!(errors.as_slice(), [Error::UnclosedBlock(error)] if error.span().range() == (13..13));
+    }
+
+    #[test]
+    fn mismatched_block() {
+        let src = "{#for a in b}{#if a}A{/for}";
+        let (source, _) = SourceFile::test_file(src);
+        let (node, errors) = Node::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+
+        // `{/for}` is left for the `{#for}`, with the `{#if}` unclosed.
+        assert_matches!(node, Some(Node::Block(LogicBlock::For(block))) if block.children.len() == 1);
+        assert_matches!(errors.as_slice(), [Error::UnclosedBlock(error)] if error.span().range() == (21..21));
+
+        let src = "{#if a}A{/for}";
+        let (source, _) = SourceFile::test_file(src);
+        let (node, errors) = Node::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+
+        assert_matches!(node, Some(Node::Block(LogicBlock::If(_))));
+        assert_matches!(errors.as_slice(), [Error::MismatchedBlock(error)] if &src[error.span().range()] == "{/for}");
+    }
+
+    #[test]
+    fn stray_leaf() {
+        let src = "{#if a}A{:then b}B{:else}C{/if}";
+        let (source, _) = SourceFile::test_file(src);
+        let (node, errors) = Node::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+
+        let Some(Node::Block(LogicBlock::If(block))) = node else {
+            panic!("{node:?}");
+        };
+        assert_matches!(
+            block.branches.as_slice(),
+            [Branch::If(first), Branch::Else(_)] if first.contents.len() == 2
+        );
+        assert_matches!(errors.as_slice(), [Error::StrayLeaf(error)] if &src[error.span().range()] == "{:then b}");
+    }
 }
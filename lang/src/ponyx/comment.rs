@@ -5,7 +5,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{any, just},
     IterParser, Parser,
@@ -13,10 +13,11 @@ use chumsky::{
 
 use crate::utils::{ParseableCloned, PonyParser, Span};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct Comment {
     span: Span,
-    content: String,
+    pub content: String,
 }
 
 impl ParseableCloned for Comment {
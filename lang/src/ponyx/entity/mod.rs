@@ -6,7 +6,7 @@
 //! [See the spec for them here.](https://html.spec.whatwg.org/multipage/syntax.html#syntax-charref)
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{any, choice, just, one_of},
     text, IterParser, Parser,
@@ -16,7 +16,7 @@ use crate::{
     lexical::string::hex_nibbles_to_u32,
     utils::{
         error::{html_ref::InvalidEntityName, string::InvalidUnicodeCodePoint, Error},
-        ParseableCloned, PonyParser, Span,
+        suggest, ParseableCloned, PonyParser, Span,
     },
 };
 
@@ -26,9 +26,17 @@ mod entities {
     pub fn get_by_name(name: &str) -> Option<&'static str> {
         CODES.binary_search(&name).ok().map(|i| VALUES[i])
     }
+
+    ///
+    /// The name of the entity closest to `name`, if it's likely misspelt.
+    ///
+    pub fn closest(name: &str) -> Option<&'static str> {
+        super::suggest::closest(name, CODES.iter().copied())
+    }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct Entity {
     span: Span,
     pub value: String,
@@ -51,7 +59,7 @@ impl ParseableCloned for Entity {
                             let u = hex_nibbles_to_u32(hex);
                             match char::try_from(u) {
                                 Ok(o) => Ok(Self {
-                                    span: span.clone(),
+                                    span,
                                     value: o.into(),
                                 }),
                                 Err(_) => Err(Error::InvalidUnicodeCodePoint(
@@ -65,11 +73,11 @@ impl ParseableCloned for Entity {
                             let u: u32 = digits.parse().unwrap();
                             match char::try_from(u) {
                                 Ok(o) => Ok(Self {
-                                    span: span.clone(),
+                                    span,
                                     value: o.into(),
                                 }),
                                 Err(_) => Err(Error::InvalidUnicodeCodePoint(
-                                    InvalidUnicodeCodePoint::new(span.clone(), u),
+                                    InvalidUnicodeCodePoint::new(span, u),
                                 )),
                             }
                         }),
@@ -78,11 +86,13 @@ impl ParseableCloned for Entity {
                     let val = entities::get_by_name(name).map(ToString::to_string);
 
                     match val {
-                        Some(value) => Ok(Self {
-                            span: span.clone(),
-                            value,
-                        }),
-                        None => Err(InvalidEntityName::new(span, name.to_owned()).into()),
+                        Some(value) => Ok(Self { span, value }),
+                        None => Err(InvalidEntityName::new(
+                            span,
+                            name.to_owned(),
+                            entities::closest(name).map(str::to_string),
+                        )
+                        .into()),
                     }
                 }),
             )))
@@ -94,10 +104,23 @@ impl ParseableCloned for Entity {
 mod tests {
     use chumsky::Parser;
 
-    use crate::utils::{Parseable, SourceFile};
+    use crate::utils::{ErrorI, Parseable, SourceFile};
 
     use super::Entity;
 
+    #[test]
+    fn misspelt() {
+        let (source, _) = SourceFile::test_file("&nbps;");
+        let errors = Entity::parser().parse(source.stream()).into_errors();
+
+        let fixes = errors
+            .into_iter()
+            .flat_map(|error| error.to_diagnostic().fixes)
+            .map(|fix| fix.replacement)
+            .collect::<Vec<_>>();
+        assert_eq!(fixes, ["nbsp"]);
+    }
+
     #[test]
     fn named() {
         let (source, _) = SourceFile::test_file("&nbsp;");
@@ -2,8 +2,8 @@
 //! ## PonyX Syntax
 //!
 
-use avpony_macros::Spanned;
-use blocks::LogicBlock;
+use avpony_macros::{Spanned, Visit};
+use blocks::{if_block::Branch as IfBranch, match_block::Arm, LogicBlock};
 use chumsky::{
     primitive::{choice, just},
     recursive::recursive,
@@ -30,7 +30,12 @@ pub mod statement;
 pub mod tag;
 pub mod text;
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum Node<Ext: External> {
     Comment(Comment),
     Text(Text),
@@ -51,7 +56,7 @@ impl<Ext: External + 'static> ParseableCloned for Node<Ext> {
                 AtStatement::parser().map(Self::Statement),
                 LogicBlock::parse_with(node.clone()).map(Self::Block),
                 Comment::parser().map(Self::Comment),
-                choice((just("{/"), just("{:")))
+                choice((just("{/"), just("{:"), just("{#")))
                     .not()
                     .rewind()
                     .ignore_then(ExternalExpr::parser())
@@ -61,5 +66,45 @@ impl<Ext: External + 'static> ParseableCloned for Node<Ext> {
     }
 }
 
+impl<Ext: External> Node<Ext> {
+    ///
+    /// Every list of child nodes of this node,
+    /// e.g. one for each branch of a logic block.
+    ///
+    pub fn children(&self) -> Vec<&[Node<Ext>]> {
+        match self {
+            Node::Tag(Tag::Enclosing(tag)) => vec![tag.children.as_slice()],
+            Node::Block(LogicBlock::If(block)) => block
+                .branches
+                .iter()
+                .map(|branch| match branch {
+                    IfBranch::If(branch) => branch.contents.as_slice(),
+                    IfBranch::ElseIf(branch) => &branch.contents,
+                    IfBranch::Else(branch) => &branch.contents,
+                })
+                .collect(),
+            Node::Block(LogicBlock::For(block)) => std::iter::once(&block.children)
+                .chain(&block.empty_case)
+                .map(Vec::as_slice)
+                .collect(),
+            Node::Block(LogicBlock::Await(block)) => block
+                .branches
+                .iter()
+                .map(|(_, nodes)| nodes.as_slice())
+                .collect(),
+            Node::Block(LogicBlock::Key(block)) => vec![block.children.as_slice()],
+            Node::Block(LogicBlock::Match(block)) => block
+                .arms
+                .iter()
+                .map(|arm| match arm {
+                    Arm::When(arm) => arm.children.as_slice(),
+                    Arm::Default(arm) => &arm.children,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 #[cfg(test)]
 pub type TNode = Node<TestLang>;
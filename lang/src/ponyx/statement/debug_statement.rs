@@ -8,7 +8,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, Parser};
 
 use crate::{
@@ -16,10 +16,19 @@ use crate::{
     utils::{placeholder::Maybe, ParseableCloned, PonyParser, Span},
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct DebugStatement<Ext: External> {
     span: Span,
-    expr: Maybe<Ext::Expression>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub expr: Maybe<Ext::Expression>,
 }
 
 impl<Ext: External> ParseableCloned for DebugStatement<Ext> {
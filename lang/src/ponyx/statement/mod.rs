@@ -6,7 +6,7 @@
 
 pub mod debug_statement;
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{choice, just},
     Parser,
@@ -18,10 +18,27 @@ use crate::{
     utils::{ParseableCloned, PonyParser},
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum AtStatement<Ext: External> {
-    Let(Ext::LetDeclaration),
-    Const(Ext::ConstDeclaration),
+    Let(
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serialize::external")
+        )]
+        Ext::LetDeclaration,
+    ),
+    Const(
+        #[cfg_attr(
+            feature = "serde",
+            serde(serialize_with = "crate::serialize::external")
+        )]
+        Ext::ConstDeclaration,
+    ),
     Debug(DebugStatement<Ext>),
 }
 
@@ -25,7 +25,7 @@
 //!     ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, Parser};
 
 use crate::{
@@ -37,7 +37,12 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum Attribute<Ext: External> {
     Key(AttributeKey),
     KeyValue(AttributeAssignment<Ext>),
@@ -58,7 +63,8 @@ impl<Ext: External + 'static> ParseableCloned for Attribute<Ext> {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 pub enum AttributeKey {
     Named(NamedAttribute),
     Directive(Directive),
@@ -83,16 +89,41 @@ impl ParseableCloned for AttributeKey {
     }
 }
 
+impl<Ext: External> Attribute<Ext> {
+    pub fn key(&self) -> &AttributeKey {
+        match self {
+            Self::Key(key) => key,
+            Self::KeyValue(AttributeAssignment { key, .. }) => key,
+        }
+    }
+
+    ///
+    /// The value of this attribute, if assigned one.
+    ///
+    pub fn value(&self) -> Option<&Maybe<SoloExpr<Ext>>> {
+        match self {
+            Self::Key(_) => None,
+            Self::KeyValue(AttributeAssignment { value, .. }) => Some(value),
+        }
+    }
+}
+
 pub type NamedAttribute = lexical::Identifier;
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct Directive {
     span: Span,
     pub base: lexical::Identifier,
     pub director: Maybe<lexical::Identifier>,
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct AttributeAssignment<Ext: External> {
     span: Span,
     pub key: AttributeKey,
@@ -2,7 +2,7 @@
 //! ## PonyX Tags
 //!
 //! ```text
-//! tag_name            := <ident> (`.` <ident>)*
+//! tag_name            := (see `name`)
 //!
 //! self_closing_tag    := `<` <tag_name> (<ws>)+ (<attribute> (<ws>)+)* `/>`
 //!
@@ -18,30 +18,60 @@ pub mod attribute;
 pub mod name;
 
 use attribute::Attribute;
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
-    primitive::{choice, just},
+    primitive::{any, choice, custom, just, none_of},
+    recovery::via_parser,
     text, IterParser, Parser,
 };
 use name::TagName;
 
 use crate::{
     syntax::external::External,
-    utils::{error::tag::UnclosedTag, ParseableCloned, PonyParser, Span},
+    utils::{
+        error::tag::{ImplicitlyClosedTag, UnclosedTag},
+        suggest, ParseableCloned, PonyParser, Span,
+    },
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum Tag<Ext: External> {
     SelfClosing(SelfClosingTag<Ext>),
     Enclosing(EnclosingTag<Ext>),
 }
 
 impl<Ext: External + 'static> Tag<Ext> {
+    ///
+    /// A tag, with `node`s as its children.
+    ///
+    /// Anything among its children that isn't a node is skipped (with an error),
+    /// up to the next `<`, or `{`, and a tag missing its closing tag is closed
+    /// (also with an error) by its parent's, or the end of its logic block, or the file.
+    /// The closing tag of a tag enclosing it is never taken as a misspelling of its own.
+    ///
     pub fn parser_with<'src>(
         node: impl PonyParser<'src, super::Node<Ext>> + Clone,
     ) -> impl PonyParser<'src, Self> + Clone {
-        just("<")
-            .ignore_then(TagName::parser())
+        let garbage = choice((just("</"), just("{/"), just("{:")))
+            .not()
+            .ignore_then(any())
+            .then(none_of("<{").repeated())
+            .to(None);
+
+        let children = node
+            .map(Some)
+            .recover_with(via_parser(garbage))
+            .repeated()
+            .collect::<Vec<_>>()
+            .map(|children| children.into_iter().flatten().collect::<Vec<_>>());
+
+        let opening = just("<")
+            .ignore_then(TagName::<Ext>::parser())
             .then(
                 text::whitespace().ignore_then(
                     Attribute::parser()
@@ -49,51 +79,135 @@ impl<Ext: External + 'static> Tag<Ext> {
                         .collect(),
                 ),
             )
-            // BUG: <A key=/> should be recoverable, but isn't:
-            //      tries to parse `/` as an expression.
             .then_ignore(text::whitespace())
-            .then(choice((
-                just("/>").ignored().map(|_| None),
-                just(">")
-                    .ignore_then(node.repeated().collect())
-                    .then(just("</").ignore_then(TagName::parser()))
-                    .then_ignore(just(">"))
-                    .map(|(children, name)| Some((children, name))),
-            )))
-            .try_map(|((name, attributes), enclosing), span| {
-                if let Some((children, name_end)) = enclosing {
-                    if name != name_end {
-                        return Err(UnclosedTag::new(span, name, name_end).into());
-                    }
-
-                    return Ok(Self::Enclosing(EnclosingTag {
-                        span,
-                        name,
-                        attributes,
-                        children,
-                    }));
+            .then(choice((just("/>").to(false), just(">").to(true))));
+
+        let closing_tag = just("</")
+            .ignore_then(TagName::<Ext>::parser())
+            .then_ignore(just(">"));
+
+        // Only a closing tag that's this tag's is consumed,
+        // any other is left for the tag it closes.
+        custom(move |stream| {
+            let ((name, attributes), enclosing) = stream.parse(&opening)?;
+            if !enclosing {
+                return Ok((name, attributes, None));
+            }
+
+            let outer = stream.ctx().clone();
+            let children =
+                stream.parse(children.clone().with_ctx(outer.in_tag(name.to_string())))?;
+
+            let before = stream.save();
+            let closing = match stream.parse(&closing_tag) {
+                Ok(closing) if closes(&name, &closing, &outer.tags) => Closing::Tag(closing),
+                _ => {
+                    stream.rewind(before);
+                    Closing::Implicit(stream.span(stream.offset()..stream.offset()))
                 }
+            };
 
-                Ok(Self::SelfClosing(SelfClosingTag {
+            Ok((name, attributes, Some((children, closing))))
+        })
+        .validate(|(name, attributes, enclosing), ctx, emitter| {
+            let span: Span = ctx.span();
+            let Some((children, closing)) = enclosing else {
+                return Self::SelfClosing(SelfClosingTag {
                     span,
                     name,
                     attributes,
-                }))
+                });
+            };
+
+            match closing {
+                Closing::Tag(closing) if closing != name => {
+                    emitter.emit(UnclosedTag::new(span, name.clone(), closing).into())
+                }
+                Closing::Tag(_) => {}
+                Closing::Implicit(at) => emitter.emit(ImplicitlyClosedTag::new(at, &name).into()),
+            }
+
+            Self::Enclosing(EnclosingTag {
+                span,
+                name,
+                attributes,
+                children,
             })
+        })
+    }
+}
+
+///
+/// How an enclosing tag's children end.
+///
+enum Closing<Ext: External> {
+    ///
+    /// With its closing tag, which may be misspelt.
+    ///
+    Tag(TagName<Ext>),
+
+    ///
+    /// Without one, at what closes its parent.
+    ///
+    Implicit(Span),
+}
+
+///
+/// If `closing` closes the tag `opening`, being its name,
+/// or a likely misspelling of it that isn't the name of any of its `ancestors`.
+///
+fn closes<Ext: External>(
+    opening: &TagName<Ext>,
+    closing: &TagName<Ext>,
+    ancestors: &[String],
+) -> bool {
+    if opening == closing {
+        return true;
+    }
+
+    !ancestors
+        .iter()
+        .any(|ancestor| closing == ancestor.as_str())
+        && suggest::closest(&closing.to_string(), [opening.to_string().as_str()]).is_some()
+}
+
+impl<Ext: External> Tag<Ext> {
+    pub fn name(&self) -> &TagName<Ext> {
+        match self {
+            Self::SelfClosing(tag) => &tag.name,
+            Self::Enclosing(tag) => &tag.name,
+        }
+    }
+
+    pub fn attributes(&self) -> &[Attribute<Ext>] {
+        match self {
+            Self::SelfClosing(tag) => &tag.attributes,
+            Self::Enclosing(tag) => &tag.attributes,
+        }
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct SelfClosingTag<Ext: External> {
     span: Span,
-    pub name: TagName,
+    pub name: TagName<Ext>,
     pub attributes: Vec<Attribute<Ext>>,
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct EnclosingTag<Ext: External> {
     span: Span,
-    pub name: TagName,
+    pub name: TagName<Ext>,
     pub attributes: Vec<Attribute<Ext>>,
     pub children: Vec<super::Node<Ext>>,
 }
@@ -102,6 +216,8 @@ pub struct EnclosingTag<Ext: External> {
 mod tests {
     use chumsky::Parser;
 
+    use std::assert_matches::assert_matches;
+
     use crate::{
         lexical::{number::NumberLit, Literal},
         ponyx::{
@@ -113,7 +229,7 @@ mod tests {
             TNode as Node,
         },
         syntax::{application::Application, parenthesized::Parenthesized, Expr, SoloExpr},
-        utils::{placeholder::Maybe, Parseable, SourceFile},
+        utils::{placeholder::Maybe, Error, Parseable, SourceFile, Spanned},
     };
 
     #[test]
@@ -181,4 +297,83 @@ mod tests {
                 )
         ))
     }
+
+    #[test]
+    fn missing_attribute_value() {
+        let (source, _) = SourceFile::test_file("<A key=/>");
+        let (node, errors) = Node::parser().parse(source.stream()).into_output_errors();
+
+        assert_matches!(
+            node,
+            Some(Node::Tag(Tag::SelfClosing(SelfClosingTag { attributes, .. })))
+                if matches!(
+                    attributes.as_slice(),
+                    [Attribute::KeyValue(AttributeAssignment { value: Maybe::Placeholder(_), .. })]
+                )
+        );
+        assert_matches!(errors.as_slice(), [Error::Expected(_)]);
+    }
+
+    #[test]
+    fn implicitly_closed() {
+        let (source, _) = SourceFile::test_file("<Row><Text>hi</Row>");
+        let (node, errors) = Node::parser().parse(source.stream()).into_output_errors();
+
+        assert_matches!(
+            node,
+            Some(Node::Tag(Tag::Enclosing(EnclosingTag { name, children, .. })))
+                if name == *"Row"
+                && matches!(
+                    children.as_slice(),
+                    [Node::Tag(Tag::Enclosing(EnclosingTag { name, children, .. }))]
+                        if name == *"Text" && children.len() == 1
+                )
+        );
+        assert_matches!(errors.as_slice(), [Error::ImplicitlyClosedTag(error)] if error.span().range() == (13..13));
+    }
+
+    #[test]
+    fn ancestor_closing_tag() {
+        // The closing tag of an ancestor is left for it, however close to this tag's name.
+        for (src, at) in [
+            ("<A><B></A>", 6),
+            ("<Row><Rows></Row>", 11),
+            ("<Tab><Tag></Tab>", 10),
+        ] {
+            let (source, _) = SourceFile::test_file(src);
+            let (node, errors) = Node::parser().parse(source.stream()).into_output_errors();
+
+            assert_matches!(
+                node,
+                Some(Node::Tag(Tag::Enclosing(EnclosingTag { children, .. })))
+                    if matches!(children.as_slice(), [Node::Tag(Tag::Enclosing(_))])
+            );
+            assert_matches!(errors.as_slice(), [Error::ImplicitlyClosedTag(error)] if error.span().range() == (at..at));
+        }
+    }
+
+    #[test]
+    fn misspelt_closing_tag() {
+        let (source, _) = SourceFile::test_file("<Row>hi</Rwo>");
+        let (node, errors) = Node::parser().parse(source.stream()).into_output_errors();
+
+        assert_matches!(node, Some(Node::Tag(Tag::Enclosing(_))));
+        assert_matches!(errors.as_slice(), [Error::UnclosedTag(_)]);
+    }
+
+    #[test]
+    fn garbage() {
+        let (source, _) = SourceFile::test_file("<Row>a } b<Text />c</Row>");
+        let (node, errors) = Node::parser().parse(source.stream()).into_output_errors();
+
+        assert_matches!(
+            node,
+            Some(Node::Tag(Tag::Enclosing(EnclosingTag { children, .. })))
+                if matches!(
+                    children.as_slice(),
+                    [Node::Text(_), Node::Tag(Tag::SelfClosing(_)), Node::Text(_)]
+                )
+        );
+        assert_eq!(errors.len(), 1);
+    }
 }
@@ -1,67 +1,218 @@
 //!
 //! ## PonyX Tag Name
 //!
-//! The intention is for this to be language dependent:
-//! * *TypeScript*: `Path.To.Component`
-//! * *Rust*: `path::to::Component`
+//! Tag names are language dependent
+//! (see [External::TAG_PATH_SEPARATOR], and [External::TAG_GENERICS_OPEN]):
+//! * *TypeScript*: `Path.To.Component`, `List<Item>`
+//! * *Rust*: `path::to::Component`, `List::<Item>`
+//!
+//! ```text
+//! tag_generics    := <generics_open> <tag_name> (`,` <tag_name>)* `>`
+//! tag_name        := <ident> (<path_separator> <ident>)* <tag_generics>?
+//! ```
 //!
 
-use std::fmt::Display;
+use std::{fmt::Display, marker::PhantomData};
 
-use avpony_macros::Spanned;
-use chumsky::{primitive::just, IterParser, Parser};
+use avpony_macros::{Spanned, Visit};
+use chumsky::{primitive::just, recursive::recursive, IterParser, Parser};
 
 use crate::{
     lexical,
+    syntax::external::External,
     utils::{ParseableCloned, PonyParser, Span},
 };
 
-#[derive(Debug, Clone, Spanned)]
-pub struct TagName {
+#[derive(Debug, Clone, Spanned, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
+pub struct TagName<Ext: External> {
     span: Span,
-    path: Vec<lexical::Identifier>,
+    pub path: Vec<lexical::Identifier>,
+    pub generics: Vec<TagName<Ext>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    __marker: PhantomData<Ext>,
 }
 
-impl Display for TagName {
+impl<Ext: External> Display for TagName<Ext> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.path
             .iter()
             .map(|a| a.value.as_str())
-            .intersperse(".")
-            .try_for_each(|a| write!(f, "{a}"))
+            .intersperse(Ext::TAG_PATH_SEPARATOR)
+            .try_for_each(|a| write!(f, "{a}"))?;
+
+        if self.generics.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, "{}", Ext::TAG_GENERICS_OPEN)?;
+        self.generics
+            .iter()
+            .map(|generic| generic.to_string())
+            .intersperse(", ".to_string())
+            .try_for_each(|a| write!(f, "{a}"))?;
+        write!(f, ">")
     }
 }
 
-impl PartialEq<TagName> for TagName {
-    fn eq(&self, other: &TagName) -> bool {
+impl<Ext: External> TagName<Ext> {
+    ///
+    /// This name's path, without generics,
+    /// e.g. `Path.To.Component` for `Path.To.Component<Item>`.
+    ///
+    pub fn path_string(&self) -> String {
         self.path
             .iter()
-            .zip(other.path.iter())
-            .all(|(i1, i2)| i1.same_name_as(i2))
+            .map(|a| a.value.as_str())
+            .intersperse(Ext::TAG_PATH_SEPARATOR)
+            .collect()
+    }
+
+    ///
+    /// Strip this name from the start of `src`, returning the rest,
+    /// with any whitespace around generics, as the parser allows.
+    ///
+    fn strip_from<'a>(&self, src: &'a str) -> Option<&'a str> {
+        let mut rest = src;
+        for (i, ident) in self.path.iter().enumerate() {
+            if i > 0 {
+                rest = rest.strip_prefix(Ext::TAG_PATH_SEPARATOR)?;
+            }
+            rest = rest.strip_prefix(ident.value.as_str())?;
+        }
+
+        if self.generics.is_empty() {
+            return Some(rest);
+        }
+
+        rest = rest.strip_prefix(Ext::TAG_GENERICS_OPEN)?;
+        for (i, generic) in self.generics.iter().enumerate() {
+            if i > 0 {
+                rest = rest.trim_start().strip_prefix(',')?;
+            }
+            rest = generic.strip_from(rest.trim_start())?;
+        }
+
+        rest.trim_start().strip_prefix('>')
     }
 }
 
-impl PartialEq<str> for TagName {
+impl<Ext: External> PartialEq<TagName<Ext>> for TagName<Ext> {
+    fn eq(&self, other: &TagName<Ext>) -> bool {
+        self.path.len() == other.path.len()
+            && self
+                .path
+                .iter()
+                .zip(other.path.iter())
+                .all(|(i1, i2)| i1.same_name_as(i2))
+            && self.generics == other.generics
+    }
+}
+
+impl<Ext: External> PartialEq<str> for TagName<Ext> {
     fn eq(&self, other: &str) -> bool {
-        self.path
-            .iter()
-            .zip(other.split("."))
-            .all(|(ident, p)| ident == p)
+        self.strip_from(other) == Some("")
     }
 }
 
-impl ParseableCloned for TagName {
+impl<Ext: External> ParseableCloned for TagName<Ext> {
     fn parser<'src>() -> impl PonyParser<'src, Self> + Clone {
-        lexical::Identifier::parser()
-            .then(
-                just(".")
-                    .ignore_then(lexical::Identifier::parser())
-                    .repeated()
-                    .collect::<Vec<_>>(),
-            )
-            .map_with(|(first, after), ctx| Self {
-                span: ctx.span(),
-                path: std::iter::once(first).chain(after).collect(),
-            })
+        recursive(|name| {
+            lexical::Identifier::parser()
+                .separated_by(just(Ext::TAG_PATH_SEPARATOR))
+                .at_least(1)
+                .collect::<Vec<_>>()
+                .then(
+                    name.separated_by(just(",").padded())
+                        .at_least(1)
+                        .collect::<Vec<_>>()
+                        .padded()
+                        .delimited_by(just(Ext::TAG_GENERICS_OPEN), just(">"))
+                        .or_not(),
+                )
+                .map_with(|(path, generics), ctx| Self {
+                    span: ctx.span(),
+                    path,
+                    generics: generics.unwrap_or_default(),
+                    __marker: PhantomData,
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::external::{rust::Rust, typescript::TypeScript},
+        utils::{Parseable, SourceFile},
+    };
+
+    use super::TagName;
+
+    #[test]
+    fn typescript_names() {
+        let (source, _) = SourceFile::test_file("Path.To.Component");
+        let res = TagName::<TypeScript>::parser().parse(source.stream());
+        assert!(matches!(
+            res.into_result(),
+            Ok(name) if name.path.len() == 3 && name == *"Path.To.Component"
+        ));
+
+        let (source, _) = SourceFile::test_file("List<Item, Map<Key, Value>>");
+        let res = TagName::<TypeScript>::parser().parse(source.stream());
+        assert!(matches!(
+            res.into_result(),
+            Ok(name) if name.generics.len() == 2 && name == *"List<Item, Map<Key, Value>>"
+        ));
+
+        let (source, _) = SourceFile::test_file("List<A,B>");
+        let res = TagName::<TypeScript>::parser().parse(source.stream());
+        assert!(matches!(
+            res.into_result(),
+            Ok(name) if name == *"List<A,B>" && name == *"List< A, B >" && name != *"List<A>"
+        ));
+    }
+
+    #[test]
+    fn rust_names() {
+        let (source, _) = SourceFile::test_file("path::to::Component");
+        let res = TagName::<Rust>::parser().parse(source.stream());
+        assert!(matches!(
+            res.into_result(),
+            Ok(name) if name.path.len() == 3 && name == *"path::to::Component"
+        ));
+
+        let (source, _) = SourceFile::test_file("ui::List::<Item>");
+        let res = TagName::<Rust>::parser().parse(source.stream());
+        assert!(matches!(
+            res.into_result(),
+            Ok(name) if name.path.len() == 2 && name == *"ui::List::<Item>"
+        ));
+
+        // TypeScript's separator means nothing in Rust.
+        let (source, _) = SourceFile::test_file("path.to.Component");
+        assert!(TagName::<Rust>::parser()
+            .parse(source.stream())
+            .has_errors());
+    }
+
+    #[test]
+    fn equality() {
+        let (source, _) = SourceFile::test_file("List::<Item>");
+        let a = TagName::<Rust>::parser().parse(source.stream()).unwrap();
+        let (source, _) = SourceFile::test_file("List");
+        let b = TagName::<Rust>::parser().parse(source.stream()).unwrap();
+        let (source, _) = SourceFile::test_file("List::Item");
+        let c = TagName::<Rust>::parser().parse(source.stream()).unwrap();
+
+        assert!(a != b);
+        assert!(b != c);
+        assert!(a == a.clone());
     }
 }
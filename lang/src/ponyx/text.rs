@@ -4,7 +4,7 @@
 //! Literally anything except any of `{}<>&`.
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{any, one_of},
     IterParser, Parser,
@@ -12,7 +12,8 @@ use chumsky::{
 
 use crate::utils::{ParseableCloned, PonyParser, Span};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct Text {
     span: Span,
     pub text: String,
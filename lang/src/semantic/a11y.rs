@@ -0,0 +1,236 @@
+//!
+//! ## Accessibility
+//!
+//! Lints tags for accessibility, with the `a11y:` directives:
+//! * Images without `a11y:alt` ([MissingAlt]);
+//! * Elements handling `on:click`, that aren't interactive already,
+//!   without an `a11y:role`, or `a11y:label` ([UnlabelledInteractive]);
+//! * The same `a11y:label` given to more than one element ([DuplicateLabel]);
+//! * Buttons with only whitespace inside, and no label ([EmptyButton]).
+//!
+//! These are all warnings.
+//!
+
+use std::collections::HashMap;
+
+use crate::{
+    lexical::Literal,
+    ponyx::{
+        tag::{
+            attribute::{Attribute, AttributeKey},
+            Tag,
+        },
+        Node,
+    },
+    syntax::{external::External, file::File, SoloExpr},
+    utils::{
+        error::a11y::{DuplicateLabel, EmptyButton, MissingAlt, UnlabelledInteractive},
+        placeholder::Maybe,
+        Error, Span, Spanned,
+    },
+    visit::{Visit, Visitable},
+};
+
+const A11Y: &str = "a11y";
+
+///
+/// Components showing an image, needing `a11y:alt`.
+///
+const IMAGES: &[&str] = &["Image", "Icon"];
+
+///
+/// Components already interactive, with their own role.
+///
+const INTERACTIVE: &[&str] = &["Button", "Link", "Checkbox", "Input"];
+
+const BUTTON: &str = "Button";
+
+///
+/// Lint every tag of `file` for accessibility, returning any warnings.
+///
+pub fn lint<Ext: External>(file: &File<Ext>) -> Vec<Error> {
+    let mut linter = Linter::default();
+    file.pony.visit(&mut linter);
+
+    linter.errors
+}
+
+#[derive(Default)]
+struct Linter {
+    ///
+    /// Where each `a11y:label` was first given.
+    ///
+    labels: HashMap<String, Span>,
+    errors: Vec<Error>,
+}
+
+///
+/// The attribute `base:director` of `tag`, if given.
+///
+fn directive<'a, Ext: External>(
+    tag: &'a Tag<Ext>,
+    base: &str,
+    director: &str,
+) -> Option<&'a Attribute<Ext>> {
+    tag.attributes().iter().find(|attribute| {
+        matches!(
+            attribute.key(),
+            AttributeKey::Directive(directive)
+                if directive.base == *base
+                && matches!(&directive.director, Maybe::Present(ident) if ident == director)
+        )
+    })
+}
+
+///
+/// The named attribute `name` of `tag`, if given.
+///
+fn named<'a, Ext: External>(tag: &'a Tag<Ext>, name: &str) -> Option<&'a Attribute<Ext>> {
+    tag.attributes()
+        .iter()
+        .find(|attribute| matches!(attribute.key(), AttributeKey::Named(ident) if ident == name))
+}
+
+///
+/// If `nodes` have no content, only whitespace, or comments.
+///
+fn is_empty<Ext: External>(nodes: &[Node<Ext>]) -> bool {
+    nodes.iter().all(|node| match node {
+        Node::Text(text) => text.text.trim().is_empty(),
+        Node::Comment(_) => true,
+        _ => false,
+    })
+}
+
+impl<Ext: External> Visit<Ext> for Linter {
+    fn visit_tag(&mut self, tag: &Tag<Ext>) {
+        let path = tag.name().path_string();
+        let component = tag.name().path.first().map(|ident| ident.value.as_str());
+        let name = tag.name().span();
+
+        if IMAGES.contains(&path.as_str()) && directive(tag, A11Y, "alt").is_none() {
+            self.errors.push(MissingAlt::new(name, path.clone()).into());
+        }
+
+        let label = directive(tag, A11Y, "label");
+        let interactive = component.is_some_and(|component| INTERACTIVE.contains(&component));
+        if !interactive
+            && directive(tag, "on", "click").is_some()
+            && directive(tag, A11Y, "role").is_none()
+            && label.is_none()
+        {
+            self.errors
+                .push(UnlabelledInteractive::new(name, path.clone()).into());
+        }
+
+        if let Some(Maybe::Present(SoloExpr::Literal(Literal::String(text)))) =
+            label.and_then(Attribute::value)
+        {
+            match self.labels.get(&text.value) {
+                Some(first) => self
+                    .errors
+                    .push(DuplicateLabel::new(text.span(), text.value.clone(), *first).into()),
+                None => {
+                    self.labels.insert(text.value.clone(), text.span());
+                }
+            }
+        }
+
+        let empty = match tag {
+            Tag::SelfClosing(_) => true,
+            Tag::Enclosing(tag) => is_empty(&tag.children),
+        };
+        if path == BUTTON && empty && label.is_none() && named(tag, "label").is_none() {
+            self.errors.push(EmptyButton::new(tag.span()).into());
+        }
+
+        tag.visit_children(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        lint::{default_level, Level},
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Error, Parseable, SourceFile, Spanned},
+    };
+
+    use super::lint;
+
+    ///
+    /// The kind, and source of each warning of `src`.
+    ///
+    fn check(src: &str) -> Vec<(&'static str, &str)> {
+        let (source, _) = SourceFile::test_file(src);
+        let (file, errors) = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        lint(&file.unwrap())
+            .into_iter()
+            .map(|error| {
+                assert_eq!(default_level(&error), Level::Warn);
+                let kind = match error {
+                    Error::MissingAlt(_) => "alt",
+                    Error::UnlabelledInteractive(_) => "interactive",
+                    Error::DuplicateLabel(_) => "label",
+                    Error::EmptyButton(_) => "button",
+                    _ => "other",
+                };
+
+                (kind, &src[error.span().range()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn accessible() {
+        let src = r#"function go() {}
+---
+<Column>
+    <Image src="frog.png" a11y:alt="A frog, dancing." />
+    <Image src="border.png" a11y:alt="" />
+    <Button on:click={go}>Go!</Button>
+    <Button label="Stop" on:click={go} />
+    <Button a11y:label="Close" on:click={go}>  </Button>
+    <Row on:click={go} a11y:role="button" a11y:label="Open">Open</Row>
+    <Input.TextBox on:click={go} />
+</Column>
+"#;
+        assert_eq!(check(src), []);
+    }
+
+    #[test]
+    fn inaccessible() {
+        let src = r#"function go() {}
+---
+<Column>
+    <Image src="frog.png" />
+    <Row on:click={go}>Open</Row>
+    <Button a11y:label="Close" on:click={go}>X</Button>
+    <Button a11y:label="Close" on:click={go}>x</Button>
+    <Button on:click={go}>
+        <!-- TODO -->
+    </Button>
+    <Button />
+</Column>
+"#;
+        assert_eq!(
+            check(src),
+            [
+                ("alt", "Image"),
+                ("interactive", "Row"),
+                ("label", r#""Close""#),
+                (
+                    "button",
+                    "<Button on:click={go}>\n        <!-- TODO -->\n    </Button>"
+                ),
+                ("button", "<Button />"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,398 @@
+//!
+//! ## Component Validation
+//!
+//! Checks every tag against the [schema](super::schema) of its component,
+//! reporting:
+//! * Components which aren't in the registry ([UnknownComponent]);
+//! * Attributes, or directives they don't accept ([UnknownAttribute]);
+//! * Attributes given more than once ([DuplicateAttribute]);
+//! * Required attributes which aren't given ([MissingAttribute]);
+//! * Children they don't allow ([InvalidChild]), looking through logic blocks;
+//! * Children given a `slot` they don't have ([UnknownSlot]);
+//! * Values not of their attribute's [type](super::types) ([TypeMismatch]).
+//!
+//! Components are named by their path, without generics,
+//! e.g. `List` for `<List<Item>>`.
+//!
+
+use std::collections::HashMap;
+
+use crate::{
+    lexical::Literal,
+    ponyx::{
+        tag::{attribute::AttributeKey, Tag},
+        Node,
+    },
+    syntax::{external::External, file::File, Expr, SoloExpr},
+    utils::{
+        error::component::{
+            DuplicateAttribute, InvalidChild, MissingAttribute, UnknownAttribute, UnknownComponent,
+            UnknownSlot,
+        },
+        error::types::TypeMismatch,
+        placeholder::Maybe,
+        suggest, Error, Span, Spanned,
+    },
+    visit::{Visit, Visitable},
+};
+
+use super::{
+    schema::{Children, Component, Registry},
+    types::{self, Type},
+};
+
+///
+/// Attribute accepted by every component,
+/// naming the slot of its parent it goes in.
+///
+const SLOT: &str = "slot";
+
+///
+/// Validate every tag of `file` against `registry`, returning any errors.
+///
+pub fn validate<Ext: External>(file: &File<Ext>, registry: &Registry) -> Vec<Error> {
+    let mut validator = Validator {
+        registry,
+        errors: Vec::new(),
+    };
+
+    file.pony.visit(&mut validator);
+
+    validator.errors
+}
+
+struct Validator<'a> {
+    registry: &'a Registry,
+    errors: Vec<Error>,
+}
+
+impl Validator<'_> {
+    fn attributes<Ext: External>(&mut self, tag: &Tag<Ext>, component: Option<&Component>) {
+        let mut given: HashMap<String, Span> = HashMap::new();
+
+        for attribute in tag.attributes() {
+            let key = attribute.key();
+            let name = key_name(key);
+
+            if let Some(first) = given.get(&name) {
+                self.errors
+                    .push(DuplicateAttribute::new(key.span(), name, *first).into());
+                continue;
+            }
+            given.insert(name.clone(), key.span());
+
+            let Some(component) = component else {
+                continue;
+            };
+
+            let prop = match key {
+                AttributeKey::Named(ident) if ident.value == SLOT => continue,
+                AttributeKey::Named(ident) => component.attributes.get(&ident.value),
+                AttributeKey::Directive(directive)
+                    if component.directives.contains(&directive.base.value) =>
+                {
+                    continue
+                }
+                AttributeKey::Directive(_) => None,
+            };
+
+            let Some(prop) = prop else {
+                self.errors
+                    .push(UnknownAttribute::new(key.span(), name, component.name.clone()).into());
+                continue;
+            };
+
+            match attribute.value() {
+                Some(Maybe::Present(value)) => self
+                    .errors
+                    .extend(types::check(&Expr::from(value.clone()), &prop.ty)),
+                // `<Button disabled />` is short for `disabled=true`.
+                None if !Type::Bool.matches(&prop.ty) => self
+                    .errors
+                    .push(TypeMismatch::new(key.span(), prop.ty.clone(), Type::Bool).into()),
+                _ => (),
+            }
+        }
+
+        let Some(component) = component else {
+            return;
+        };
+
+        for (name, _) in component
+            .attributes
+            .iter()
+            .filter(|(_, prop)| prop.required)
+        {
+            if !given.contains_key(name) {
+                self.errors.push(
+                    MissingAttribute::new(tag.name().span(), name.clone(), component.name.clone())
+                        .into(),
+                );
+            }
+        }
+    }
+
+    ///
+    /// Check the children of `parent` (looking through logic blocks)
+    /// are allowed by it, and given only its slots.
+    ///
+    fn children<Ext: External>(&mut self, parent: &Component, nodes: &[Node<Ext>]) {
+        for node in nodes {
+            let (span, child) = match node {
+                Node::Comment(_) | Node::Statement(_) => continue,
+                Node::Text(text) if text.text.trim().is_empty() => continue,
+                Node::Block(_) => {
+                    for nodes in node.children() {
+                        self.children(parent, nodes);
+                    }
+                    continue;
+                }
+                Node::Tag(tag) => {
+                    self.slot(parent, tag);
+                    (
+                        tag.name().span(),
+                        format!("`<{}>`", tag.name().path_string()),
+                    )
+                }
+                Node::Text(_) => (node.span(), "Text".to_string()),
+                Node::Entity(_) => (node.span(), "This entity".to_string()),
+                Node::Mustache(_) => (node.span(), "This expression".to_string()),
+            };
+
+            let allowed = match &parent.children {
+                Children::Any => true,
+                Children::None => false,
+                Children::Only(names) => {
+                    matches!(node, Node::Tag(tag) if names.contains(&tag.name().path_string()))
+                }
+            };
+
+            if !allowed {
+                let only = match &parent.children {
+                    Children::Only(names) => names.iter().cloned().collect(),
+                    _ => Vec::new(),
+                };
+
+                self.errors
+                    .push(InvalidChild::new(span, child, parent.name.clone(), only).into());
+            }
+        }
+    }
+
+    ///
+    /// Check the `slot="..."` of `tag` (if any) is one of `parent`'s.
+    ///
+    fn slot<Ext: External>(&mut self, parent: &Component, tag: &Tag<Ext>) {
+        let slot = tag
+            .attributes()
+            .iter()
+            .filter(
+                |attribute| matches!(attribute.key(), AttributeKey::Named(ident) if ident == SLOT),
+            )
+            .find_map(|attribute| match attribute.value() {
+                Some(Maybe::Present(SoloExpr::Literal(Literal::String(slot)))) => Some(slot),
+                _ => None,
+            });
+
+        if let Some(slot) = slot.filter(|slot| !parent.slots.contains(&slot.value)) {
+            self.errors.push(
+                UnknownSlot::new(slot.span(), slot.value.clone(), parent.name.clone()).into(),
+            );
+        }
+    }
+}
+
+///
+/// The name of an attribute, as written, e.g. `label`, or `on:click`.
+///
+fn key_name(key: &AttributeKey) -> String {
+    match key {
+        AttributeKey::Named(ident) => ident.value.clone(),
+        AttributeKey::Directive(directive) => match &directive.director {
+            Maybe::Present(director) => format!("{}:{}", directive.base.value, director.value),
+            Maybe::Placeholder(_) => format!("{}:", directive.base.value),
+        },
+    }
+}
+
+impl<Ext: External> Visit<Ext> for Validator<'_> {
+    fn visit_tag(&mut self, tag: &Tag<Ext>) {
+        let registry = self.registry;
+        let name = tag.name().path_string();
+        let component = registry.get(&name);
+
+        if component.is_none() {
+            let suggestion = suggest::closest(&name, registry.names()).map(str::to_string);
+            self.errors
+                .push(UnknownComponent::new(tag.name().span(), name, suggestion).into());
+        }
+
+        self.attributes(tag, component);
+
+        if let (Some(component), Tag::Enclosing(tag)) = (component, tag) {
+            self.children(component, &tag.children);
+        }
+
+        tag.visit_children(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        semantic::{
+            schema::{Children, Component, Prop, Registry},
+            types::Type,
+        },
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Error, Parseable, SourceFile, Spanned},
+    };
+
+    use super::validate;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry
+            .add(
+                Component::new("Column")
+                    .attribute(
+                        "gap",
+                        Prop {
+                            required: false,
+                            ty: Type::Length,
+                        },
+                    )
+                    .slot("footer"),
+            )
+            .add(
+                Component::new("Button")
+                    .attribute(
+                        "label",
+                        Prop {
+                            required: true,
+                            ty: Type::String,
+                        },
+                    )
+                    .attribute(
+                        "disabled",
+                        Prop {
+                            required: false,
+                            ty: Type::Bool,
+                        },
+                    )
+                    .directive("on")
+                    .children(Children::None),
+            )
+            .add(Component::new("List").children(Children::Only(["Item".to_string()].into())))
+            .add(Component::new("Item"))
+            .add(Component::new("Input.TextBox").directive("bind"));
+        registry
+    }
+
+    ///
+    /// The kind, and source of each error of `src`.
+    ///
+    fn check(src: &str) -> Vec<(&'static str, &str)> {
+        let (source, _) = SourceFile::test_file(src);
+        let (file, errors) = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        validate(&file.unwrap(), &registry())
+            .into_iter()
+            .map(|error| {
+                let kind = match error {
+                    Error::UnknownComponent(_) => "component",
+                    Error::UnknownAttribute(_) => "attribute",
+                    Error::DuplicateAttribute(_) => "duplicate",
+                    Error::MissingAttribute(_) => "missing",
+                    Error::InvalidChild(_) => "child",
+                    Error::UnknownSlot(_) => "slot",
+                    Error::TypeMismatch(_) => "type",
+                    Error::UnknownUnit(_) => "unit",
+                    _ => "other",
+                };
+
+                (kind, &src[error.span().range()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid() {
+        let src = r#"let value = "";
+function go() {}
+---
+<Column gap=(2pt)>
+    <!-- A comment. -->
+    <Button label="Go" disabled on:click={go} />
+    <Button label={value} disabled=false />
+    <Input.TextBox bind:value={value} />
+    <List>
+        <Item />
+        {#if value}
+            <Item>{value}</Item>
+        {/if}
+    </List>
+    <Button label="Done" slot="footer" />
+</Column>
+"#;
+        assert_eq!(check(src), []);
+    }
+
+    #[test]
+    fn invalid() {
+        let src = r#"let value = "";
+---
+<Column gap=(2pt) gap=(3pt) size=4>
+    <Image />
+    <Button bind:label={value} />
+    <Button label="Go" slot="header">Go!</Button>
+    <List>
+        {#if value}
+            <Column />
+        {/if}
+        {value}
+    </List>
+</Column>
+"#;
+        assert_eq!(
+            check(src),
+            [
+                ("duplicate", "gap"),
+                ("attribute", "size"),
+                ("slot", r#""header""#),
+                ("component", "Image"),
+                ("attribute", "bind:label"),
+                ("missing", "Button"),
+                ("child", "Go!"),
+                ("child", "Column"),
+                ("child", "{value}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn types() {
+        let src = r#"let value = "";
+---
+<Column gap=(2pz)>
+    <Button label=5 disabled="no" />
+    <Button label={value} disabled />
+    <Column gap />
+</Column>
+"#;
+        assert_eq!(
+            check(src),
+            [
+                ("unit", "pz"),
+                ("type", "5"),
+                ("type", r#""no""#),
+                ("type", "gap"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//!
+//! ## Semantic Analysis
+//!
+//! Checks of parsed files beyond their syntax:
+//! * [scope] -- names which aren't in scope, shadowed, or unused
+//!   (by [External::check](crate::syntax::external::External::check));
+//! * [a11y] -- tags which aren't accessible.
+//!
+//! External languages supported by [External::check](crate::syntax::external::External::check):
+//! * TypeScript.
+//!
+//! Files can also be [validated](components::validate) against
+//! the [schemas](schema) of the components they use, in any language,
+//! including the [types] of the values given to their attributes.
+//!
+//! Each of these is run as a [lint](crate::lint), by a [Linter](crate::lint::Linter),
+//! whose rules can be configured, or suppressed.
+//!
+
+pub mod a11y;
+pub mod components;
+pub mod schema;
+pub mod scope;
+pub mod types;
+
@@ -0,0 +1,372 @@
+//!
+//! ## Component Schemas
+//!
+//! Declares which components exist, and how they're used,
+//! for [validation](super::components):
+//! * Their named attributes, and which are required;
+//! * The namespaces of directives they accept, like `on`, for `on:click`;
+//! * Which children they allow, and the slots those can be given.
+//!
+//! Schemas are built in Rust, or read from a JSON manifest:
+//! ```json
+//! {
+//!     "components": {
+//!         "Button": {
+//!             "attributes": {
+//!                 "label": { "type": "string", "required": true },
+//!                 "padding": { "type": { "tuple": ["length", "length"] } },
+//!                 "disabled": {}
+//!             },
+//!             "directives": ["on"],
+//!             "children": "none"
+//!         },
+//!         "Dialog": {
+//!             "attributes": { "title": {} },
+//!             "children": ["Text", "Button"],
+//!             "slots": ["footer"]
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! `children` is one of `"any"` (the default), `"none"`,
+//! or the names of the only components allowed.
+//!
+//! Attributes' `type`s are written as the name of a [Type], like `"length"`,
+//! or `{ "list": <type> }`, `{ "tuple": [<type>...] }`, `{ "record": { <field>: <type> } }`;
+//! without one, an attribute accepts any value.
+//!
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde_json::Value;
+
+use super::types::Type;
+
+///
+/// The schemas of every known component, by name.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Registry {
+    components: HashMap<String, Component>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    ///
+    /// Add (or replace) the schema of a component.
+    ///
+    pub fn add(&mut self, component: Component) -> &mut Self {
+        self.components.insert(component.name.clone(), component);
+        self
+    }
+
+    ///
+    /// The schema of the component called `name`,
+    /// written as its path, e.g. `Input.TextBox`.
+    ///
+    pub fn get(&self, name: &str) -> Option<&Component> {
+        self.components.get(name)
+    }
+
+    ///
+    /// The names of every component, in no particular order.
+    ///
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.components.keys().map(String::as_str)
+    }
+
+    ///
+    /// Read a registry from a JSON manifest (see [the module](self)).
+    ///
+    pub fn from_json(src: &str) -> Result<Self, String> {
+        let manifest: Value = serde_json::from_str(src).map_err(|err| err.to_string())?;
+        let components = manifest
+            .get("components")
+            .and_then(Value::as_object)
+            .ok_or("expected an object of `components`")?;
+
+        let mut registry = Self::new();
+        for (name, schema) in components {
+            registry.add(
+                Component::from_json(name, schema)
+                    .map_err(|err| format!("in component `{name}`: {err}"))?,
+            );
+        }
+
+        Ok(registry)
+    }
+}
+
+///
+/// The schema of a single component.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    pub name: String,
+    pub attributes: BTreeMap<String, Prop>,
+
+    ///
+    /// Namespaces of the directives accepted, e.g. `on`, or `bind`.
+    ///
+    pub directives: BTreeSet<String>,
+    pub children: Children,
+
+    ///
+    /// Names of the slots children can be given, with `slot="..."`.
+    ///
+    pub slots: BTreeSet<String>,
+}
+
+impl Component {
+    ///
+    /// A component without any attributes, or slots,
+    /// allowing any children.
+    ///
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            attributes: BTreeMap::new(),
+            directives: BTreeSet::new(),
+            children: Children::Any,
+            slots: BTreeSet::new(),
+        }
+    }
+
+    pub fn attribute(mut self, name: impl Into<String>, prop: Prop) -> Self {
+        self.attributes.insert(name.into(), prop);
+        self
+    }
+
+    pub fn directive(mut self, namespace: impl Into<String>) -> Self {
+        self.directives.insert(namespace.into());
+        self
+    }
+
+    pub fn children(mut self, children: Children) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn slot(mut self, name: impl Into<String>) -> Self {
+        self.slots.insert(name.into());
+        self
+    }
+
+    fn from_json(name: &str, schema: &Value) -> Result<Self, String> {
+        let mut component = Self::new(name);
+
+        if let Some(attributes) = schema.get("attributes") {
+            let attributes = attributes
+                .as_object()
+                .ok_or("expected an object of `attributes`")?;
+
+            for (name, prop) in attributes {
+                let required = match prop.get("required") {
+                    None => false,
+                    Some(required) => required
+                        .as_bool()
+                        .ok_or_else(|| format!("expected `{name}.required` to be a boolean"))?,
+                };
+
+                let ty = match prop.get("type") {
+                    None => Type::Opaque,
+                    Some(ty) => {
+                        type_from_json(ty).map_err(|err| format!("in `{name}.type`: {err}"))?
+                    }
+                };
+
+                component = component.attribute(name, Prop { required, ty });
+            }
+        }
+
+        if let Some(directives) = schema.get("directives") {
+            component.directives = strings(directives, "directives")?;
+        }
+
+        if let Some(slots) = schema.get("slots") {
+            component.slots = strings(slots, "slots")?;
+        }
+
+        component.children = match schema.get("children") {
+            None => Children::Any,
+            Some(Value::String(children)) if children == "any" => Children::Any,
+            Some(Value::String(children)) if children == "none" => Children::None,
+            Some(children @ Value::Array(_)) => Children::Only(strings(children, "children")?),
+            Some(_) => {
+                return Err(
+                    "expected `children` to be `\"any\"`, `\"none\"`, or an array of names"
+                        .to_string(),
+                )
+            }
+        };
+
+        Ok(component)
+    }
+}
+
+fn type_from_json(ty: &Value) -> Result<Type, String> {
+    let fields = match ty {
+        Value::String(name) => {
+            return match name.as_str() {
+                "number" => Ok(Type::Number),
+                "string" => Ok(Type::String),
+                "bool" => Ok(Type::Bool),
+                "length" => Ok(Type::Length),
+                "color" => Ok(Type::Color),
+                "opaque" => Ok(Type::Opaque),
+                _ => Err(format!("unknown type `{name}`")),
+            }
+        }
+        Value::Object(fields) if fields.len() == 1 => fields,
+        _ => {
+            return Err(
+                "expected the name of a type, or an object of a `list`, `tuple`, or `record`"
+                    .to_string(),
+            )
+        }
+    };
+
+    match fields.iter().next().unwrap() {
+        (kind, item) if kind == "list" => Ok(Type::List(Box::new(type_from_json(item)?))),
+        (kind, Value::Array(items)) if kind == "tuple" => Ok(Type::Tuple(
+            items.iter().map(type_from_json).collect::<Result<_, _>>()?,
+        )),
+        (kind, Value::Object(fields)) if kind == "record" => Ok(Type::Record(
+            fields
+                .iter()
+                .map(|(name, ty)| Ok((name.clone(), type_from_json(ty)?)))
+                .collect::<Result<_, String>>()?,
+        )),
+        (kind, _) if ["tuple", "record"].contains(&kind.as_str()) => {
+            Err(format!("malformed `{kind}` type"))
+        }
+        (kind, _) => Err(format!("unknown type `{kind}`")),
+    }
+}
+
+fn strings(value: &Value, field: &str) -> Result<BTreeSet<String>, String> {
+    value
+        .as_array()
+        .and_then(|values| {
+            values
+                .iter()
+                .map(|value| value.as_str().map(str::to_string))
+                .collect()
+        })
+        .ok_or_else(|| format!("expected `{field}` to be an array of strings"))
+}
+
+///
+/// A named attribute of a component.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Prop {
+    pub required: bool,
+
+    ///
+    /// The type of values given to it
+    /// (as [Type::Opaque], any value).
+    ///
+    pub ty: Type,
+}
+
+///
+/// Which children a component allows.
+///
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Children {
+    #[default]
+    Any,
+
+    ///
+    /// No children at all, except whitespace, and comments.
+    ///
+    None,
+
+    ///
+    /// Only these components (and whitespace, comments, or logic blocks of them).
+    ///
+    Only(BTreeSet<String>),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::semantic::types::Type;
+
+    use super::{Children, Component, Prop, Registry};
+
+    #[test]
+    fn manifest() {
+        let registry = Registry::from_json(
+            r#"{
+                "components": {
+                    "Button": {
+                        "attributes": {
+                            "label": { "type": "string", "required": true },
+                            "padding": { "type": { "tuple": ["length", { "list": "number" }] } },
+                            "disabled": {}
+                        },
+                        "directives": ["on"],
+                        "children": "none"
+                    },
+                    "Dialog": { "children": ["Text", "Button"], "slots": ["footer"] },
+                    "Input.TextBox": {}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            registry.get("Button"),
+            Some(
+                &Component::new("Button")
+                    .attribute(
+                        "label",
+                        Prop {
+                            required: true,
+                            ty: Type::String
+                        }
+                    )
+                    .attribute(
+                        "padding",
+                        Prop {
+                            required: false,
+                            ty: Type::Tuple(vec![Type::Length, Type::List(Box::new(Type::Number))])
+                        }
+                    )
+                    .attribute("disabled", Prop::default())
+                    .directive("on")
+                    .children(Children::None)
+            )
+        );
+        assert_eq!(
+            registry.get("Dialog"),
+            Some(
+                &Component::new("Dialog")
+                    .children(Children::Only(["Text", "Button"].map(String::from).into()))
+                    .slot("footer")
+            )
+        );
+        assert_eq!(
+            registry.get("Input.TextBox"),
+            Some(&Component::new("Input.TextBox"))
+        );
+        assert_eq!(registry.get("Image"), None);
+
+        assert!(Registry::from_json("{}").is_err());
+        assert!(Registry::from_json(r#"{ "components": { "A": { "children": 2 } } }"#).is_err());
+        assert!(Registry::from_json(r#"{ "components": { "A": { "slots": [1] } } }"#).is_err());
+        assert!(Registry::from_json(
+            r#"{ "components": { "A": { "attributes": { "b": { "type": "int" } } } } }"#
+        )
+        .is_err());
+        assert!(Registry::from_json(
+            r#"{ "components": { "A": { "attributes": { "b": { "type": { "tuple": "length" } } } } } }"#
+        )
+        .is_err());
+    }
+}
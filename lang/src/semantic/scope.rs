@@ -0,0 +1,730 @@
+//!
+//! ## Scopes
+//!
+//! Resolves the names used by PonyX (in mustaches, attribute values,
+//! and logic blocks) to what binds them:
+//! * Declarations of the module, for the whole file;
+//! * `{#for <ident> in ...}`, for the loop's children (and key);
+//! * `{:then <ident>}`, and `{:catch <ident>}`, for their branch;
+//! * `{:default <ident>}`, for its arm;
+//! * `{@let ...}`, and `{@const ...}`, for the nodes after them
+//!   in the same tag, or branch.
+//!
+//! Reports names which aren't bound ([UnresolvedIdentifier]), bindings
+//! shadowing another ([ShadowedIdentifier]), and loop, or await bindings
+//! which are never used ([UnusedBinding]), unless prefixed with `_`.
+//!
+//! Tag names aren't resolved, as they may be built-in components,
+//! and neither are the [units](UNITS) of lengths, or the functions
+//! building [colors](COLORS), like `pt` in `5pt`, and `rgb` in `rgb(1, 2, 3)`.
+//!
+
+use std::collections::HashSet;
+
+use chumsky::span::Span as _;
+use swc_ecma_ast as ast;
+use swc_ecma_visit::{self as ecma_visit, VisitWith};
+
+use crate::{
+    lexical::Literal,
+    ponyx::{
+        blocks::{
+            await_block::{AwaitBlock, Branch},
+            for_block::ForBlock,
+            if_block::{ElseBranch, ElseIfBranch, IfBranch},
+            key_block::KeyBlock,
+            match_block::{DefaultArm, WhenArm},
+        },
+        statement::AtStatement,
+        tag::{name::TagName, EnclosingTag},
+        Node,
+    },
+    semantic::types::{COLORS, HEX, UNITS},
+    syntax::{
+        external::{typescript::TypeScript, ExternalExpr},
+        file::File,
+        Expr, SoloExpr,
+    },
+    utils::{
+        error::scope::{ShadowedIdentifier, UnresolvedIdentifier, UnusedBinding},
+        placeholder::Maybe,
+        suggest, Error, FileId, Span, Spanned,
+    },
+    visit::{Visit, Visitable},
+};
+
+///
+/// Names always in scope, from the JavaScript runtime.
+///
+const GLOBALS: &[&str] = &[
+    "undefined",
+    "NaN",
+    "Infinity",
+    "globalThis",
+    "console",
+    "Array",
+    "ArrayBuffer",
+    "BigInt",
+    "Boolean",
+    "DataView",
+    "Date",
+    "Error",
+    "Float32Array",
+    "Float64Array",
+    "Function",
+    "Int8Array",
+    "Int16Array",
+    "Int32Array",
+    "Intl",
+    "JSON",
+    "Map",
+    "Math",
+    "Number",
+    "Object",
+    "Promise",
+    "Proxy",
+    "RangeError",
+    "Reflect",
+    "RegExp",
+    "Set",
+    "String",
+    "Symbol",
+    "TypeError",
+    "Uint8Array",
+    "Uint16Array",
+    "Uint32Array",
+    "URL",
+    "WeakMap",
+    "WeakSet",
+    "clearInterval",
+    "clearTimeout",
+    "decodeURIComponent",
+    "encodeURIComponent",
+    "fetch",
+    "isFinite",
+    "isNaN",
+    "parseFloat",
+    "parseInt",
+    "queueMicrotask",
+    "setInterval",
+    "setTimeout",
+    "structuredClone",
+];
+
+///
+/// What introduced a binding.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Module,
+    Loop,
+    Await,
+    Arm,
+    Declaration,
+}
+
+#[derive(Debug)]
+struct Binding {
+    name: String,
+    span: Span,
+    kind: Kind,
+    used: bool,
+}
+
+///
+/// Resolve the names used in `file`, returning any errors.
+///
+pub fn resolve(file: &File<TypeScript>) -> Vec<Error> {
+    let mut resolver = Resolver {
+        file: file.span().file(),
+        scopes: vec![Vec::new()],
+        errors: Vec::new(),
+    };
+
+    let mut declared = Declared {
+        block_scoped: true,
+        ..Default::default()
+    };
+    file.module.visit_with(&mut declared);
+    for ident in declared.idents {
+        resolver.declare(&ident.sym, resolver.span(ident.span), Kind::Module);
+    }
+
+    file.pony.visit(&mut resolver);
+    resolver.exit();
+
+    resolver.errors
+}
+
+struct Resolver {
+    file: FileId,
+
+    ///
+    /// Bindings of every scope around the current node, innermost last.
+    ///
+    scopes: Vec<Vec<Binding>>,
+    errors: Vec<Error>,
+}
+
+impl Resolver {
+    fn span(&self, span: swc_common::Span) -> Span {
+        Span::new(self.file, span.lo.0 as usize..span.hi.0 as usize)
+    }
+
+    fn lookup(&mut self, name: &str) -> Option<&mut Binding> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.iter_mut().rev().find(|binding| binding.name == name))
+    }
+
+    fn declare(&mut self, name: &str, span: Span, kind: Kind) {
+        // Redeclarations in the module are for TypeScript to check.
+        if kind != Kind::Module {
+            if let Some(shadowed) = self.lookup(name) {
+                let shadowed = shadowed.span;
+                self.errors
+                    .push(ShadowedIdentifier::new(span, name.to_string(), shadowed).into());
+            }
+        }
+
+        self.scopes.last_mut().unwrap().push(Binding {
+            name: name.to_string(),
+            span,
+            kind,
+            used: false,
+        });
+    }
+
+    fn reference(&mut self, name: &str, span: Span) {
+        if let Some(binding) = self.lookup(name) {
+            binding.used = true;
+        } else if !GLOBALS.contains(&name) {
+            let in_scope = self
+                .scopes
+                .iter()
+                .flatten()
+                .map(|binding| binding.name.as_str());
+            let suggestion = suggest::closest(name, in_scope).map(str::to_string);
+            self.errors
+                .push(UnresolvedIdentifier::new(span, name.to_string(), suggestion).into());
+        }
+    }
+
+    fn enter(&mut self) {
+        self.scopes.push(Vec::new());
+    }
+
+    fn exit(&mut self) {
+        let unused = self
+            .scopes
+            .pop()
+            .into_iter()
+            .flatten()
+            .filter(|binding| matches!(binding.kind, Kind::Loop | Kind::Await))
+            .filter(|binding| !binding.used && !binding.name.starts_with('_'));
+
+        for binding in unused {
+            self.errors
+                .push(UnusedBinding::new(binding.span, binding.name).into());
+        }
+    }
+
+    ///
+    /// Nodes in a scope of their own.
+    ///
+    fn nodes(&mut self, nodes: &[Node<TypeScript>]) {
+        self.enter();
+        nodes.iter().for_each(|node| node.visit(self));
+        self.exit();
+    }
+
+    fn external(&mut self, expr: &Maybe<ast::Expr>) {
+        if let Maybe::Present(expr) = expr {
+            self.references(expr);
+        }
+    }
+
+    fn references(&mut self, node: &impl VisitWith<References>) {
+        let mut references = References::default();
+        node.visit_with(&mut references);
+
+        for ident in references.found {
+            self.reference(&ident.sym, self.span(ident.span));
+        }
+    }
+}
+
+impl Visit<TypeScript> for Resolver {
+    fn visit_external_expr(&mut self, expr: &ExternalExpr<TypeScript>) {
+        self.external(&expr.expr);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr<TypeScript>) {
+        match expr {
+            Expr::Identifier(ident) => self.reference(&ident.value, ident.span()),
+            Expr::Application(application) => {
+                match (&*application.function, &*application.argument) {
+                    (Expr::Literal(Literal::Number(_)), Expr::Identifier(unit))
+                        if UNITS.contains(&unit.value.as_str()) => {}
+                    (Expr::Identifier(function), argument)
+                        if COLORS.contains(&function.value.as_str()) || function.value == HEX =>
+                    {
+                        self.visit_expr(argument)
+                    }
+                    _ => expr.visit_children(self),
+                }
+            }
+            expr => expr.visit_children(self),
+        }
+    }
+
+    fn visit_solo_expr(&mut self, expr: &SoloExpr<TypeScript>) {
+        match expr {
+            SoloExpr::Identifier(ident) => self.reference(&ident.value, ident.span()),
+            expr => expr.visit_children(self),
+        }
+    }
+
+    fn visit_tag_name(&mut self, _name: &TagName<TypeScript>) {}
+
+    fn visit_enclosing_tag(&mut self, tag: &EnclosingTag<TypeScript>) {
+        tag.attributes
+            .iter()
+            .for_each(|attribute| attribute.visit(self));
+        self.nodes(&tag.children);
+    }
+
+    fn visit_at_statement(&mut self, statement: &AtStatement<TypeScript>) {
+        let decl = match statement {
+            AtStatement::Let(decl) | AtStatement::Const(decl) => decl,
+            AtStatement::Debug(debug) => return self.external(&debug.expr),
+        };
+
+        self.external(&decl.init);
+
+        let mut declared = Declared::default();
+        if let Maybe::Present(pat) = &decl.pat {
+            pat.visit_with(&mut declared);
+        }
+        for ident in declared.idents {
+            self.declare(&ident.sym, self.span(ident.span), Kind::Declaration);
+        }
+    }
+
+    fn visit_if_branch(&mut self, branch: &IfBranch<TypeScript>) {
+        self.external(&branch.expr);
+        self.nodes(&branch.contents);
+    }
+
+    fn visit_else_if_branch(&mut self, branch: &ElseIfBranch<TypeScript>) {
+        self.external(&branch.expr);
+        self.nodes(&branch.contents);
+    }
+
+    fn visit_else_branch(&mut self, branch: &ElseBranch<TypeScript>) {
+        self.nodes(&branch.contents);
+    }
+
+    fn visit_for_block(&mut self, block: &ForBlock<TypeScript>) {
+        self.external(&block.iter);
+
+        self.enter();
+        if let Maybe::Present(ident) = &block.ident {
+            self.declare(&ident.value, ident.span(), Kind::Loop);
+        }
+        if let Some(key) = &block.key {
+            self.external(key);
+        }
+        block.children.iter().for_each(|node| node.visit(self));
+        self.exit();
+
+        if let Some(empty) = &block.empty_case {
+            self.nodes(empty);
+        }
+    }
+
+    fn visit_await_block(&mut self, block: &AwaitBlock<TypeScript>) {
+        self.external(&block.expr);
+
+        for (branch, nodes) in &block.branches {
+            self.enter();
+            if let Branch::Success(Maybe::Present(ident)) | Branch::Failure(Maybe::Present(ident)) =
+                branch
+            {
+                self.declare(&ident.value, ident.span(), Kind::Await);
+            }
+            nodes.iter().for_each(|node| node.visit(self));
+            self.exit();
+        }
+    }
+
+    fn visit_key_block(&mut self, block: &KeyBlock<TypeScript>) {
+        self.external(&block.expr);
+        self.nodes(&block.children);
+    }
+
+    fn visit_when_arm(&mut self, arm: &WhenArm<TypeScript>) {
+        // Patterns are matched as values, so only use names.
+        if let Maybe::Present(pattern) = &arm.pattern {
+            self.references(&pattern.expr);
+        }
+        self.nodes(&arm.children);
+    }
+
+    fn visit_default_arm(&mut self, arm: &DefaultArm<TypeScript>) {
+        self.enter();
+        if let Some(ident) = &arm.binding {
+            self.declare(&ident.value, ident.span(), Kind::Arm);
+        }
+        arm.children.iter().for_each(|node| node.visit(self));
+        self.exit();
+    }
+}
+
+///
+/// Names a TypeScript node (a module, or pattern) declares,
+/// not counting those inside of functions, or classes.
+///
+#[derive(Default)]
+struct Declared {
+    idents: Vec<ast::Ident>,
+
+    ///
+    /// If declarations in blocks, and loops are left out (except `var`s),
+    /// as they're not the module's.
+    ///
+    block_scoped: bool,
+
+    ///
+    /// How many blocks, or loops the current node is in.
+    ///
+    depth: usize,
+}
+
+impl Declared {
+    fn declare(&mut self, ident: &ast::Ident) {
+        if !self.block_scoped || self.depth == 0 {
+            self.idents.push(ident.clone());
+        }
+    }
+
+    fn block(&mut self, f: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        f(self);
+        self.depth -= 1;
+    }
+}
+
+impl ecma_visit::Visit for Declared {
+    fn visit_binding_ident(&mut self, ident: &ast::BindingIdent) {
+        self.declare(&ident.id);
+    }
+
+    fn visit_fn_decl(&mut self, decl: &ast::FnDecl) {
+        self.declare(&decl.ident);
+    }
+
+    fn visit_class_decl(&mut self, decl: &ast::ClassDecl) {
+        self.declare(&decl.ident);
+    }
+
+    fn visit_ts_enum_decl(&mut self, decl: &ast::TsEnumDecl) {
+        self.declare(&decl.id);
+    }
+
+    fn visit_var_decl(&mut self, decl: &ast::VarDecl) {
+        // `var`s are hoisted out of blocks.
+        let depth = match decl.kind {
+            ast::VarDeclKind::Var => std::mem::take(&mut self.depth),
+            _ => self.depth,
+        };
+        decl.visit_children_with(self);
+        self.depth = depth;
+    }
+
+    fn visit_block_stmt(&mut self, block: &ast::BlockStmt) {
+        self.block(|this| block.visit_children_with(this));
+    }
+
+    fn visit_for_stmt(&mut self, stmt: &ast::ForStmt) {
+        self.block(|this| stmt.visit_children_with(this));
+    }
+
+    fn visit_for_in_stmt(&mut self, stmt: &ast::ForInStmt) {
+        self.block(|this| stmt.visit_children_with(this));
+    }
+
+    fn visit_for_of_stmt(&mut self, stmt: &ast::ForOfStmt) {
+        self.block(|this| stmt.visit_children_with(this));
+    }
+
+    fn visit_catch_clause(&mut self, clause: &ast::CatchClause) {
+        self.block(|this| clause.visit_children_with(this));
+    }
+
+    fn visit_switch_stmt(&mut self, stmt: &ast::SwitchStmt) {
+        self.block(|this| stmt.visit_children_with(this));
+    }
+
+    fn visit_import_named_specifier(&mut self, specifier: &ast::ImportNamedSpecifier) {
+        self.idents.push(specifier.local.clone());
+    }
+
+    fn visit_import_default_specifier(&mut self, specifier: &ast::ImportDefaultSpecifier) {
+        self.idents.push(specifier.local.clone());
+    }
+
+    fn visit_import_star_as_specifier(&mut self, specifier: &ast::ImportStarAsSpecifier) {
+        self.idents.push(specifier.local.clone());
+    }
+
+    // Assigning to a name doesn't declare it.
+    fn visit_assign_target(&mut self, _target: &ast::AssignTarget) {}
+
+    fn visit_function(&mut self, _function: &ast::Function) {}
+
+    fn visit_arrow_expr(&mut self, _arrow: &ast::ArrowExpr) {}
+
+    fn visit_class(&mut self, _class: &ast::Class) {}
+}
+
+///
+/// Names a TypeScript expression uses, but doesn't bind itself
+/// (with the parameters, and declarations of its functions).
+///
+#[derive(Default)]
+struct References {
+    ///
+    /// Names bound by each function around the current node.
+    ///
+    locals: Vec<HashSet<String>>,
+    found: Vec<ast::Ident>,
+}
+
+impl References {
+    fn scoped(&mut self, idents: Vec<ast::Ident>, f: impl FnOnce(&mut Self)) {
+        self.locals.push(
+            idents
+                .into_iter()
+                .map(|ident| ident.sym.to_string())
+                .collect(),
+        );
+        f(self);
+        self.locals.pop();
+    }
+}
+
+impl ecma_visit::Visit for References {
+    fn visit_ident(&mut self, ident: &ast::Ident) {
+        if !self.locals.iter().any(|names| names.contains(&*ident.sym)) {
+            self.found.push(ident.clone());
+        }
+    }
+
+    // Only computed keys, and members (`a[b]`) use names.
+    fn visit_member_prop(&mut self, prop: &ast::MemberProp) {
+        if let ast::MemberProp::Computed(computed) = prop {
+            computed.visit_with(self);
+        }
+    }
+
+    fn visit_super_prop(&mut self, prop: &ast::SuperProp) {
+        if let ast::SuperProp::Computed(computed) = prop {
+            computed.visit_with(self);
+        }
+    }
+
+    fn visit_prop_name(&mut self, name: &ast::PropName) {
+        if let ast::PropName::Computed(computed) = name {
+            computed.visit_with(self);
+        }
+    }
+
+    fn visit_private_name(&mut self, _name: &ast::PrivateName) {}
+
+    fn visit_arrow_expr(&mut self, arrow: &ast::ArrowExpr) {
+        let mut declared = Declared::default();
+        arrow.params.visit_with(&mut declared);
+        arrow.body.visit_with(&mut declared);
+
+        self.scoped(declared.idents, |this| arrow.visit_children_with(this));
+    }
+
+    fn visit_function(&mut self, function: &ast::Function) {
+        let mut declared = Declared::default();
+        function.params.visit_with(&mut declared);
+        function.body.visit_with(&mut declared);
+        declared
+            .idents
+            .push(ast::Ident::new("arguments".into(), swc_common::DUMMY_SP));
+
+        self.scoped(declared.idents, |this| function.visit_children_with(this));
+    }
+
+    fn visit_fn_expr(&mut self, expr: &ast::FnExpr) {
+        let idents = expr.ident.iter().cloned().collect();
+        self.scoped(idents, |this| expr.function.visit_with(this));
+    }
+
+    fn visit_class_expr(&mut self, expr: &ast::ClassExpr) {
+        let idents = expr.ident.iter().cloned().collect();
+        self.scoped(idents, |this| expr.class.visit_with(this));
+    }
+
+    // Types aren't values.
+    fn visit_ts_type(&mut self, _ty: &ast::TsType) {}
+
+    fn visit_ts_type_param_decl(&mut self, _params: &ast::TsTypeParamDecl) {}
+
+    // Nor are labels.
+    fn visit_labeled_stmt(&mut self, stmt: &ast::LabeledStmt) {
+        stmt.body.visit_with(self);
+    }
+
+    fn visit_break_stmt(&mut self, _stmt: &ast::BreakStmt) {}
+
+    fn visit_continue_stmt(&mut self, _stmt: &ast::ContinueStmt) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Error, ErrorI, Parseable, SourceFile, Spanned},
+    };
+
+    use super::resolve;
+
+    ///
+    /// The kind, and source of each error of `src`.
+    ///
+    fn check(src: &str) -> Vec<(&'static str, &str)> {
+        let (source, _) = SourceFile::test_file(src);
+        let (file, errors) = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+        assert!(errors.is_empty(), "{errors:?}");
+
+        resolve(&file.unwrap())
+            .into_iter()
+            .map(|error| {
+                let kind = match error {
+                    Error::UnresolvedIdentifier(_) => "unresolved",
+                    Error::ShadowedIdentifier(_) => "shadowed",
+                    Error::UnusedBinding(_) => "unused",
+                    _ => "other",
+                };
+
+                (kind, &src[error.span().range()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unresolved() {
+        let src = r#"import { api } from "./api";
+let count = 0;
+---
+<Column>
+    {#for dog in kennel by dog.name}
+        <Text>{item.id}</Text>
+    {/for}
+    {#await api.load() then res}
+        Loading...
+    {/await}
+    <Button on:click={() => count++} label={(e) => e.target.value} />
+    {@let doubled = count * 2}
+    {doubled} {Math.max(count, size)}
+</Column>
+"#;
+
+        assert_eq!(
+            check(src),
+            [
+                ("unresolved", "kennel"),
+                ("unresolved", "item"),
+                ("unused", "res"),
+                ("unresolved", "size"),
+            ]
+        );
+    }
+
+    #[test]
+    fn scopes() {
+        let src = r#"let item = 1;
+---
+<Column>
+    {#for item in [1, 2]}
+        {item}
+    {/for}
+    {#if item > 0}
+        {@const x = item}
+        {x}
+    {:else}
+        {x}
+    {/if}
+    {#await fetch("/") catch _err}{/await}
+    {#match item}
+        {:when 1}
+            One
+        {:default other}
+            {other}
+    {/match}
+</Column>
+"#;
+
+        assert_eq!(check(src), [("shadowed", "item"), ("unresolved", "x")]);
+    }
+
+    #[test]
+    fn module_blocks() {
+        let src = r#"let items = [];
+if (items.length) {
+    let inner = 1;
+    var hoisted = 2;
+}
+for (const item of items) {}
+---
+<Column>{inner} {hoisted} {item}</Column>
+"#;
+
+        assert_eq!(
+            check(src),
+            [("unresolved", "inner"), ("unresolved", "item")]
+        );
+    }
+
+    #[test]
+    fn lengths_and_colors() {
+        let src = r##"let a = 1;
+---
+<Row gap=(2pt) color=(rgb(1, 2, 3)) border=(hex"#ff8800") />
+"##;
+
+        let errors = check(src);
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn suggestions() {
+        let src = "let count = 0;\n---\n{#for item in [1]}{itme} {cuont} {other}{/for}\n";
+        let (source, _) = SourceFile::test_file(src);
+        let file = File::<TypeScript>::parser().parse(source.stream()).unwrap();
+
+        let notes = resolve(&file)
+            .into_iter()
+            .map(|error| error.to_diagnostic().notes)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            notes,
+            [
+                vec!["Did you mean `item`?"],
+                vec!["Did you mean `count`?"],
+                vec![]
+            ]
+        );
+    }
+}
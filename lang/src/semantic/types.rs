@@ -0,0 +1,405 @@
+//!
+//! ## Types
+//!
+//! Types of Pony expressions, given to attributes:
+//! * `number`, `string`, and `bool`, for literals;
+//! * `length`, for numbers applied to a [unit](UNITS), e.g. `5pt`;
+//! * `color`, for `rgb(r, g, b)`, `rgba(r, g, b, a)`, `hsl(h, s, l)`,
+//!   `hsla(h, s, l, a)`, or `hex"#rrggbb"`;
+//! * Records `(.x: number, .y: number)`, for maps;
+//! * Tuples `(number, string)`, and lists `[number]`;
+//! * `opaque`, for external expressions, identifiers,
+//!   and anything else that can't be inferred.
+//!
+//! Expressions are [checked](check) against the types of
+//! component props (see [super::schema::Prop]).
+//!
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    lexical::Literal,
+    syntax::{
+        external::External,
+        map::{Field, Map},
+        operator::UnaryOperator,
+        Expr,
+    },
+    utils::{
+        error::types::{MissingField, TypeMismatch, UnexpectedField, UnknownUnit},
+        placeholder::Maybe,
+        Error, Spanned,
+    },
+};
+
+///
+/// Units of length, applied to numbers, e.g. `5pt`.
+///
+pub const UNITS: &[&str] = &[
+    "px", "pt", "pc", "in", "cm", "mm", "em", "rem", "ex", "ch", "vw", "vh", "vmin", "vmax", "fr",
+];
+
+///
+/// Functions building colors, from a tuple of numbers.
+///
+pub const COLORS: &[&str] = &["rgb", "rgba", "hsl", "hsla"];
+
+///
+/// Builds a color from a hex string literal, e.g. `hex"#ff8800"`.
+///
+pub const HEX: &str = "hex";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Type {
+    Number,
+    String,
+    Bool,
+    Length,
+    Color,
+    Record(BTreeMap<String, Type>),
+    Tuple(Vec<Type>),
+    List(Box<Type>),
+
+    ///
+    /// Unknown, matching every other type.
+    ///
+    #[default]
+    Opaque,
+}
+
+impl Type {
+    ///
+    /// If a value of this type can be given where `expected` is.
+    ///
+    pub fn matches(&self, expected: &Type) -> bool {
+        match (self, expected) {
+            (Self::Opaque, _) | (_, Self::Opaque) => true,
+            (Self::List(found), Self::List(expected)) => found.matches(expected),
+            (Self::Tuple(found), Self::Tuple(expected)) => {
+                found.len() == expected.len()
+                    && found.iter().zip(expected).all(|(a, b)| a.matches(b))
+            }
+            (Self::Record(found), Self::Record(expected)) => {
+                found.len() == expected.len()
+                    && found
+                        .iter()
+                        .zip(expected)
+                        .all(|((k1, a), (k2, b))| k1 == k2 && a.matches(b))
+            }
+            (found, expected) => found == expected,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number => write!(f, "number"),
+            Self::String => write!(f, "string"),
+            Self::Bool => write!(f, "bool"),
+            Self::Length => write!(f, "length"),
+            Self::Color => write!(f, "color"),
+            Self::Opaque => write!(f, "opaque"),
+            Self::List(item) => write!(f, "[{item}]"),
+            Self::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    match i {
+                        0 => write!(f, "{item}")?,
+                        _ => write!(f, ", {item}")?,
+                    }
+                }
+                match items.len() {
+                    1 => write!(f, ",)"),
+                    _ => write!(f, ")"),
+                }
+            }
+            Self::Record(fields) => {
+                write!(f, "(")?;
+                for (i, (name, ty)) in fields.iter().enumerate() {
+                    match i {
+                        0 => write!(f, ".{name}: {ty}")?,
+                        _ => write!(f, ", .{name}: {ty}")?,
+                    }
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+///
+/// The type of `expr`.
+///
+pub fn infer<Ext: External>(expr: &Expr<Ext>) -> Type {
+    match expr {
+        Expr::Literal(Literal::Number(_)) => Type::Number,
+        Expr::Literal(Literal::String(_)) => Type::String,
+        Expr::Literal(Literal::Boolean(_)) => Type::Bool,
+        Expr::UnaryOp(op) => match (&op.operator, infer(&op.operand)) {
+            (UnaryOperator::Symbols(symbol), ty @ (Type::Number | Type::Length))
+                if symbol.value == "-" || symbol.value == "+" =>
+            {
+                ty
+            }
+            (UnaryOperator::Symbols(symbol), Type::Bool) if symbol.value == "!" => Type::Bool,
+            _ => Type::Opaque,
+        },
+        Expr::Array(array) => {
+            let mut items = array.contents.iter().map(infer);
+            let Some(first) = items.next() else {
+                return Type::List(Box::new(Type::Opaque));
+            };
+
+            match items.all(|item| item == first) {
+                true => Type::List(Box::new(first)),
+                false => Type::List(Box::new(Type::Opaque)),
+            }
+        }
+        Expr::Map(map) => Type::Record(
+            map.fields
+                .iter()
+                .filter_map(|field| match field {
+                    Field::Key(key) => key.ident.present().map(|ident| (ident, Type::Opaque)),
+                    Field::KeyValue(field) => field.key.present().map(|ident| {
+                        let ty = field.value.present().map(infer).unwrap_or_default();
+                        (ident, ty)
+                    }),
+                })
+                .map(|(ident, ty)| (ident.value.clone(), ty))
+                .collect(),
+        ),
+        Expr::Tuple(tuple) => Type::Tuple(tuple.items.iter().map(infer).collect()),
+        Expr::Parenthesised(parenthesized) => infer(&parenthesized.inner),
+        Expr::Application(application) => match (&*application.function, &*application.argument) {
+            (Expr::Literal(Literal::Number(_)), Expr::Identifier(unit))
+                if UNITS.contains(&unit.value.as_str()) =>
+            {
+                Type::Length
+            }
+            (Expr::Identifier(function), Expr::Tuple(tuple))
+                if COLORS.contains(&function.value.as_str())
+                    && tuple.items.iter().all(|item| infer(item) == Type::Number) =>
+            {
+                Type::Color
+            }
+            (Expr::Identifier(function), Expr::Literal(Literal::String(_)))
+                if function.value == HEX =>
+            {
+                Type::Color
+            }
+            _ => Type::Opaque,
+        },
+        Expr::Identifier(_)
+        | Expr::External(_)
+        | Expr::MemberAccess(_)
+        | Expr::Indexing(_)
+        | Expr::BinaryOp(_) => Type::Opaque,
+    }
+}
+
+///
+/// Check `expr` can be given where a value of type `expected` is,
+/// returning errors for the innermost expressions that can't.
+///
+pub fn check<Ext: External>(expr: &Expr<Ext>, expected: &Type) -> Vec<Error> {
+    let mut errors = Vec::new();
+    check_into(expr, expected, &mut errors);
+    errors
+}
+
+fn check_into<Ext: External>(expr: &Expr<Ext>, expected: &Type, errors: &mut Vec<Error>) {
+    match (expr, expected) {
+        (_, Type::Opaque) => (),
+        (Expr::Parenthesised(parenthesized), _) => {
+            check_into(&parenthesized.inner, expected, errors)
+        }
+        (Expr::Array(array), Type::List(item)) => array
+            .contents
+            .iter()
+            .for_each(|expr| check_into(expr, item, errors)),
+        (Expr::Tuple(tuple), Type::Tuple(items)) if tuple.items.len() == items.len() => tuple
+            .items
+            .iter()
+            .zip(items)
+            .for_each(|(expr, item)| check_into(expr, item, errors)),
+        (Expr::Map(map), Type::Record(fields)) => check_record(map, expected, fields, errors),
+        (Expr::Application(application), Type::Length) => {
+            match (&*application.function, &*application.argument) {
+                (Expr::Literal(Literal::Number(_)), Expr::Identifier(unit))
+                    if !UNITS.contains(&unit.value.as_str()) =>
+                {
+                    errors.push(UnknownUnit::new(unit.span(), unit.value.clone()).into())
+                }
+                _ => mismatch(expr, expected, errors),
+            }
+        }
+        _ => mismatch(expr, expected, errors),
+    }
+}
+
+fn check_record<Ext: External>(
+    map: &Map<Ext>,
+    expected: &Type,
+    fields: &BTreeMap<String, Type>,
+    errors: &mut Vec<Error>,
+) {
+    let mut given = Vec::new();
+
+    for field in map.fields.iter() {
+        let (key, value) = match field {
+            Field::Key(key) => (&key.ident, None),
+            Field::KeyValue(field) => (&field.key, field.value.present()),
+        };
+        let Maybe::Present(key) = key else {
+            continue;
+        };
+        given.push(key.value.as_str());
+
+        let Some(ty) = fields.get(&key.value) else {
+            errors
+                .push(UnexpectedField::new(key.span(), key.value.clone(), expected.clone()).into());
+            continue;
+        };
+
+        if let Some(value) = value {
+            check_into(value, ty, errors);
+        }
+    }
+
+    for (name, ty) in fields {
+        if !given.contains(&name.as_str()) {
+            errors.push(MissingField::new(map.span(), name.clone(), ty.clone()).into());
+        }
+    }
+}
+
+fn mismatch<Ext: External>(expr: &Expr<Ext>, expected: &Type, errors: &mut Vec<Error>) {
+    let found = infer(expr);
+    if !found.matches(expected) {
+        errors.push(TypeMismatch::new(expr.span(), expected.clone(), found).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::VExpr as Expr,
+        utils::{Error, Parseable, SourceFile, Spanned},
+    };
+
+    use super::{check, infer, Type};
+
+    fn parse(src: &str) -> Expr {
+        let (source, _) = SourceFile::test_file(src);
+        Expr::parser().parse(source.stream()).into_result().unwrap()
+    }
+
+    fn record(fields: &[(&str, Type)]) -> Type {
+        Type::Record(
+            fields
+                .iter()
+                .map(|(name, ty)| (name.to_string(), ty.clone()))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn inference() {
+        let cases = [
+            ("5", Type::Number),
+            ("-2.5", Type::Number),
+            (r#""Hello""#, Type::String),
+            ("true", Type::Bool),
+            ("5pt", Type::Length),
+            ("-1.5em", Type::Length),
+            ("rgb(255, 128, 0)", Type::Color),
+            (r##"hex"#ff8800""##, Type::Color),
+            ("[1, 2, 3]", Type::List(Box::new(Type::Number))),
+            ("[1, \"a\"]", Type::List(Box::new(Type::Opaque))),
+            ("(1, \"a\")", Type::Tuple(vec![Type::Number, Type::String])),
+            (
+                "(.x = 1px, .label = \"a\")",
+                record(&[("x", Type::Length), ("label", Type::String)]),
+            ),
+            ("((5))", Type::Number),
+            ("a", Type::Opaque),
+            ("5 foo", Type::Opaque),
+            ("a + 1", Type::Opaque),
+        ];
+
+        for (src, ty) in cases {
+            assert_eq!(infer(&parse(src)), ty, "{src}");
+        }
+    }
+
+    #[test]
+    fn display() {
+        let ty = record(&[
+            ("at", Type::Tuple(vec![Type::Length, Type::Length])),
+            ("tags", Type::List(Box::new(Type::String))),
+            ("only", Type::Tuple(vec![Type::Color])),
+        ]);
+        assert_eq!(
+            ty.to_string(),
+            "(.at: (length, length), .only: (color,), .tags: [string])"
+        );
+    }
+
+    ///
+    /// The kind, and source of each error of checking `src`.
+    ///
+    fn errors<'a>(src: &'a str, expected: &Type) -> Vec<(&'static str, &'a str)> {
+        check(&parse(src), expected)
+            .into_iter()
+            .map(|error| {
+                let kind = match error {
+                    Error::TypeMismatch(_) => "mismatch",
+                    Error::UnknownUnit(_) => "unit",
+                    Error::UnexpectedField(_) => "unexpected",
+                    Error::MissingField(_) => "missing",
+                    _ => "other",
+                };
+
+                (kind, &src[error.span().range()])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn checking() {
+        assert_eq!(errors("5px", &Type::Length), []);
+        assert_eq!(errors("a", &Type::Length), []);
+        assert_eq!(errors("5", &Type::Length), [("mismatch", "5")]);
+        assert_eq!(errors("5pz", &Type::Length), [("unit", "pz")]);
+        assert_eq!(errors("\"red\"", &Type::Color), [("mismatch", "\"red\"")]);
+        assert_eq!(errors("\"a\"", &Type::Opaque), []);
+
+        let list = Type::List(Box::new(Type::Length));
+        assert_eq!(errors("[1px, 2, 3em]", &list), [("mismatch", "2")]);
+        assert_eq!(errors("(1px, 2px)", &list), [("mismatch", "(1px, 2px)")]);
+
+        let tuple = Type::Tuple(vec![Type::Number, Type::Bool]);
+        assert_eq!(errors("(1, \"no\")", &tuple), [("mismatch", "\"no\"")]);
+        assert_eq!(
+            errors("(1, true, 2)", &tuple),
+            [("mismatch", "(1, true, 2)")]
+        );
+
+        let point = record(&[("x", Type::Length), ("y", Type::Length)]);
+        assert_eq!(errors("(.x = 1px, .y = 2px)", &point), []);
+        assert_eq!(errors("(.x = 1px, .y)", &point), []);
+        assert_eq!(
+            errors("(.x = 1, .z = 2px)", &point),
+            [
+                ("mismatch", "1"),
+                ("unexpected", "z"),
+                ("missing", "(.x = 1, .z = 2px)"),
+            ]
+        );
+    }
+}
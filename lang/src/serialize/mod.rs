@@ -0,0 +1,423 @@
+//!
+//! ## Serialization
+//!
+//! Parsed files as JSON (with the `serde` feature), for tools not written
+//! in Rust, such as the output of `avpony parse --json`:
+//! ```json
+//! {
+//!     "version": 1,
+//!     "language": "ts",
+//!     "path": "src/Button.pony",
+//!     "ast": { "kind": "File", "span": { ... }, "module": { ... }, "pony": { ... } },
+//!     "errors": [{ "severity": "error", "span": { ... }, "report": "..." }]
+//! }
+//! ```
+//!
+//! * Nodes are objects tagged by their `kind` (`"IfBlock"`, `"Identifier"`, ...);
+//!   enums of nodes, like [Node](crate::ponyx::Node), are the node they hold.
+//! * Spans are `{ "file", "range": [start, end], "start": { "line", "column" }, "end" }`,
+//!   with byte offsets, and 1-based lines, and columns (counted in characters).
+//! * Missing nodes (after a syntax error) are `{ "kind": "Placeholder", "span", "expected" }`.
+//! * Nodes of the external language are `{ "kind": "External", "source" }`,
+//!   printed by the language.
+//!
+//! This shape is described by [SCHEMA], a JSON Schema,
+//! and [VERSION] is bumped whenever it changes incompatibly.
+//!
+
+use std::cell::RefCell;
+
+use chumsky::span::Span as _;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::{
+    lint::{self, Level},
+    syntax::{
+        external::{External, ExternalNode},
+        file::File,
+    },
+    utils::{
+        placeholder::{HasPlaceholder, Maybe},
+        Encoding, Error, FileId, LineCol, LineIndex, SourceFile, Span, Spanned,
+    },
+};
+
+///
+/// Version of the JSON shape.
+///
+pub const VERSION: u32 = 1;
+
+///
+/// JSON Schema of documents made by [document].
+///
+pub const SCHEMA: &str = include_str!("schema.json");
+
+///
+/// The file being serialized, and where its lines are.
+///
+struct Lines {
+    file: FileId,
+    index: LineIndex,
+}
+
+thread_local! {
+    static LINES: RefCell<Option<Lines>> = const { RefCell::new(None) };
+}
+
+///
+/// Serialize within `f` with line, and column numbers
+/// for spans in `source` (which are otherwise left out).
+///
+pub fn with_source<T>(source: &SourceFile, f: impl FnOnce() -> T) -> T {
+    let previous = LINES.replace(Some(Lines {
+        file: source.id(),
+        index: source.lines().clone(),
+    }));
+    let out = f();
+    LINES.set(previous);
+
+    out
+}
+
+///
+/// Line, and column (both from 1) of byte `offset`.
+///
+#[derive(Serialize)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl Lines {
+    fn position(&self, offset: usize) -> Position {
+        let LineCol { line, col } = self.index.line_col(offset, Encoding::Char);
+
+        Position {
+            line: line + 1,
+            column: col + 1,
+        }
+    }
+}
+
+impl Serialize for Span {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let positions = LINES.with_borrow(|lines| {
+            lines
+                .as_ref()
+                .filter(|lines| lines.file == self.file())
+                .map(|lines| (lines.position(self.start()), lines.position(self.end())))
+        });
+
+        let mut span = serializer.serialize_struct("Span", 4)?;
+        span.serialize_field("file", self.file().path().as_ref())?;
+        span.serialize_field("range", &[self.start(), self.end()])?;
+        match positions {
+            Some((start, end)) => {
+                span.serialize_field("start", &start)?;
+                span.serialize_field("end", &end)?;
+            }
+            None => {
+                span.skip_field("start")?;
+                span.skip_field("end")?;
+            }
+        }
+        span.end()
+    }
+}
+
+///
+/// Fields holding nodes of the external language.
+///
+pub trait ExternalField {
+    fn serialize_external<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+impl<T: ExternalNode> ExternalField for T {
+    fn serialize_external<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut node = serializer.serialize_struct("External", 2)?;
+        node.serialize_field("kind", "External")?;
+        node.serialize_field("source", &self.to_source())?;
+        node.end()
+    }
+}
+
+impl<T: ExternalNode + HasPlaceholder> ExternalField for Maybe<T> {
+    fn serialize_external<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Maybe::Present(node) => node.serialize_external(serializer),
+            Maybe::Placeholder(placeholder) => placeholder.serialize(serializer),
+        }
+    }
+}
+
+impl<T: ExternalNode + HasPlaceholder> ExternalField for Option<Maybe<T>> {
+    fn serialize_external<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(node) => node.serialize_external(serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+///
+/// Serialize a field holding external nodes,
+/// for `#[serde(serialize_with = "crate::serialize::external")]`.
+///
+pub(crate) fn external<T: ExternalField, S: Serializer>(
+    field: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    field.serialize_external(serializer)
+}
+
+///
+/// A parsed file (if any), and its errors, as a JSON document.
+///
+pub fn document<Ext: External>(
+    file: Option<&File<Ext>>,
+    errors: &[Error],
+    source: &SourceFile,
+) -> serde_json::Value {
+    with_source(source, || {
+        let errors = errors
+            .iter()
+            .map(|error| {
+                serde_json::json!({
+                    "severity": match lint::default_level(error) {
+                        Level::Deny => "error",
+                        Level::Warn | Level::Allow => "warning",
+                    },
+                    "span": error.span(),
+                    "report": error.clone().render(source),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "version": VERSION,
+            "language": Ext::ID,
+            "path": source.path().as_ref(),
+            "ast": file,
+            "errors": errors,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Parseable, SourceFile},
+    };
+
+    use serde_json::Value;
+
+    use super::{document, SCHEMA, VERSION};
+
+    ///
+    /// Check `value` against `schema`, of the subset of JSON Schema that [SCHEMA] uses,
+    /// returning the path to the first value that doesn't conform.
+    ///
+    fn conforms(value: &Value, schema: &Value, root: &Value, path: &str) -> Result<(), String> {
+        let fail = |why: &str| Err(format!("{path}: {why}, in {value}"));
+
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/$defs/");
+            return conforms(
+                value,
+                &root["$defs"][name],
+                root,
+                &format!("{path}<{name}>"),
+            );
+        }
+
+        if let Some(schemas) = schema["oneOf"].as_array() {
+            let matching = schemas
+                .iter()
+                .filter(|schema| conforms(value, schema, root, path).is_ok())
+                .count();
+            if matching != 1 {
+                return fail(&format!("{matching} of `oneOf` match"));
+            }
+        }
+
+        if !schema["const"].is_null() && schema["const"] != *value {
+            return fail(&format!("expected {}", schema["const"]));
+        }
+
+        if let Some(values) = schema["enum"].as_array() {
+            if !values.contains(value) {
+                return fail("not in `enum`");
+            }
+        }
+
+        let is_type = match schema["type"].as_str() {
+            None => true,
+            Some("object") => value.is_object(),
+            Some("array") => value.is_array(),
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("number") => value.is_number(),
+            Some("boolean") => value.is_boolean(),
+            Some("null") => value.is_null(),
+            Some(ty) => return fail(&format!("unknown type `{ty}`")),
+        };
+        if !is_type {
+            return fail(&format!("expected {}", schema["type"]));
+        }
+
+        if let Some(minimum) = schema["minimum"].as_f64() {
+            if value.as_f64().is_some_and(|value| value < minimum) {
+                return fail("below `minimum`");
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            for key in schema["required"].as_array().into_iter().flatten() {
+                if !object.contains_key(key.as_str().unwrap()) {
+                    return fail(&format!("missing {key}"));
+                }
+            }
+
+            for (key, value) in object {
+                match schema["properties"].get(key) {
+                    Some(property) => conforms(value, property, root, &format!("{path}.{key}"))?,
+                    None if schema["additionalProperties"] == false => {
+                        return fail(&format!("unexpected `{key}`"))
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        if let Some(items) = value.as_array() {
+            let prefix = schema["prefixItems"].as_array();
+            for (i, item) in items.iter().enumerate() {
+                let path = format!("{path}[{i}]");
+                match prefix.and_then(|prefix| prefix.get(i)) {
+                    Some(schema) => conforms(item, schema, root, &path)?,
+                    None if !schema["items"].is_null() => {
+                        conforms(item, &schema["items"], root, &path)?
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_file() {
+        let (source, _) =
+            SourceFile::test_file("let a = 1;\n---\n<Row gap=2>\n    {#if a}é{a}{/if}\n</Row>");
+        let (file, errors) = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+        let json = document(file.as_ref(), &errors, &source);
+
+        assert_eq!(json["version"], VERSION);
+        assert_eq!(json["language"], "ts");
+        assert_eq!(json["errors"], serde_json::json!([]));
+
+        let ast = &json["ast"];
+        assert_eq!(ast["kind"], "File");
+        assert_eq!(ast["module"]["kind"], "External");
+
+        let tag = &ast["pony"];
+        assert_eq!(tag["kind"], "EnclosingTag");
+        assert_eq!(tag["name"]["path"][0]["value"], "Row");
+        assert_eq!(
+            tag["attributes"][0],
+            serde_json::json!({
+                "kind": "AttributeAssignment",
+                "span": {
+                    "file": "TEST",
+                    "range": [20, 25],
+                    "start": { "line": 3, "column": 6 },
+                    "end": { "line": 3, "column": 11 },
+                },
+                "key": {
+                    "kind": "Identifier",
+                    "span": {
+                        "file": "TEST",
+                        "range": [20, 23],
+                        "start": { "line": 3, "column": 6 },
+                        "end": { "line": 3, "column": 9 },
+                    },
+                    "value": "gap",
+                },
+                "value": {
+                    "kind": "IntegerLit",
+                    "span": {
+                        "file": "TEST",
+                        "range": [24, 25],
+                        "start": { "line": 3, "column": 10 },
+                        "end": { "line": 3, "column": 11 },
+                    },
+                    "value": 2,
+                    "raw_value": "2",
+                },
+            })
+        );
+
+        let block = tag["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|node| node["kind"] == "IfBlock")
+            .unwrap();
+        let branch = &block["branches"][0];
+        assert_eq!(branch["kind"], "IfBranch");
+        assert_eq!(branch["expr"]["kind"], "External");
+
+        // Columns count characters, not bytes.
+        let mustache = &branch["contents"][1];
+        assert_eq!(mustache["kind"], "ExternalExpr");
+        assert_eq!(
+            mustache["span"]["start"],
+            serde_json::json!({ "line": 4, "column": 13 })
+        );
+
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+        assert_eq!(schema["properties"]["version"]["const"], VERSION);
+    }
+
+    #[test]
+    fn matches_schema() {
+        let (source, _) = SourceFile::test_file(
+            "let items = [1];\n---\n<Row gap=(2px) on:click={() => items} hidden>\n    <!-- items -->\n    &amp; {items}\n    {#for item in items}{#if item}{item}{:else}none{/if}{/for}\n    <Image src= a11y:alt=\"A\" />\n</Row>",
+        );
+        let (file, errors) = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_output_errors();
+        let json = document(file.as_ref(), &errors, &source);
+
+        let schema: Value = serde_json::from_str(SCHEMA).unwrap();
+        if let Err(err) = conforms(&json, &schema, &schema, "$") {
+            panic!("{err}");
+        }
+
+        // Every kind of node in the fixture, including the placeholder
+        // of the missing attribute value, is tagged by its kind.
+        let json = json.to_string();
+        for kind in [
+            "File",
+            "EnclosingTag",
+            "SelfClosingTag",
+            "AttributeAssignment",
+            "Directive",
+            "Comment",
+            "Entity",
+            "ExternalExpr",
+            "ForBlock",
+            "IfBlock",
+            "ElseBranch",
+            "Placeholder",
+        ] {
+            assert!(json.contains(&format!(r#""kind":"{kind}""#)), "no {kind}");
+        }
+    }
+}
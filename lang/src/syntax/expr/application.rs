@@ -9,7 +9,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::choice, recursive::recursive, Parser};
 
 use crate::{
@@ -19,7 +19,12 @@ use crate::{
 
 use super::Expr;
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct Application<Ext: External> {
     pub span: Span,
     pub function: Box<super::Expr<Ext>>,
@@ -5,7 +5,7 @@
 //! arr := `[` (<expr> `,`?(end))* `]`
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, text, Parser};
 
 use crate::{
@@ -15,7 +15,12 @@ use crate::{
 
 use super::{external::External, utils::Punctuated};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct Array<Ext: External> {
     span: Span,
     pub contents: Punctuated<super::Expr<Ext>, punctuation::Comma>,
@@ -6,7 +6,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, Parser};
 
 use crate::utils::{
@@ -16,7 +16,12 @@ use crate::utils::{
 
 use super::{external::External, utils::Accessor};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct Indexing<Ext: External> {
     pub span: Span,
     pub receiver: Box<super::Expr<Ext>>,
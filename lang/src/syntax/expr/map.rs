@@ -12,7 +12,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, text, Parser};
 
 use crate::{
@@ -25,7 +25,12 @@ use crate::{
 
 use super::{external::External, utils::Punctuated};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct Map<Ext: External> {
     span: Span,
     pub fields: Punctuated<Field<Ext>, punctuation::Comma>,
@@ -45,7 +50,12 @@ impl<Ext: External> Map<Ext> {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum Field<Ext: External> {
     Key(FieldKey),
     KeyValue(FieldKeyValue<Ext>),
@@ -80,17 +90,23 @@ impl<Ext: External> Field<Ext> {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct FieldKey {
     span: Span,
-    ident: Maybe<Identifier>,
+    pub ident: Maybe<Identifier>,
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct FieldKeyValue<Ext: External> {
     span: Span,
-    key: Maybe<Identifier>,
-    value: Box<Maybe<super::Expr<Ext>>>,
+    pub key: Maybe<Identifier>,
+    pub value: Box<Maybe<super::Expr<Ext>>>,
 }
 
 #[cfg(test)]
@@ -7,7 +7,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, Parser};
 
 use crate::{
@@ -20,7 +20,12 @@ use crate::{
 
 use super::{external::External, utils::Accessor};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct MemberAccess<Ext: External> {
     pub span: Span,
     pub receiver: Box<super::Expr<Ext>>,
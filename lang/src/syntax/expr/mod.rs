@@ -34,7 +34,7 @@ pub mod utils;
 pub use super::external;
 
 use super::external::{External, ExternalExpr};
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::choice, recursive::recursive, Parser};
 use utils::Accessor;
 
@@ -47,7 +47,12 @@ use crate::{
     },
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum Expr<Ext: External> {
     Literal(lexical::Literal),
     Identifier(lexical::Identifier),
@@ -107,7 +112,12 @@ impl Marker for ExprMarker {
         Self
     }
 }
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(untagged, bound = "")
+)]
 pub enum SoloExpr<Ext: External> {
     Literal(lexical::Literal),
     Identifier(lexical::Identifier),
@@ -141,6 +151,21 @@ impl<Ext: External + 'static> ParseableCloned for SoloExpr<Ext> {
     }
 }
 
+impl<Ext: External> From<SoloExpr<Ext>> for Expr<Ext> {
+    fn from(expr: SoloExpr<Ext>) -> Self {
+        match expr {
+            SoloExpr::Literal(t) => Self::Literal(t),
+            SoloExpr::Identifier(t) => Self::Identifier(t),
+            SoloExpr::Array(t) => Self::Array(t),
+            SoloExpr::Map(t) => Self::Map(t),
+            SoloExpr::Tuple(t) => Self::Tuple(t),
+            SoloExpr::Parenthesised(t) => Self::Parenthesised(t),
+            SoloExpr::External(t) => Self::External(t),
+            SoloExpr::UnaryOp(t) => Self::UnaryOp(t),
+        }
+    }
+}
+
 impl<Ext: External> HasPlaceholder for SoloExpr<Ext> {
     type Marker = SoloExprMarker;
 }
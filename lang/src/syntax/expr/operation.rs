@@ -6,7 +6,7 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{text, Parser};
 
 use crate::utils::{
@@ -19,7 +19,13 @@ use super::{
     operator::{BinaryOperator, UnaryOperator},
 };
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[visit(name = unary_operation)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", rename = "UnaryOperation", bound = "")
+)]
 pub struct UnarayOperation<Ext: External> {
     pub span: Span,
     pub operator: UnaryOperator,
@@ -40,7 +46,12 @@ impl<Ext: External> UnarayOperation<Ext> {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct BinaryOperation<Ext: External> {
     pub span: Span,
     pub operator: BinaryOperator,
@@ -4,7 +4,7 @@
 //!
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{choice, just, one_of},
     IterParser, Parser,
@@ -17,7 +17,8 @@ use crate::{
 
 use super::{external::External, operation::BinaryOperation};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 pub enum UnaryOperator {
     Symbols(Symbolic),
 }
@@ -28,7 +29,8 @@ impl ParseableCloned for UnaryOperator {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct Symbolic {
     span: Span,
     pub value: String,
@@ -47,7 +49,8 @@ impl ParseableCloned for Symbolic {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 pub enum BinaryOperator {
     Symbols(Symbolic),
     Named(NamedBinary),
@@ -92,7 +95,8 @@ impl<'a> PartialEq<&'a str> for BinaryOperator {
     }
 }
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct NamedBinary {
     span: Span,
     pub ident: Identifier,
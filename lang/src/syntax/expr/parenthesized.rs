@@ -11,14 +11,19 @@
 //! ```
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, Parser};
 
 use crate::utils::{PonyParser, Span};
 
 use super::external::External;
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct Parenthesized<Ext: External> {
     span: Span,
     pub inner: Box<super::Expr<Ext>>,
@@ -5,7 +5,7 @@
 //! tuple_expr := `(` (<expr>,)* `)`
 //! ```
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::just,
     text::{self},
@@ -19,7 +19,12 @@ use crate::{
 
 use super::{external::External, utils::Punctuated};
 
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct Tuple<Ext: External> {
     span: Span,
     pub items: Vec<super::Expr<Ext>>,
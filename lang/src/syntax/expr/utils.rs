@@ -14,6 +14,7 @@ use chumsky::{
 use crate::{
     lexical,
     utils::{placeholder::Maybe, ParseableCloned, PonyParser, Span, Spanned},
+    visit::{Fold, Visit, VisitMut, Visitable},
 };
 
 use super::{
@@ -34,6 +35,33 @@ pub struct Punctuated<Token, Punct> {
     __marker: PhantomData<Punct>,
 }
 
+impl<Ext: External, Token: Visitable<Ext>, Punct> Visitable<Ext> for Punctuated<Token, Punct> {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        Visitable::<Ext>::visit(&self.span, visitor);
+        Visitable::<Ext>::visit(&self.inner, visitor);
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        Visitable::<Ext>::visit_mut(&mut self.span, visitor);
+        Visitable::<Ext>::visit_mut(&mut self.inner, visitor);
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        Self {
+            span: Visitable::<Ext>::fold(self.span, folder),
+            inner: Visitable::<Ext>::fold(self.inner, folder),
+            ..self
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Token: serde::Serialize, Punct> serde::Serialize for Punctuated<Token, Punct> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.inner)
+    }
+}
+
 impl<Token, Punct: ParseableCloned> Punctuated<Token, Punct> {
     pub fn iter(&self) -> impl Iterator<Item = &Token> + '_ {
         self.inner.iter()
@@ -118,8 +146,8 @@ pub(super) enum Accessor<Ext: External> {
 impl<Ext: External> Spanned for Accessor<Ext> {
     fn span(&self) -> crate::utils::span::Span {
         match self {
-            Accessor::Member(_, s) => s.clone(),
-            Accessor::Index(_, s) => s.clone(),
+            Accessor::Member(_, s) => *s,
+            Accessor::Index(_, s) => *s,
         }
     }
 }
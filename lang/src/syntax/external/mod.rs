@@ -6,10 +6,11 @@
 //!
 //! External languages supported:
 //! * TypeScript;
+//! * Rust.
 //!
 use std::fmt::Debug;
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{
     primitive::{just, one_of},
     text, Parser,
@@ -21,10 +22,14 @@ use crate::utils::Empty;
 use crate::{
     ponyx::blocks,
     utils::{
-        self, placeholder::{HasPlaceholder, Maybe}, ParseableCloned, PonyParser, Span
+        self, placeholder::{HasPlaceholder, Maybe}, Error, ParseableCloned, PonyParser, Span
     },
+    visit::Visitable,
 };
 
+use super::file::File;
+
+pub mod rust;
 pub mod typescript;
 
 ///
@@ -36,26 +41,94 @@ pub mod typescript;
 pub trait External: PartialEq + Debug + Clone {
     const ID: &'static str;
 
-    type Module: PartialEq + Clone + Debug;
-    type Expression: PartialEq + Clone + Debug + HasPlaceholder;
-
-    type LetDeclaration: utils::Spanned + PartialEq + Clone + Debug;
-    type ConstDeclaration: utils::Spanned + PartialEq + Clone + Debug;
+    ///
+    /// Separator between segments of a [TagName](crate::ponyx::tag::name::TagName),
+    /// e.g. `.` for `Path.To.Component`.
+    ///
+    const TAG_PATH_SEPARATOR: &'static str;
+
+    ///
+    /// Opens the generic arguments of a [TagName](crate::ponyx::tag::name::TagName),
+    /// e.g. `<` for `List<Item>`; these are always closed with `>`.
+    ///
+    const TAG_GENERICS_OPEN: &'static str;
+
+    type Module: PartialEq + Clone + Debug + Visitable<Self> + ExternalNode;
+    type Expression: PartialEq + Clone + Debug + HasPlaceholder + Visitable<Self> + ExternalNode;
+
+    ///
+    /// A refutable pattern, used by `{:when <pat>}` leaves
+    /// of [blocks::match_block::MatchBlock].
+    ///
+    type Pattern: PartialEq + Clone + Debug + HasPlaceholder + Visitable<Self> + ExternalNode;
+
+    type LetDeclaration: utils::Spanned + PartialEq + Clone + Debug + Visitable<Self> + ExternalNode;
+    type ConstDeclaration: utils::Spanned
+        + PartialEq
+        + Clone
+        + Debug
+        + Visitable<Self>
+        + ExternalNode;
 
     fn module<'src>() -> impl PonyParser<'src, Self::Module>;
     fn expression<'src>() -> impl PonyParser<'src, Maybe<Self::Expression>> + Clone;
+    fn pattern<'src>() -> impl PonyParser<'src, Maybe<Self::Pattern>> + Clone;
     fn let_declaration<'src>() -> impl PonyParser<'src, Self::LetDeclaration> + Clone;
     fn const_declaration<'src>() -> impl PonyParser<'src, Self::ConstDeclaration> + Clone;
+
+    ///
+    /// Pretty-print the source of a module (keeping its comments),
+    /// used by [crate::format].
+    ///
+    /// Returns `None` to leave the module as written.
+    ///
+    fn format_module(_src: &str) -> Option<String> {
+        None
+    }
+
+    ///
+    /// Check a parsed file for errors beyond its syntax,
+    /// like unresolved identifiers (see [crate::semantic]).
+    ///
+    fn check(_file: &File<Self>) -> Vec<Error> {
+        Vec::new()
+    }
+}
+
+///
+/// A syntax node of an external language
+/// (module, expression, pattern, or declaration).
+///
+pub trait ExternalNode {
+    ///
+    /// This node, printed as source code.
+    ///
+    fn to_source(&self) -> String;
+
+    ///
+    /// Move every position at, or after byte `from` by `delta` bytes,
+    /// after an edit of the source before them (see [crate::incremental]).
+    ///
+    fn shift(&mut self, from: usize, delta: isize);
 }
 
 ///
 /// An expression originating from an external language,
 /// delimited by braces `{}`.
 ///
-#[derive(Debug, Clone, Spanned, PartialEq)]
+#[derive(Debug, Clone, Spanned, PartialEq, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct ExternalExpr<Ext: External> {
     span: Span,
-    expr: Maybe<Ext::Expression>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub expr: Maybe<Ext::Expression>,
 }
 
 impl<Ext: External> ParseableCloned for ExternalExpr<Ext> {
@@ -93,12 +166,24 @@ impl<Ext: External> HasPlaceholder for ExternalExpr<Ext> {
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestLang;
 
+#[cfg(test)]
+impl ExternalNode for Empty {
+    fn to_source(&self) -> String {
+        String::new()
+    }
+
+    fn shift(&mut self, _from: usize, _delta: isize) {}
+}
+
 #[cfg(test)]
 impl External for TestLang {
     const ID: &'static str = "TESTING LANGUAGE";
+    const TAG_PATH_SEPARATOR: &'static str = ".";
+    const TAG_GENERICS_OPEN: &'static str = "<";
 
     type Module = Empty;
     type Expression = Empty;
+    type Pattern = Empty;
     type LetDeclaration = Empty;
     type ConstDeclaration = Empty;
 
@@ -110,6 +195,10 @@ impl External for TestLang {
         Empty::parser().map(Maybe::Present)
     }
 
+    fn pattern<'src>() -> impl PonyParser<'src, Maybe<Self::Pattern>> + Clone {
+        Empty::parser().map(Maybe::Present)
+    }
+
     fn let_declaration<'src>() -> impl PonyParser<'src, Self::LetDeclaration> + Clone {
         Empty::parser()
     }
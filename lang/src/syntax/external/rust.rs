@@ -0,0 +1,437 @@
+//!
+//! ## Rust support.
+//!
+//! Rust syntax is parsed with [syn].
+//!
+//! As [syn] only parses complete token streams, inline Rust (expressions, patterns)
+//! is first cut off at the first unbalanced closing delimiter, and then parsed
+//! as a prefix of that.
+//!
+use avpony_macros::Spanned;
+use chumsky::{
+    input::Marker,
+    primitive::{any, custom, just},
+    span::Span,
+    IterParser, Parser,
+};
+use quote::ToTokens;
+use syn::{
+    parse::{ParseStream, Parser as _},
+    Token,
+};
+
+use crate::utils::{
+    self,
+    error::external::rust::ConvertSynError,
+    placeholder::{HasPlaceholder, Marker as PlaceholderMarker, Maybe, Placeholder},
+    Error, PonyParser, Spanned,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rust;
+
+///
+/// Byte length of the longest prefix of `src` with balanced delimiters,
+/// i.e. up to (not including) the first unmatched `)`, `]`, or `}`.
+///
+/// String, and character literals are skipped over.
+///
+fn balanced_len(src: &str) -> usize {
+    let mut depth = 0usize;
+    let mut chars = src.char_indices().peekable();
+
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => return i,
+            ')' | ']' | '}' => depth -= 1,
+            '"' => {
+                while let Some((_, ch)) = chars.next() {
+                    match ch {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+            }
+            // Character literals (but not lifetimes): `'a'`, `'\n'`, `'}'`.
+            '\'' => {
+                let rest = &src[i + 1..];
+                let literal_len = match rest.strip_prefix('\\') {
+                    Some(escaped) => escaped.find('\'').map(|end| end + 2),
+                    None => rest
+                        .chars()
+                        .next()
+                        .filter(|ch| rest[ch.len_utf8()..].starts_with('\''))
+                        .map(|ch| ch.len_utf8() + 1),
+                };
+
+                if let Some(literal_len) = literal_len {
+                    while chars.peek().is_some_and(|(j, _)| *j <= i + literal_len) {
+                        chars.next();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    src.len()
+}
+
+impl Rust {
+    ///
+    /// Parse `src` (starting at `span`) with `func`,
+    /// also returning how many bytes were consumed.
+    ///
+    fn parse_str<O>(
+        src: &str,
+        span: utils::Span,
+        func: impl Fn(ParseStream) -> syn::Result<O>,
+    ) -> Result<(O, usize), Error> {
+        let parser = |input: ParseStream| {
+            let out = func(input)?;
+
+            // Anything left over is not ours, so rewind to it.
+            let consumed = match input.cursor().token_tree() {
+                Some((tt, _)) => tt.span().byte_range().start,
+                None => src.len(),
+            };
+
+            input.parse::<proc_macro2::TokenStream>()?;
+            Ok((out, consumed))
+        };
+
+        parser
+            .parse_str(src)
+            .map_err(|err| err.convert(span).into())
+    }
+
+    fn inline_parse_for<'src, O, F>(func: F) -> impl PonyParser<'src, Result<O, Error>> + Clone
+    where
+        F: Fn(ParseStream) -> syn::Result<O> + Clone + 'src,
+    {
+        custom(move |stream| {
+            let span: utils::Span = stream.span(stream.offset()..stream.offset());
+            let input_left: &str = stream.slice_from(stream.offset()..);
+            let src = &input_left[..balanced_len(input_left)];
+            let start = stream.offset().raw();
+            let end = start + src.len();
+
+            let res = Self::parse_str(
+                src,
+                utils::Span::new(span.context(), start..end),
+                func.clone(),
+            );
+
+            let new_start = match &res {
+                Ok((_, consumed)) => start + consumed,
+                Err(err) => err.span().end(),
+            };
+            stream.rewind(unsafe { Marker::from_raw(new_start, 0) });
+
+            Ok(res.map(|(o, _)| o))
+        })
+    }
+
+    fn local(input: ParseStream) -> syn::Result<syn::Local> {
+        let let_token: Token![let] = input.parse()?;
+        let mut pat = syn::Pat::parse_single(input)?;
+
+        if input.peek(Token![:]) {
+            pat = syn::Pat::Type(syn::PatType {
+                attrs: Default::default(),
+                pat: Box::new(pat),
+                colon_token: input.parse()?,
+                ty: Box::new(input.parse()?),
+            });
+        }
+
+        Ok(syn::Local {
+            attrs: Default::default(),
+            let_token,
+            pat,
+            init: Some(syn::LocalInit {
+                eq_token: input.parse()?,
+                expr: Box::new(input.parse()?),
+                diverge: None,
+            }),
+            semi_token: Default::default(),
+        })
+    }
+
+    fn item_const(input: ParseStream) -> syn::Result<syn::ItemConst> {
+        Ok(syn::ItemConst {
+            attrs: Default::default(),
+            vis: syn::Visibility::Inherited,
+            const_token: input.parse()?,
+            ident: input.parse()?,
+            generics: Default::default(),
+            colon_token: input.parse()?,
+            ty: Box::new(input.parse()?),
+            eq_token: input.parse()?,
+            expr: Box::new(input.parse()?),
+            semi_token: Default::default(),
+        })
+    }
+}
+
+///
+/// `{@let <pat> (: <type>)? = <expr>}`
+///
+#[derive(Debug, Clone, Spanned, PartialEq)]
+pub struct LetDecl {
+    span: utils::Span,
+    pub local: syn::Local,
+}
+
+///
+/// `{@const <ident>: <type> = <expr>}`
+///
+#[derive(Debug, Clone, Spanned, PartialEq)]
+pub struct ConstDecl {
+    span: utils::Span,
+    pub item: syn::ItemConst,
+}
+
+impl super::External for Rust {
+    const ID: &'static str = "rs";
+    const TAG_PATH_SEPARATOR: &'static str = "::";
+    const TAG_GENERICS_OPEN: &'static str = "::<";
+
+    type Module = syn::File;
+    type Expression = syn::Expr;
+    type Pattern = syn::Pat;
+    type LetDeclaration = LetDecl;
+    type ConstDeclaration = ConstDecl;
+
+    fn module<'src>() -> impl PonyParser<'src, Self::Module> {
+        any()
+            .and_is(just("---").not())
+            .repeated()
+            .collect::<String>()
+            .try_map(|src, span: utils::Span| {
+                Self::parse_str(&src, span, |input| input.parse::<syn::File>())
+                    .map(|(file, _)| file)
+            })
+    }
+
+    fn expression<'src>() -> impl PonyParser<'src, Maybe<Self::Expression>> + Clone {
+        Self::inline_parse_for(syn::Expr::parse_without_eager_brace).validate(|expr, _, emitter| {
+            match expr {
+                Ok(expr) => Maybe::Present(expr),
+                Err(err) => {
+                    let ph = Maybe::Placeholder(Placeholder::at::<ExprMarker>(err.span()));
+                    emitter.emit(err);
+                    ph
+                }
+            }
+        })
+    }
+
+    fn pattern<'src>() -> impl PonyParser<'src, Maybe<Self::Pattern>> + Clone {
+        Self::inline_parse_for(syn::Pat::parse_multi_with_leading_vert).validate(
+            |pat, _, emitter| match pat {
+                Ok(pat) => Maybe::Present(pat),
+                Err(err) => {
+                    let ph = Maybe::Placeholder(Placeholder::at::<PatMarker>(err.span()));
+                    emitter.emit(err);
+                    ph
+                }
+            },
+        )
+    }
+
+    fn let_declaration<'src>() -> impl PonyParser<'src, Self::LetDeclaration> + Clone {
+        just("let ")
+            .rewind()
+            .ignore_then(Self::inline_parse_for(Self::local))
+            .try_map(|local, span| local.map(|local| LetDecl { span, local }))
+    }
+
+    fn const_declaration<'src>() -> impl PonyParser<'src, Self::ConstDeclaration> + Clone {
+        just("const ")
+            .rewind()
+            .ignore_then(Self::inline_parse_for(Self::item_const))
+            .try_map(|item, span| item.map(|item| ConstDecl { span, item }))
+    }
+}
+
+impl HasPlaceholder for syn::Expr {
+    type Marker = ExprMarker;
+}
+
+pub struct ExprMarker;
+
+impl PlaceholderMarker for ExprMarker {
+    const ID: u8 = 96;
+
+    const NAME: &'static str = "RS_EXPRESSION";
+
+    fn new() -> Self {
+        Self
+    }
+}
+
+impl HasPlaceholder for syn::Pat {
+    type Marker = PatMarker;
+}
+
+pub struct PatMarker;
+
+impl PlaceholderMarker for PatMarker {
+    const ID: u8 = 97;
+
+    const NAME: &'static str = "RS_PATTERN";
+
+    fn new() -> Self {
+        Self
+    }
+}
+
+// External nodes are opaque to visitors.
+crate::visit::external!(syn::File, syn::Expr, syn::Pat, LetDecl, ConstDecl);
+
+impl super::ExternalNode for syn::File {
+    fn to_source(&self) -> String {
+        self.to_token_stream().to_string()
+    }
+
+    // Spans of parsed tokens are relative to their source.
+    fn shift(&mut self, _from: usize, _delta: isize) {}
+}
+
+impl super::ExternalNode for syn::Expr {
+    fn to_source(&self) -> String {
+        self.to_token_stream().to_string()
+    }
+
+    // Spans of parsed tokens are relative to their source.
+    fn shift(&mut self, _from: usize, _delta: isize) {}
+}
+
+impl super::ExternalNode for syn::Pat {
+    fn to_source(&self) -> String {
+        self.to_token_stream().to_string()
+    }
+
+    // Spans of parsed tokens are relative to their source.
+    fn shift(&mut self, _from: usize, _delta: isize) {}
+}
+
+impl super::ExternalNode for LetDecl {
+    fn to_source(&self) -> String {
+        self.local.to_token_stream().to_string()
+    }
+
+    fn shift(&mut self, from: usize, delta: isize) {
+        self.span.shift(from, delta);
+    }
+}
+
+impl super::ExternalNode for ConstDecl {
+    fn to_source(&self) -> String {
+        self.item.to_token_stream().to_string()
+    }
+
+    fn shift(&mut self, from: usize, delta: isize) {
+        self.span.shift(from, delta);
+    }
+}
+
+impl utils::Span {
+    ///
+    /// Convert a [proc_macro2::Span] from a token stream parsed
+    /// from source code starting at `self`.
+    ///
+    /// [syn] reports errors at the end of input at an empty span,
+    /// so these are placed at the end of `self`.
+    ///
+    pub fn convert_syn(&self, span: proc_macro2::Span) -> Self {
+        let range = span.byte_range();
+
+        if range.is_empty() {
+            return Self::new(self.context(), self.end()..self.end());
+        }
+
+        self.relative_range(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches::assert_matches;
+
+    use chumsky::Parser;
+
+    use crate::{
+        ponyx::{blocks::LogicBlock, statement::AtStatement, Node},
+        syntax::{external::External, file::File},
+        utils::{input::SourceFile, placeholder::Maybe, Error, Parseable},
+    };
+
+    use super::Rust;
+
+    #[test]
+    fn parse_expr() {
+        let (file, _) = SourceFile::test_file("13+");
+        let res = Rust::expression().parse(file.stream());
+        assert_matches!(res.errors().next(), Some(Error::RustError(_)));
+
+        let (file, _) = SourceFile::test_file("{ count.to_string() }");
+        let res = Node::<Rust>::parser().parse(file.stream());
+        assert!(res.has_output() && !res.has_errors());
+    }
+
+    #[test]
+    fn parse_module() {
+        let (file, _) = SourceFile::test_file(
+            r#"
+            use avpony::prelude::*;
+
+            const NAME: &str = "CLICK ME!";
+            static mut CLICKED: bool = false;
+            "#,
+        );
+        let res = Rust::module().parse(file.stream()).into_result();
+        assert!(res.is_ok())
+    }
+
+    #[test]
+    fn parse_blocks() {
+        let (file, _) = SourceFile::test_file(
+            r#"{#for dog in kennel.iter() by dog.name}{@let name: &str = &dog.name}{@const LIMIT: usize = 3}{name}{/for}"#,
+        );
+        let res = Node::<Rust>::parser().parse(file.stream());
+        assert!(res.has_output() && !res.has_errors());
+
+        assert_matches!(
+            res.output(),
+            Some(Node::Block(LogicBlock::For(block)))
+                if matches!(&block.key, Some(Maybe::Present(syn::Expr::Field(_))))
+                && matches!(block.children.as_slice(), [
+                    Node::Statement(AtStatement::Let(_)),
+                    Node::Statement(AtStatement::Const(_)),
+                    Node::Mustache(_),
+                ])
+        );
+    }
+
+    #[test]
+    fn parse_file() {
+        let (source, _) = SourceFile::test_file(
+            r#"
+        static CLICKED: AtomicUsize = AtomicUsize::new(0);
+        ---
+        <Button on:click={|| CLICKED.fetch_add(1, Ordering::Relaxed)}>
+            Clicked {CLICKED.load(Ordering::Relaxed)} times!
+        </Button>
+        "#,
+        );
+
+        let res = File::<Rust>::parser().parse(source.stream());
+        assert!(res.has_output() && !res.has_errors());
+    }
+}
@@ -8,15 +8,20 @@ use chumsky::{
     span::Span,
     IterParser, Parser,
 };
-use swc_common::BytePos;
+use swc_common::{sync::Lrc, BytePos, SourceMap};
 use swc_ecma_ast::VarDeclKind;
+use swc_ecma_codegen::{text_writer::JsWriter, Emitter, Node as _};
 use swc_ecma_parser::{StringInput, Syntax};
+use swc_ecma_visit::{VisitMut, VisitMutWith};
 
-use crate::utils::{
-    self,
-    error::external::typescript::ConvertTSError,
-    placeholder::{HasPlaceholder, Marker as PlaceholderMarker, Maybe, Placeholder},
-    Error, PonyParser, Spanned,
+use crate::{
+    syntax::file::File,
+    utils::{
+        self,
+        error::external::typescript::ConvertTSError,
+        placeholder::{HasPlaceholder, Marker as PlaceholderMarker, Maybe, Placeholder},
+        Error, PonyParser, Spanned,
+    },
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -69,7 +74,8 @@ impl TypeScript {
                 stream.rewind(unsafe { Marker::from_raw(new_start, 0) });
             })
             .inspect_err(|err| {
-                let new_start = err.span().end();
+                // Before the token it failed at, e.g. the `}` closing a mustache.
+                let new_start = err.span().start();
                 stream.rewind(unsafe { Marker::from_raw(new_start, 0) });
             }))
         })
@@ -92,19 +98,36 @@ impl TypeScript {
     }
 }
 
+///
+/// A [TC39-style](https://tc39.es/proposal-pattern-matching/#sec-pattern-matching)
+/// pattern for `{:when <pat>}` leaves.
+///
+/// TC39 patterns share their syntax with expressions
+/// (literals, identifiers, array and object shapes),
+/// so we re-use swc's expression parser for them.
+///
+#[derive(Debug, Clone, Spanned, PartialEq)]
+pub struct Pattern {
+    span: utils::Span,
+    pub expr: swc_ecma_ast::Expr,
+}
+
 #[derive(Debug, Clone, Spanned, PartialEq)]
 pub struct VarDecl {
     span: utils::Span,
-    ty: swc_ecma_ast::VarDeclKind,
-    pat: Maybe<swc_ecma_ast::Pat>,
-    init: Maybe<swc_ecma_ast::Expr>,
+    pub ty: swc_ecma_ast::VarDeclKind,
+    pub pat: Maybe<swc_ecma_ast::Pat>,
+    pub init: Maybe<swc_ecma_ast::Expr>,
 }
 
 impl super::External for TypeScript {
     const ID: &'static str = "ts";
+    const TAG_PATH_SEPARATOR: &'static str = ".";
+    const TAG_GENERICS_OPEN: &'static str = "<";
 
     type Module = swc_ecma_ast::Module;
     type Expression = swc_ecma_ast::Expr;
+    type Pattern = Pattern;
     type LetDeclaration = VarDecl;
     type ConstDeclaration = VarDecl;
 
@@ -114,7 +137,7 @@ impl super::External for TypeScript {
             .repeated()
             .collect::<String>()
             .try_map(|src, span: utils::Span| {
-                Self::parse_str(&src, span.clone(), swc_ecma_parser::Parser::parse_module)
+                Self::parse_str(&src, span, swc_ecma_parser::Parser::parse_module)
             })
     }
 
@@ -131,6 +154,22 @@ impl super::External for TypeScript {
         )
     }
 
+    fn pattern<'src>() -> impl PonyParser<'src, Maybe<Self::Pattern>> + Clone {
+        Self::inline_parse_for(|parser| parser.parse_expr().map(|b| *b)).validate(
+            |expr, ctx, emitter| match expr {
+                Ok(expr) => Maybe::Present(Pattern {
+                    span: ctx.span(),
+                    expr,
+                }),
+                Err(err) => {
+                    let ph = Maybe::Placeholder(Placeholder::at::<PatternMarker>(err.span()));
+                    emitter.emit(err);
+                    ph
+                }
+            },
+        )
+    }
+
     fn let_declaration<'src>() -> impl PonyParser<'src, Self::LetDeclaration> + Clone {
         just("let ")
             .ignore_then(Self::variable_declaration().padded())
@@ -147,11 +186,19 @@ impl super::External for TypeScript {
             .ignore_then(Self::variable_declaration().padded())
             .map_with(|(pat, init), ctx| VarDecl {
                 span: ctx.span(),
-                ty: VarDeclKind::Let,
+                ty: VarDeclKind::Const,
                 pat,
                 init,
             })
     }
+
+    fn format_module(src: &str) -> Option<String> {
+        crate::format::typescript::module(src)
+    }
+
+    fn check(file: &File<Self>) -> Vec<Error> {
+        crate::semantic::scope::resolve(file)
+    }
 }
 
 impl HasPlaceholder for swc_ecma_ast::Expr {
@@ -186,19 +233,154 @@ impl PlaceholderMarker for PatMarker {
     }
 }
 
+impl HasPlaceholder for Pattern {
+    type Marker = PatternMarker;
+}
+
+pub struct PatternMarker;
+
+impl PlaceholderMarker for PatternMarker {
+    const ID: u8 = 82;
+
+    const NAME: &'static str = "TS_MATCH_PATTERN";
+
+    fn new() -> Self {
+        Self
+    }
+}
+
+// External nodes are opaque to visitors.
+crate::visit::external!(swc_ecma_ast::Module, swc_ecma_ast::Expr, Pattern, VarDecl);
+
+///
+/// Print a node with swc's codegen.
+///
+fn print(node: &impl swc_ecma_codegen::Node) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+
+    let mut buf = Vec::new();
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
+        };
+
+        node.emit_with(&mut emitter)
+            .expect("writing to a `Vec` cannot fail");
+    }
+
+    String::from_utf8(buf).expect("swc only emits UTF-8")
+}
+
+///
+/// Moves swc's positions, see [super::ExternalNode::shift].
+///
+struct Shift {
+    from: usize,
+    delta: isize,
+}
+
+impl VisitMut for Shift {
+    fn visit_mut_span(&mut self, span: &mut swc_common::Span) {
+        for pos in [&mut span.lo, &mut span.hi] {
+            if pos.0 as usize >= self.from {
+                pos.0 = (pos.0 as isize + self.delta) as u32;
+            }
+        }
+    }
+}
+
+impl super::ExternalNode for swc_ecma_ast::Module {
+    fn to_source(&self) -> String {
+        print(self)
+    }
+
+    fn shift(&mut self, from: usize, delta: isize) {
+        self.visit_mut_with(&mut Shift { from, delta });
+    }
+}
+
+impl super::ExternalNode for swc_ecma_ast::Expr {
+    fn to_source(&self) -> String {
+        print(self)
+    }
+
+    fn shift(&mut self, from: usize, delta: isize) {
+        self.visit_mut_with(&mut Shift { from, delta });
+    }
+}
+
+impl super::ExternalNode for Pattern {
+    fn to_source(&self) -> String {
+        print(&self.expr)
+    }
+
+    fn shift(&mut self, from: usize, delta: isize) {
+        self.span.shift(from, delta);
+        self.expr.visit_mut_with(&mut Shift { from, delta });
+    }
+}
+
+impl super::ExternalNode for VarDecl {
+    fn to_source(&self) -> String {
+        let kind = match self.ty {
+            VarDeclKind::Var => "var",
+            VarDeclKind::Let => "let",
+            VarDeclKind::Const => "const",
+        };
+
+        let pat = match &self.pat {
+            Maybe::Present(pat) => print(pat),
+            Maybe::Placeholder(_) => String::new(),
+        };
+
+        let init = match &self.init {
+            Maybe::Present(init) => print(init),
+            Maybe::Placeholder(_) => String::new(),
+        };
+
+        format!("{kind} {pat} = {init}")
+    }
+
+    fn shift(&mut self, from: usize, delta: isize) {
+        self.span.shift(from, delta);
+
+        let mut shift = Shift { from, delta };
+        match &mut self.pat {
+            Maybe::Present(pat) => pat.visit_mut_with(&mut shift),
+            Maybe::Placeholder(placeholder) => placeholder.shift(from, delta),
+        }
+        match &mut self.init {
+            Maybe::Present(init) => init.visit_mut_with(&mut shift),
+            Maybe::Placeholder(placeholder) => placeholder.shift(from, delta),
+        }
+    }
+}
+
 impl utils::Span {
+    ///
+    /// The span of `ecma`, in the same file as `self`.
+    ///
+    /// Source code is given to swc at its offset in the file,
+    /// so its positions are already offsets in the file.
+    ///
     pub fn convert_ecma(&self, ecma: swc_common::Span) -> Self {
-        Self::new(
-            self.context().clone(),
-            ((ecma.lo.0 as usize) - 1)..((ecma.hi.0 as usize) - 1),
-        )
+        Self::new(self.context(), (ecma.lo.0 as usize)..(ecma.hi.0 as usize))
     }
 }
+
 #[cfg(test)]
 mod tests {
     use chumsky::Parser;
 
-    use crate::{syntax::external::External, utils::input::SourceFile};
+    use swc_ecma_ast::VarDeclKind;
+
+    use crate::{
+        syntax::external::External,
+        utils::{diagnostic::Severity, input::SourceFile, ErrorI},
+    };
 
     use super::TypeScript;
 
@@ -224,4 +406,34 @@ mod tests {
         let res = TypeScript::module().parse(file.stream()).into_result();
         assert!(res.is_ok())
     }
+
+    #[test]
+    fn const_declaration() {
+        let (file, _) = SourceFile::test_file("const a = 1");
+        let decl = TypeScript::const_declaration()
+            .parse(file.stream())
+            .into_result()
+            .unwrap();
+        assert_eq!(decl.ty, VarDeclKind::Const);
+    }
+
+    #[test]
+    fn error_message() {
+        let (file, _) = SourceFile::test_file("const x = ;");
+        let errors = TypeScript::module().parse(file.stream()).into_errors();
+        let [error] = errors.as_slice() else {
+            panic!("{errors:?}");
+        };
+
+        let diagnostic = error.clone().to_diagnostic().plain();
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.code.as_deref(), Some("TS000"));
+
+        let label = diagnostic.primary().unwrap();
+        assert_eq!(label.span.range(), 10..11);
+        assert!(label
+            .message
+            .as_ref()
+            .is_some_and(|message| !message.is_empty()));
+    }
 }
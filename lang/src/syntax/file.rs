@@ -5,7 +5,7 @@
 //!
 //!
 
-use avpony_macros::Spanned;
+use avpony_macros::{Spanned, Visit};
 use chumsky::{primitive::just, Parser};
 
 use crate::{
@@ -23,21 +23,56 @@ use super::external::External;
 /// * A "fence" `---`, to seperate the sections;
 /// * PonyX nodes, for UI.
 ///
-#[derive(Debug, Clone, PartialEq, Spanned)]
+#[derive(Debug, Clone, PartialEq, Spanned, Visit)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "kind", bound = "")
+)]
 pub struct File<Ext: External> {
     span: Span,
-    module: Ext::Module,
-    pony: ponyx::Node<Ext>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::external")
+    )]
+    pub module: Ext::Module,
+
+    ///
+    /// The `---` between the sections.
+    ///
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub fence: Span,
+    pub pony: ponyx::Node<Ext>,
+}
+
+impl<Ext: External> File<Ext> {
+    ///
+    /// A file of already parsed sections, see [crate::incremental].
+    ///
+    pub(crate) fn from_parts(
+        span: Span,
+        module: Ext::Module,
+        fence: Span,
+        pony: ponyx::Node<Ext>,
+    ) -> Self {
+        Self {
+            span,
+            module,
+            fence,
+            pony,
+        }
+    }
 }
 
 impl<E: External + 'static> Parseable for File<E> {
     fn parser<'src>() -> impl PonyParser<'src, Self> {
         E::module()
-            .then_ignore(just("---").padded())
+            .then(just("---").map_with(|_, ctx| ctx.span()).padded())
             .then(ponyx::Node::parser().padded())
-            .map_with(|(module, pony), ctx| File {
+            .map_with(|((module, fence), pony), ctx| File {
                 span: ctx.span(),
                 module,
+                fence,
                 pony,
             })
     }
@@ -0,0 +1,264 @@
+//!
+//! A structured model of errors, which every [Error](super::Error)
+//! converts into (see [ErrorI::to_diagnostic](super::ErrorI::to_diagnostic)),
+//! for terminals (as [ariadne] reports), editors, and other tools
+//! (see [crate::emit]).
+//!
+//! Text may hold terminal colors (from [ariadne::Fmt]),
+//! which [Diagnostic::plain] removes.
+//!
+
+use ariadne::Color;
+
+use super::Span;
+
+///
+/// How serious a diagnostic is.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Advice,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Advice => "advice",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+
+    fn report_kind(self) -> ariadne::ReportKind<'static> {
+        match self {
+            Self::Advice => ariadne::ReportKind::Advice,
+            Self::Warning => ariadne::ReportKind::Warning,
+            Self::Error => ariadne::ReportKind::Error,
+        }
+    }
+}
+
+///
+/// A message about a span of source code.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: Option<String>,
+
+    ///
+    /// Only used by terminals.
+    ///
+    pub color: Option<Color>,
+}
+
+impl Label {
+    pub fn new(span: Span) -> Self {
+        Self {
+            span,
+            message: None,
+            color: None,
+        }
+    }
+
+    pub fn with_message(mut self, message: impl ToString) -> Self {
+        self.message = Some(message.to_string());
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+///
+/// A suggested edit, replacing a span of source code.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub span: Span,
+    pub replacement: String,
+
+    ///
+    /// What it does, e.g. "Add a unit".
+    ///
+    pub message: String,
+}
+
+impl Fix {
+    pub fn new(span: Span, replacement: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            replacement: replacement.into(),
+            message: message.into(),
+        }
+    }
+}
+
+///
+/// An error, or warning, at a [Span] of source code.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    ///
+    /// Its code (see [ErrorI::code](super::ErrorI::code)).
+    ///
+    pub code: Option<String>,
+    pub span: Span,
+    pub message: String,
+
+    ///
+    /// The first label is the primary one, the rest are secondary.
+    ///
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    pub fixes: Vec<Fix>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, span: Span) -> Self {
+        Self {
+            severity,
+            code: None,
+            span,
+            message: String::new(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+            fixes: Vec::new(),
+        }
+    }
+
+    pub fn with_code(mut self, code: impl ToString) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    pub fn with_message(mut self, message: impl ToString) -> Self {
+        self.message = message.to_string();
+        self
+    }
+
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.add_label(label);
+        self
+    }
+
+    pub fn with_labels(mut self, labels: impl IntoIterator<Item = Label>) -> Self {
+        self.labels.extend(labels);
+        self
+    }
+
+    pub fn add_label(&mut self, label: Label) {
+        self.labels.push(label);
+    }
+
+    pub fn with_note(mut self, note: impl ToString) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl ToString) -> Self {
+        self.help.push(help.to_string());
+        self
+    }
+
+    pub fn with_fix(mut self, fix: Fix) -> Self {
+        self.fixes.push(fix);
+        self
+    }
+
+    ///
+    /// The primary label, if any.
+    ///
+    pub fn primary(&self) -> Option<&Label> {
+        self.labels.first()
+    }
+
+    ///
+    /// This diagnostic, without any terminal colors in its text.
+    ///
+    pub fn plain(mut self) -> Self {
+        self.message = plain(&self.message);
+        for label in &mut self.labels {
+            label.message = label.message.as_deref().map(plain);
+        }
+        for text in self.notes.iter_mut().chain(&mut self.help) {
+            *text = plain(text);
+        }
+        for fix in &mut self.fixes {
+            fix.message = plain(&fix.message);
+        }
+
+        self
+    }
+
+    ///
+    /// Make a nice-looking [ariadne] report of this diagnostic.
+    ///
+    pub fn to_report(self) -> ariadne::Report<'static, Span> {
+        let mut report = self.span.build_report(self.severity.report_kind());
+
+        if let Some(code) = self.code {
+            report = report.with_code(code);
+        }
+
+        if !self.message.is_empty() {
+            report = report.with_message(self.message);
+        }
+
+        report = report.with_labels(self.labels.into_iter().map(|label| {
+            let mut report_label = ariadne::Label::new(label.span);
+            if let Some(message) = label.message {
+                report_label = report_label.with_message(message);
+            }
+            if let Some(color) = label.color {
+                report_label = report_label.with_color(color);
+            }
+
+            report_label
+        }));
+
+        // ariadne only shows one note, and one help.
+        let notes = self.notes.join("\n");
+        if !notes.is_empty() {
+            report = report.with_note(notes);
+        }
+
+        let help = self
+            .help
+            .into_iter()
+            .chain(self.fixes.into_iter().map(|fix| fix.message))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !help.is_empty() {
+            report = report.with_help(help);
+        }
+
+        report.finish()
+    }
+}
+
+///
+/// `text` without ANSI escape sequences.
+///
+pub fn plain(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            chars.by_ref().find(|ch| ch.is_ascii_alphabetic());
+            continue;
+        }
+        out.push(ch);
+    }
+
+    out
+}
@@ -0,0 +1,160 @@
+//!
+//! Warnings for accessibility (see [crate::semantic::a11y]).
+//!
+
+use ariadne::{ColorGenerator, Fmt};
+use avpony_macros::ErrorType;
+
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    Span,
+};
+
+use super::ErrorI;
+
+#[ErrorType(crate::utils::Error)]
+pub struct MissingAlt {
+    span: Span,
+    component: String,
+}
+
+impl MissingAlt {
+    pub fn new(span: Span, component: String) -> Self {
+        Self { span, component }
+    }
+}
+
+impl ErrorI for MissingAlt {
+    fn code(&self) -> String {
+        "X500".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
+            .with_message("Missing alternative text")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "This `<{}>` has no `a11y:alt`.",
+                        self.component.fg(color)
+                    )),
+            )
+            .with_help(
+                "Add `a11y:alt=\"...\"` describing it, or `a11y:alt=\"\"` if it's only decorative.",
+            )
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnlabelledInteractive {
+    span: Span,
+    component: String,
+}
+
+impl UnlabelledInteractive {
+    pub fn new(span: Span, component: String) -> Self {
+        Self { span, component }
+    }
+}
+
+impl ErrorI for UnlabelledInteractive {
+    fn code(&self) -> String {
+        "X501".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
+            .with_message("Interactive element without a role")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`<{}>` handles clicks, but has no `a11y:role`, or `a11y:label`.",
+                        self.component.fg(color)
+                    )),
+            )
+            .with_help("Add `a11y:role=\"button\"`, and `a11y:label=\"...\"`, or use a `<Button>` instead.")
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct DuplicateLabel {
+    span: Span,
+    label: String,
+    first: Span,
+}
+
+impl DuplicateLabel {
+    pub fn new(span: Span, label: String, first: Span) -> Self {
+        Self { span, label, first }
+    }
+}
+
+impl ErrorI for DuplicateLabel {
+    fn code(&self) -> String {
+        "X502".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
+            .with_message("Duplicate label")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "The label `{}` is used more than once.",
+                        self.label.fg(color)
+                    )),
+            )
+            .with_label(
+                Label::new(self.first)
+                    .with_color(colors.next())
+                    .with_message("First used here."),
+            )
+            .with_help("Give each element a label describing what sets it apart.")
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct EmptyButton {
+    span: Span,
+}
+
+impl EmptyButton {
+    pub fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl ErrorI for EmptyButton {
+    fn code(&self) -> String {
+        "X503".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
+            .with_message("Empty button")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(colors.next())
+                    .with_message("This button has no content, or label."),
+            )
+            .with_help("Add some text inside it, or `a11y:label=\"...\"`.")
+    }
+}
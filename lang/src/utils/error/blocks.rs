@@ -2,35 +2,42 @@
 //! ## Errors for Logic Blocks
 //!
 
-use ariadne::{ColorGenerator, Label, ReportKind};
+use ariadne::{ColorGenerator, Fmt};
 use avpony_macros::ErrorType;
 
-use crate::utils::Span;
+use crate::utils::{
+    diagnostic::{Diagnostic, Fix, Label, Severity},
+    suggest, Span,
+};
 
 use super::ErrorI;
 
 #[ErrorType(crate::utils::Error)]
 pub struct UnreachableBranch {
     span: Span,
-    if_block_span: Span,
+    block_span: Span,
+    keyword: &'static str,
 }
 
 impl UnreachableBranch {
-    pub fn new(span: Span, if_block_span: Span) -> Self {
+    pub fn new(span: Span, block_span: Span, keyword: &'static str) -> Self {
         Self {
             span,
-            if_block_span,
+            block_span,
+            keyword,
         }
     }
 }
 
 impl ErrorI for UnreachableBranch {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "X100".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
-        self.span
-            .clone()
-            .build_report(ReportKind::Warning)
-            .with_code("X100")
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
             .with_message("Unreachable code")
             .with_label(
                 Label::new(self.span)
@@ -38,10 +45,277 @@ impl ErrorI for UnreachableBranch {
                     .with_message("These branches are unreachable."),
             )
             .with_label(
-                Label::new(self.if_block_span)
+                Label::new(self.block_span)
+                    .with_color(colors.next())
+                    .with_message(format!("Inside this `{{#{} ...}}` block.", self.keyword)),
+            )
+            .with_fix(Fix::new(self.span, "", "Remove the unreachable branches."))
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct MissingDefaultArm {
+    span: Span,
+}
+
+impl MissingDefaultArm {
+    pub fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl ErrorI for MissingDefaultArm {
+    fn code(&self) -> String {
+        "X102".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
+            .with_message("Missing `{:default}` leaf")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(colors.next())
+                    .with_message("Nothing will be rendered if none of these patterns match."),
+            )
+            .with_help("Add a `{:default}` leaf at the end of this block.")
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnknownBlock {
+    span: Span,
+
+    ///
+    /// `#` for blocks, or `:` for leaves.
+    ///
+    sigil: char,
+    keyword: String,
+
+    ///
+    /// The closest keyword, if any.
+    ///
+    suggestion: Option<&'static str>,
+}
+
+impl UnknownBlock {
+    pub fn new(span: Span, sigil: char, keyword: String, suggestion: Option<&'static str>) -> Self {
+        Self {
+            span,
+            sigil,
+            keyword,
+            suggestion,
+        }
+    }
+}
+
+impl ErrorI for UnknownBlock {
+    fn code(&self) -> String {
+        "X103".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+        let (message, kind) = match self.sigil {
+            '#' => ("Unknown logic block", "logic block"),
+            _ => ("Unknown leaf", "leaf"),
+        };
+
+        let diagnostic = Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message(message)
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`{{{}{}}}` isn't a {kind}.",
+                        self.sigil,
+                        self.keyword.fg(color)
+                    )),
+            );
+
+        match self.suggestion {
+            Some(suggestion) => diagnostic
+                .with_note(suggest::did_you_mean(format!(
+                    "{{{}{suggestion}}}",
+                    self.sigil
+                )))
+                .with_fix(Fix::new(
+                    self.span,
+                    suggestion,
+                    format!("Replace `{}` with `{suggestion}`.", self.keyword),
+                )),
+            None => diagnostic,
+        }
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnclosedBlock {
+    ///
+    /// Where the block ended, without its `{/keyword}`.
+    ///
+    span: Span,
+    opener: Span,
+    keyword: &'static str,
+}
+
+impl UnclosedBlock {
+    pub fn new(span: Span, opener: Span, keyword: &'static str) -> Self {
+        Self {
+            span,
+            opener,
+            keyword,
+        }
+    }
+}
+
+impl ErrorI for UnclosedBlock {
+    fn code(&self) -> String {
+        "X104".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let opener = colors.next();
+        let closing = format!("{{/{}}}", self.keyword);
+
+        Diagnostic::new(Severity::Error, self.opener)
+            .with_code(self.code())
+            .with_message("Unclosed logic block")
+            .with_label(
+                Label::new(self.opener)
+                    .with_color(opener)
+                    .with_message(format!(
+                        "This `{}` block is never closed.",
+                        format!("{{#{}}}", self.keyword).fg(opener)
+                    )),
+            )
+            .with_label(
+                Label::new(self.span)
+                    .with_color(colors.next())
+                    .with_message(format!("Expected `{closing}` here.")),
+            )
+            .with_fix(Fix::new(
+                self.span,
+                closing.clone(),
+                format!("Close it with `{closing}`."),
+            ))
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct MismatchedBlock {
+    span: Span,
+    opener: Span,
+    keyword: &'static str,
+
+    ///
+    /// The keyword of the `{/keyword}` closing it.
+    ///
+    closing: String,
+}
+
+impl MismatchedBlock {
+    pub fn new(span: Span, opener: Span, keyword: &'static str, closing: String) -> Self {
+        Self {
+            span,
+            opener,
+            keyword,
+            closing,
+        }
+    }
+}
+
+impl ErrorI for MismatchedBlock {
+    fn code(&self) -> String {
+        "X105".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let opener = colors.next();
+        let closing = colors.next();
+        let expected = format!("{{/{}}}", self.keyword);
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Mismatched logic block")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(closing)
+                    .with_message(format!(
+                        "Expected `{}`, got `{}`.",
+                        (&expected).fg(opener),
+                        format!("{{/{}}}", self.closing).fg(closing)
+                    )),
+            )
+            .with_label(
+                Label::new(self.opener)
+                    .with_color(opener)
+                    .with_message(format!(
+                        "`{}` block opened here.",
+                        format!("{{#{}}}", self.keyword).fg(opener)
+                    )),
+            )
+            .with_fix(Fix::new(
+                self.span,
+                expected.clone(),
+                format!("Replace it with `{expected}`."),
+            ))
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct StrayLeaf {
+    span: Span,
+    opener: Span,
+    keyword: &'static str,
+    leaf: String,
+}
+
+impl StrayLeaf {
+    pub fn new(span: Span, opener: Span, keyword: &'static str, leaf: String) -> Self {
+        Self {
+            span,
+            opener,
+            keyword,
+            leaf,
+        }
+    }
+}
+
+impl ErrorI for StrayLeaf {
+    fn code(&self) -> String {
+        "X106".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let leaf = colors.next();
+
+        // Which blocks this leaf does go in.
+        let blocks = match self.leaf.as_str() {
+            "else" => "`{#if}`, or `{#for}` blocks",
+            "then" | "catch" => "`{#await}` blocks",
+            _ => "`{#match}` blocks",
+        };
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Leaf outside of its block")
+            .with_label(Label::new(self.span).with_color(leaf).with_message(format!(
+                "`{}` can't be in a `{{#{}}}` block.",
+                format!("{{:{}}}", self.leaf).fg(leaf),
+                self.keyword
+            )))
+            .with_label(
+                Label::new(self.opener)
                     .with_color(colors.next())
-                    .with_message("Inside this `{:if ...}` block."),
+                    .with_message("In this block."),
             )
-            .finish()
+            .with_note(format!("`{{:{}}}` only goes in {blocks}.", self.leaf))
     }
 }
@@ -0,0 +1,287 @@
+//!
+//! Errors for components, checked against their schemas
+//! (see [crate::semantic::components]).
+//!
+
+use ariadne::{ColorGenerator, Fmt};
+use avpony_macros::ErrorType;
+
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    suggest, Span,
+};
+
+use super::ErrorI;
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnknownComponent {
+    span: Span,
+    name: String,
+
+    ///
+    /// The closest known component, if any.
+    ///
+    suggestion: Option<String>,
+}
+
+impl UnknownComponent {
+    pub fn new(span: Span, name: String, suggestion: Option<String>) -> Self {
+        Self {
+            span,
+            name,
+            suggestion,
+        }
+    }
+}
+
+impl ErrorI for UnknownComponent {
+    fn code(&self) -> String {
+        "X300".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        let diagnostic = Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Unknown component")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!("There's no component `{}`.", self.name.fg(color))),
+            );
+
+        match self.suggestion {
+            Some(suggestion) => {
+                diagnostic.with_note(suggest::did_you_mean(format!("<{suggestion}>")))
+            }
+            None => diagnostic,
+        }
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnknownAttribute {
+    span: Span,
+    name: String,
+    component: String,
+}
+
+impl UnknownAttribute {
+    pub fn new(span: Span, name: String, component: String) -> Self {
+        Self {
+            span,
+            name,
+            component,
+        }
+    }
+}
+
+impl ErrorI for UnknownAttribute {
+    fn code(&self) -> String {
+        "X301".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Unknown attribute")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`<{}>` doesn't accept `{}`.",
+                        self.component,
+                        self.name.fg(color)
+                    )),
+            )
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct DuplicateAttribute {
+    span: Span,
+    name: String,
+    first: Span,
+}
+
+impl DuplicateAttribute {
+    pub fn new(span: Span, name: String, first: Span) -> Self {
+        Self { span, name, first }
+    }
+}
+
+impl ErrorI for DuplicateAttribute {
+    fn code(&self) -> String {
+        "X302".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Duplicate attribute")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`{}` is given more than once.",
+                        self.name.fg(color)
+                    )),
+            )
+            .with_label(
+                Label::new(self.first)
+                    .with_color(colors.next())
+                    .with_message("First given here."),
+            )
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct MissingAttribute {
+    span: Span,
+    name: String,
+    component: String,
+}
+
+impl MissingAttribute {
+    pub fn new(span: Span, name: String, component: String) -> Self {
+        Self {
+            span,
+            name,
+            component,
+        }
+    }
+}
+
+impl ErrorI for MissingAttribute {
+    fn code(&self) -> String {
+        "X303".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Missing attribute")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`<{}>` requires `{}`.",
+                        self.component,
+                        self.name.fg(color)
+                    )),
+            )
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct InvalidChild {
+    span: Span,
+    child: String,
+    component: String,
+    allowed: Vec<String>,
+}
+
+impl InvalidChild {
+    ///
+    /// `child` describes what was found, like `<Row>`, or `text`;
+    /// `allowed` are the only components allowed instead (if any).
+    ///
+    pub fn new(span: Span, child: String, component: String, allowed: Vec<String>) -> Self {
+        Self {
+            span,
+            child,
+            component,
+            allowed,
+        }
+    }
+}
+
+impl ErrorI for InvalidChild {
+    fn code(&self) -> String {
+        "X304".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        let help = match self.allowed.as_slice() {
+            [] => format!("`<{}>` can't have any children.", self.component),
+            allowed => format!(
+                "`<{}>` can only contain {}.",
+                self.component,
+                allowed
+                    .iter()
+                    .map(|name| format!("`<{name}>`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Invalid child")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "{} isn't allowed inside `<{}>`.",
+                        self.child.fg(color),
+                        self.component
+                    )),
+            )
+            .with_help(help)
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnknownSlot {
+    span: Span,
+    name: String,
+    component: String,
+}
+
+impl UnknownSlot {
+    pub fn new(span: Span, name: String, component: String) -> Self {
+        Self {
+            span,
+            name,
+            component,
+        }
+    }
+}
+
+impl ErrorI for UnknownSlot {
+    fn code(&self) -> String {
+        "X305".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Unknown slot")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`<{}>` has no slot `{}`.",
+                        self.component,
+                        self.name.fg(color)
+                    )),
+            )
+    }
+}
@@ -2,10 +2,14 @@
 //! ## Generic 'Expected' errors
 //!
 
-use ariadne::{ColorGenerator, Label, ReportKind};
+use ariadne::ColorGenerator;
 use avpony_macros::ErrorType;
 
-use crate::utils::{placeholder::Placeholder, Span, Spanned};
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    placeholder::Placeholder,
+    Span, Spanned,
+};
 
 #[ErrorType(crate::utils::Error)]
 pub struct Expected {
@@ -23,18 +27,19 @@ impl Expected {
 }
 
 impl super::ErrorI for Expected {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        format!("S{}", 132 + self.placeholder.id())
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
-        self.span
-            .clone()
-            .build_report(ReportKind::Error)
-            .with_code(format!("S{}", 132 + self.placeholder.id()))
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message(format!("Expected {}", self.placeholder.expected()))
             .with_label(
                 Label::new(self.span)
                     .with_color(colors.next())
                     .with_message(format!("Expected {} here.", self.placeholder.expected())),
             )
-            .finish()
     }
 }
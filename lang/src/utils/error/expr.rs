@@ -1,7 +1,10 @@
-use ariadne::{ColorGenerator, Label, ReportKind};
+use ariadne::ColorGenerator;
 use avpony_macros::ErrorType;
 
-use crate::utils::Span;
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    Span,
+};
 
 #[ErrorType(crate::utils::Error)]
 pub struct ExpectedExpr {
@@ -15,19 +18,20 @@ impl ExpectedExpr {
 }
 
 impl super::ErrorI for ExpectedExpr {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S999".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
 
-        self.span
-            .clone()
-            .build_report(ReportKind::Error)
-            .with_code("S999")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Expected expression")
             .with_label(
                 Label::new(self.span)
                     .with_color(colors.next())
                     .with_message("Expected an expression here"),
             )
-            .finish()
     }
 }
@@ -2,4 +2,5 @@
 //! Errors from external languages.
 //!
 
+pub mod rust;
 pub mod typescript;
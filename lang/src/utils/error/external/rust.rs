@@ -0,0 +1,69 @@
+//!
+//! Rust Errors
+//!
+
+use ariadne::ColorGenerator;
+use avpony_macros::ErrorType;
+
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    ErrorI, Span,
+};
+
+///
+/// A representation of a [syn] parser error.
+///
+#[ErrorType(crate::utils::Error)]
+pub struct RustError {
+    span: Span,
+    message: String,
+    extra: Vec<(Span, String)>,
+}
+
+impl ErrorI for RustError {
+    fn code(&self) -> String {
+        "RS000".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Rust syntax error")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(colors.next())
+                    .with_message(self.message),
+            )
+            .with_labels(self.extra.into_iter().map(|(span, message)| {
+                Label::new(span)
+                    .with_color(colors.next())
+                    .with_message(message)
+            }))
+    }
+}
+
+pub trait ConvertSynError {
+    fn convert(self, span: Span) -> RustError;
+}
+
+impl ConvertSynError for syn::Error {
+    fn convert(self, span: Span) -> RustError {
+        // A `syn::Error` can hold several messages (see `syn::Error::combine`):
+        // the first is the main error, and the rest are extra labels.
+        let mut errors = self
+            .into_iter()
+            .map(|err| (span.convert_syn(err.span()), err.to_string()));
+
+        let (main_span, message) = errors
+            .next()
+            .unwrap_or_else(|| (span, "Invalid Rust syntax.".to_string()));
+
+        RustError {
+            span: main_span,
+            message,
+            extra: errors.collect(),
+        }
+    }
+}
@@ -2,39 +2,65 @@
 //! TypeScript Errors
 //!
 
-use ariadne::{Label, Report, ReportKind};
+use ariadne::ColorGenerator;
 use avpony_macros::ErrorType;
-use swc_common::errors::{EmitterWriter, Handler};
+use swc_common::errors::{DiagnosticId, EmitterWriter, Handler, Level};
 
-use crate::utils::{ErrorI, Span};
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    ErrorI, Span,
+};
 
 ///
-/// A representation of a TypeScript parser (+ linter?) error.
+/// A representation of a TypeScript parser (+ linter?) error,
+/// converted from [swc's diagnostics](swc_common::errors::Diagnostic).
 ///
 #[ErrorType(crate::utils::Error)]
 pub struct TSError {
     span: Span,
     code: String,
-    kind: ReportKind<'static>,
+    severity: Severity,
+    message: String,
+
+    ///
+    /// Other labelled spans, besides the primary one.
+    ///
+    labels: Vec<(Span, String)>,
+    notes: Vec<String>,
+    help: Vec<String>,
 }
 
 impl ErrorI for TSError {
-    fn to_report(self) -> Report<'static, Span> {
-        // TODO: Finish this.
-        self.span
-            .clone()
-            .build_report(self.kind)
-            .with_code(self.code)
-            .with_label(Label::new(self.span).with_message("TS ERROR HERE!"))
-            .finish()
+    fn code(&self) -> String {
+        self.code.clone()
     }
-}
 
-fn dummy_out() -> impl std::io::Write + Sync {
-    std::fs::OpenOptions::new()
-        .write(true)
-        .open("/dev/null")
-        .expect("Not on a supported unix system!")
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+
+        let mut diagnostic = Diagnostic::new(self.severity, self.span)
+            .with_code(self.code())
+            .with_message("TypeScript syntax error")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(colors.next())
+                    .with_message(self.message),
+            )
+            .with_labels(self.labels.into_iter().map(|(span, message)| {
+                Label::new(span)
+                    .with_color(colors.next())
+                    .with_message(message)
+            }));
+
+        for note in self.notes {
+            diagnostic = diagnostic.with_note(note);
+        }
+        for help in self.help {
+            diagnostic = diagnostic.with_help(help);
+        }
+
+        diagnostic
+    }
 }
 
 pub trait ConvertTSError {
@@ -43,31 +69,66 @@ pub trait ConvertTSError {
 
 impl ConvertTSError for swc_ecma_parser::error::Error {
     fn convert(self, span: Span) -> TSError {
-        let emitter = EmitterWriter::new(Box::new(dummy_out()), None, false, true);
+        // Diagnostics need a handler to be built, but are never emitted
+        // (only cancelled), so nothing's ever written to its output.
+        let emitter = EmitterWriter::new(Box::new(std::io::sink()), None, false, true);
         let handler = Handler::with_emitter(true, false, Box::new(emitter));
         let mut diag = self.into_diagnostic(&handler);
-        diag.emit();
 
-        let (kind, code) = diag
-            .get_code()
-            .map(|id| match id {
-                swc_common::errors::DiagnosticId::Error(err) => (ReportKind::Error, err.clone()),
-                swc_common::errors::DiagnosticId::Lint(lint) => (ReportKind::Warning, lint.clone()),
-            })
-            .unwrap_or_else(|| (ReportKind::Error, "TS????".to_string()));
+        let code = match diag.get_code() {
+            Some(DiagnosticId::Error(code) | DiagnosticId::Lint(code)) => code.clone(),
+            None => "TS000".to_string(),
+        };
+        let severity = match diag.level {
+            Level::Warning => Severity::Warning,
+            Level::Note | Level::Help => Severity::Advice,
+            _ => Severity::Error,
+        };
+        diag.cancel();
+
+        let message = |message: &[(String, _)]| {
+            message
+                .iter()
+                .map(|(text, _)| text.as_str())
+                .collect::<String>()
+        };
+
+        let primary = diag
+            .span
+            .primary_span()
+            .filter(|primary| !primary.is_dummy())
+            .map_or(span, |primary| span.convert_ecma(primary));
 
-        let msgs = diag.message.iter().map(|a| a.0.clone()).collect::<String>();
-        println!("{msgs}");
-        // TODO: Add extra info here.
+        // Spans of sub-diagnostics are labelled with their message,
+        // the rest are notes, or help.
+        let mut labels = diag
+            .span
+            .span_labels()
+            .into_iter()
+            .filter(|label| !label.is_primary && !label.span.is_dummy())
+            .filter_map(|label| Some((span.convert_ecma(label.span), label.label?)))
+            .collect::<Vec<_>>();
+        let mut notes = Vec::new();
+        let mut help = Vec::new();
+
+        for child in &diag.children {
+            let text = message(&child.message);
+
+            match child.span.primary_span().filter(|span| !span.is_dummy()) {
+                Some(child_span) => labels.push((span.convert_ecma(child_span), text)),
+                None if child.level == Level::Help => help.push(text),
+                None => notes.push(text),
+            }
+        }
 
         TSError {
-            span: diag
-                .span
-                .primary_span()
-                .map(|s| span.convert_ecma(s))
-                .unwrap_or(span),
+            span: primary,
             code,
-            kind,
+            severity,
+            message: message(&diag.message),
+            labels,
+            notes,
+            help,
         }
     }
 }
@@ -2,31 +2,45 @@
 //! HTML Entity Errors
 //!
 
-use ariadne::{ColorGenerator, Fmt, Label, ReportKind};
+use ariadne::{ColorGenerator, Fmt};
 use avpony_macros::ErrorType;
 
-use crate::utils::Span;
+use crate::utils::{
+    diagnostic::{Diagnostic, Fix, Label, Severity},
+    suggest, Span,
+};
 
 #[ErrorType(crate::utils::Error)]
 pub struct InvalidEntityName {
     span: Span,
     code: String,
+
+    ///
+    /// The closest valid entity name, if any.
+    ///
+    suggestion: Option<String>,
 }
 
 impl InvalidEntityName {
-    pub fn new(span: Span, code: String) -> Self {
-        Self { span, code }
+    pub fn new(span: Span, code: String, suggestion: Option<String>) -> Self {
+        Self {
+            span,
+            code,
+            suggestion,
+        }
     }
 }
 
 impl super::ErrorI for InvalidEntityName {
-    fn to_report(self) -> ariadne::Report<'static, Span> {
+    fn code(&self) -> String {
+        "X000".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
         let color = colors.next();
-        self.span
-            .clone()
-            .build_report(ReportKind::Error)
-            .with_code("X000")
+        let diagnostic = Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Invalid HTML Entity code.")
             .with_label(
                 Label::new(self.span)
@@ -35,7 +49,17 @@ impl super::ErrorI for InvalidEntityName {
                         "`{}` is not a valid HTML entity code.",
                         (&self.code).fg(color)
                     )),
-            )
-            .finish()
+            );
+
+        match self.suggestion {
+            Some(suggestion) => diagnostic
+                .with_note(suggest::did_you_mean(format!("&{suggestion};")))
+                .with_fix(Fix::new(
+                    self.span,
+                    suggestion.clone(),
+                    format!("Replace with `&{suggestion};`."),
+                )),
+            None => diagnostic,
+        }
     }
 }
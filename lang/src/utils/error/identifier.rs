@@ -2,10 +2,13 @@
 //! Identifier Errors
 //!
 
-use ariadne::{ColorGenerator, Fmt, Label};
+use ariadne::{ColorGenerator, Fmt};
 use avpony_macros::ErrorType;
 
-use crate::utils::Span;
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    Span,
+};
 
 use super::ErrorI;
 
@@ -22,19 +25,20 @@ impl ReservedIdentifier {
 }
 
 impl ErrorI for ReservedIdentifier {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S300".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
         let err_color = colors.next();
-        self.span
-            .clone()
-            .build_report(ariadne::ReportKind::Error)
-            .with_code("S300")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Use of reserved identifier")
             .with_label(
                 Label::new(self.span)
                     .with_color(err_color)
                     .with_message(format!("You cannot use `{}`", self.erroneous.fg(err_color))),
             )
-            .finish()
     }
 }
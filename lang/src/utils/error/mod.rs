@@ -2,39 +2,69 @@
 //! ## Error types.
 //!
 
+pub mod a11y;
 pub mod blocks;
+pub mod component;
 pub mod expected;
 pub mod expr;
 pub mod external;
 pub mod html_ref;
 pub mod identifier;
 pub mod number;
+pub mod scope;
 pub mod string;
 pub mod tag;
+pub mod types;
 
-use ariadne::{Color, ColorGenerator, Fmt, Label, ReportKind};
+use a11y::{DuplicateLabel, EmptyButton, MissingAlt, UnlabelledInteractive};
+use ariadne::{Color, ColorGenerator, Fmt};
 use avpony_macros::{Errors, Spanned};
-use blocks::UnreachableBranch;
+use blocks::{
+    MismatchedBlock, MissingDefaultArm, StrayLeaf, UnclosedBlock, UnknownBlock, UnreachableBranch,
+};
 use chumsky::util::MaybeRef;
+use component::{
+    DuplicateAttribute, InvalidChild, MissingAttribute, UnknownAttribute, UnknownComponent,
+    UnknownSlot,
+};
 use expected::Expected;
 use expr::ExpectedExpr;
-use external::typescript::TSError;
+use external::{rust::RustError, typescript::TSError};
 use html_ref::*;
 use identifier::*;
 use number::*;
+use scope::{ShadowedIdentifier, UnresolvedIdentifier, UnusedBinding};
 use string::*;
 use tag::*;
+use types::{MissingField, TypeMismatch, UnexpectedField, UnknownUnit};
 
-use super::{PonyInput, Span};
+use super::{
+    diagnostic::{Diagnostic, Label, Severity},
+    PonyInput, SourceFile, Span,
+};
 
 pub trait ErrorI: Sized + super::Spanned + PartialEq {
-    fn to_report(self) -> ariadne::Report<'static, super::Span>;
+    ///
+    /// This error's code, e.g. `S000` for syntax errors,
+    /// or `X201` for those found after parsing.
+    ///
+    fn code(&self) -> String;
+
+    ///
+    /// Describe this error, as a [Diagnostic].
+    ///
+    fn to_diagnostic(self) -> Diagnostic;
+
+    fn to_report(self) -> ariadne::Report<'static, super::Span> {
+        self.to_diagnostic().to_report()
+    }
 }
 
 #[Errors]
 pub enum Error {
     UnexpectedToken(UnexpectedToken),
     TSError(TSError),
+    RustError(RustError),
     InvalidInt(InvalidInt),
     MultipleNumericDividers(MultipleNumericDividers),
     DivdersBadlyPlaced(DivdersBadlyPlaced),
@@ -46,8 +76,66 @@ pub enum Error {
     ExpectedExpr(ExpectedExpr),
     InvalidEntityName(InvalidEntityName),
     UnclosedTag(UnclosedTag),
+    ImplicitlyClosedTag(ImplicitlyClosedTag),
     Expected(Expected),
     UnreachableBranch(UnreachableBranch),
+    MissingDefaultArm(MissingDefaultArm),
+    UnknownBlock(UnknownBlock),
+    UnclosedBlock(UnclosedBlock),
+    MismatchedBlock(MismatchedBlock),
+    StrayLeaf(StrayLeaf),
+    UnresolvedIdentifier(UnresolvedIdentifier),
+    ShadowedIdentifier(ShadowedIdentifier),
+    UnusedBinding(UnusedBinding),
+    UnknownComponent(UnknownComponent),
+    UnknownAttribute(UnknownAttribute),
+    DuplicateAttribute(DuplicateAttribute),
+    MissingAttribute(MissingAttribute),
+    InvalidChild(InvalidChild),
+    UnknownSlot(UnknownSlot),
+    TypeMismatch(TypeMismatch),
+    UnknownUnit(UnknownUnit),
+    UnexpectedField(UnexpectedField),
+    MissingField(MissingField),
+    MissingAlt(MissingAlt),
+    UnlabelledInteractive(UnlabelledInteractive),
+    DuplicateLabel(DuplicateLabel),
+    EmptyButton(EmptyButton),
+}
+
+impl Error {
+    ///
+    /// Render this error's report against `source`,
+    /// as plain text (without colors).
+    ///
+    pub fn render(self, source: &SourceFile) -> String {
+        let mut rendered = Vec::new();
+        let _ = self.to_report().write(source, &mut rendered);
+
+        super::diagnostic::plain(&String::from_utf8_lossy(&rendered))
+    }
+
+    ///
+    /// This error's code (e.g. `S000`), and message, followed by
+    /// its labels' messages (one per line), for editors, and other tools.
+    ///
+    pub fn summary(self) -> (Option<String>, String) {
+        let diagnostic = self.to_diagnostic().plain();
+
+        let message = std::iter::once(diagnostic.message.as_str())
+            .chain(
+                diagnostic
+                    .labels
+                    .iter()
+                    .filter_map(|label| label.message.as_deref())
+                    .map(str::trim)
+                    .filter(|label| !label.is_empty()),
+            )
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        (diagnostic.code, message)
+    }
 }
 
 impl<'src> chumsky::error::Error<'src, PonyInput<'src>> for Error {
@@ -81,7 +169,11 @@ impl From<UnexpectedToken> for Error {
 }
 
 impl ErrorI for UnexpectedToken {
-    fn to_report(self) -> ariadne::Report<'static, super::Span> {
+    fn code(&self) -> String {
+        "S000".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
 
         let color = colors.next();
@@ -107,16 +199,13 @@ impl ErrorI for UnexpectedToken {
             .intersperse(", ".to_string())
             .collect::<String>();
 
-        self.span
-            .clone()
-            .build_report(ReportKind::Error)
-            .with_code("S000")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Unexpected Token")
             .with_label(
                 Label::new(self.span)
                     .with_color(color)
                     .with_message(format!("Found `{found}`, expected {expected}.")),
             )
-            .finish()
     }
 }
@@ -4,10 +4,16 @@
 
 use std::num::ParseIntError;
 
-use ariadne::{Color, ColorGenerator, Fmt, Label};
+use ariadne::{Color, ColorGenerator, Fmt};
 use avpony_macros::ErrorType;
 
-use crate::{lexical::number, utils::Span};
+use crate::{
+    lexical::number,
+    utils::{
+        diagnostic::{Diagnostic, Fix, Label, Severity},
+        Span,
+    },
+};
 
 use super::ErrorI;
 
@@ -29,27 +35,28 @@ impl InvalidInt {
 }
 
 impl ErrorI for InvalidInt {
-    fn to_report(self) -> ariadne::Report<'static, Span> {
-        let (code, message) = match self.internal.kind() {
-            std::num::IntErrorKind::PosOverflow => {
-                ("S100", "Integer positive overflow: Integer too large.")
-            }
-            std::num::IntErrorKind::NegOverflow => {
-                ("S101", "Integer negative overflow: Integer too small.")
-            }
+    fn code(&self) -> String {
+        match self.internal.kind() {
+            std::num::IntErrorKind::NegOverflow => "S101",
+            _ => "S100",
+        }
+        .to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let message = match self.internal.kind() {
+            std::num::IntErrorKind::PosOverflow => "Integer positive overflow: Integer too large.",
+            std::num::IntErrorKind::NegOverflow => "Integer negative overflow: Integer too small.",
             _ => unimplemented!(), // The rest of these IntErrorKind-s are already covered by parsing.
         };
 
         let mut colors = ColorGenerator::new();
         let int_literal = Color::Yellow;
-        let mut builder = self
-            .span
-            .clone()
-            .build_report(ariadne::ReportKind::Error)
-            .with_code(code)
+        let mut builder = Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message(message)
             .with_label(
-                Label::new(self.span.clone())
+                Label::new(self.span)
                     .with_color(colors.next())
                     .with_message(
                         "AvPony does not currently support integer literals of this value.",
@@ -72,7 +79,7 @@ impl ErrorI for InvalidInt {
             )
         }
 
-        builder.finish()
+        builder
     }
 }
 
@@ -89,20 +96,21 @@ impl MultipleNumericDividers {
 }
 
 impl ErrorI for MultipleNumericDividers {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S110".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
-        self.span
-            .clone()
-            .build_report(ariadne::ReportKind::Error)
-            .with_code("S110")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Multiple numeric separators in a row.")
             .with_label(
                 Label::new(self.span)
                     .with_color(colors.next())
                     .with_message("Excess underscores."),
             )
-            .with_help("Remove the excess underscores.")
-            .finish()
+            .with_fix(Fix::new(self.span, "", "Remove the excess underscores."))
     }
 }
 
@@ -119,19 +127,20 @@ impl DivdersBadlyPlaced {
 }
 
 impl ErrorI for DivdersBadlyPlaced {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S111".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
-        self.span
-            .clone()
-            .build_report(ariadne::ReportKind::Error)
-            .with_code("S111")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Improperly placed numeric seperators.")
             .with_label(
                 Label::new(self.span)
                     .with_color(colors.next())
                     .with_message("You can only place underscores between digits."),
             )
-            .with_help("Remove the excess underscores.")
-            .finish()
+            .with_fix(Fix::new(self.span, "", "Remove the underscore."))
     }
 }
@@ -0,0 +1,141 @@
+//!
+//! Errors for names, and their scopes.
+//!
+
+use ariadne::{ColorGenerator, Fmt};
+use avpony_macros::ErrorType;
+
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    suggest, Span,
+};
+
+use super::ErrorI;
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnresolvedIdentifier {
+    span: Span,
+    name: String,
+
+    ///
+    /// The closest name in scope, if any.
+    ///
+    suggestion: Option<String>,
+}
+
+impl UnresolvedIdentifier {
+    pub fn new(span: Span, name: String, suggestion: Option<String>) -> Self {
+        Self {
+            span,
+            name,
+            suggestion,
+        }
+    }
+}
+
+impl ErrorI for UnresolvedIdentifier {
+    fn code(&self) -> String {
+        "X200".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        let diagnostic = Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Unresolved identifier")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!("`{}` is not in scope.", self.name.fg(color))),
+            )
+            .with_help("Declare it in the module, or with `{@let ...}`, or `{@const ...}`.");
+
+        match self.suggestion {
+            Some(suggestion) => diagnostic.with_note(suggest::did_you_mean(suggestion)),
+            None => diagnostic,
+        }
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct ShadowedIdentifier {
+    span: Span,
+    name: String,
+    shadowed: Span,
+}
+
+impl ShadowedIdentifier {
+    pub fn new(span: Span, name: String, shadowed: Span) -> Self {
+        Self {
+            span,
+            name,
+            shadowed,
+        }
+    }
+}
+
+impl ErrorI for ShadowedIdentifier {
+    fn code(&self) -> String {
+        "X201".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
+            .with_message("Shadowed identifier")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`{}` shadows an outer binding.",
+                        self.name.fg(color)
+                    )),
+            )
+            .with_label(
+                Label::new(self.shadowed)
+                    .with_color(colors.next())
+                    .with_message("Which is declared here."),
+            )
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnusedBinding {
+    span: Span,
+    name: String,
+}
+
+impl UnusedBinding {
+    pub fn new(span: Span, name: String) -> Self {
+        Self { span, name }
+    }
+}
+
+impl ErrorI for UnusedBinding {
+    fn code(&self) -> String {
+        "X202".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Warning, self.span)
+            .with_code(self.code())
+            .with_message("Unused binding")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!("`{}` is never used.", self.name.fg(color))),
+            )
+            .with_help(format!(
+                "If this is intentional, prefix it with an underscore: `_{}`.",
+                self.name
+            ))
+    }
+}
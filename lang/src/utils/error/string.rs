@@ -2,10 +2,13 @@
 //! Errors whilst parsing a string.
 //!
 
-use ariadne::{ColorGenerator, Label};
+use ariadne::ColorGenerator;
 use avpony_macros::ErrorType;
 
-use crate::utils::Span;
+use crate::utils::{
+    diagnostic::{Diagnostic, Label, Severity},
+    Span,
+};
 
 use super::ErrorI;
 
@@ -22,13 +25,15 @@ impl InvalidUnicodeCodePoint {
 }
 
 impl ErrorI for InvalidUnicodeCodePoint {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S200".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
 
-        self.span
-            .clone()
-            .build_report(ariadne::ReportKind::Error)
-            .with_code("S200")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Invalid unicode character escape.")
             .with_label(
                 Label::new(self.span)
@@ -38,7 +43,6 @@ impl ErrorI for InvalidUnicodeCodePoint {
                         self.erroneous
                     )),
             )
-            .finish()
     }
 }
 
@@ -55,13 +59,15 @@ impl InvalidAsciiCode {
 }
 
 impl ErrorI for InvalidAsciiCode {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S201".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
 
-        self.span
-            .clone()
-            .build_report(ariadne::ReportKind::Error)
-            .with_code("S201")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Invalid ASCII character escape.")
             .with_label(
                 Label::new(self.span)
@@ -71,7 +77,6 @@ impl ErrorI for InvalidAsciiCode {
                         self.erroneous
                     )),
             )
-            .finish()
     }
 }
 
@@ -87,13 +92,15 @@ impl InvalidEscapeSequence {
 }
 
 impl ErrorI for InvalidEscapeSequence {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S102".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
 
-        self.span
-            .clone()
-            .build_report(ariadne::ReportKind::Error)
-            .with_code("S102")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Invalid escape sequence.")
             .with_label(
                 Label::new(self.span)
@@ -101,6 +108,5 @@ impl ErrorI for InvalidEscapeSequence {
                     .with_message("Starting here."),
             )
             .with_help("Try removing this `\\`.")
-            .finish()
     }
 }
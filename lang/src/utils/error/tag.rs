@@ -2,12 +2,16 @@
 //! Errors for PonyX tags.
 //!
 
-use ariadne::{ColorGenerator, Fmt, Label, ReportKind};
+use ariadne::{ColorGenerator, Fmt};
 use avpony_macros::ErrorType;
 
 use crate::{
     ponyx::tag::name::TagName,
-    utils::{Span, Spanned},
+    syntax::external::External,
+    utils::{
+        diagnostic::{Diagnostic, Fix, Label, Severity},
+        suggest, Span, Spanned,
+    },
 };
 
 #[ErrorType(crate::utils::Error)]
@@ -22,13 +26,15 @@ impl SoloExprOnly {
 }
 
 impl super::ErrorI for SoloExprOnly {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "S400".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
 
-        self.span
-            .clone()
-            .build_report(ReportKind::Error)
-            .with_code("X100")
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Unexpected expression")
             .with_label(
                 Label::new(self.span)
@@ -36,7 +42,6 @@ impl super::ErrorI for SoloExprOnly {
                     .with_message("Expected a solo expression here"),
             )
             .with_note("Solo expressions are literals, arrays, tuples, maps, external, or parenthesized expressions.")
-            .finish()
     }
 }
 
@@ -45,28 +50,43 @@ pub struct UnclosedTag {
     span: Span,
     opening: (Span, String),
     closing: (Span, String),
+
+    ///
+    /// The path of the opening tag's name, to close it with.
+    ///
+    path: String,
 }
 
 impl UnclosedTag {
-    pub fn new(span: Span, opening: TagName, closing: TagName) -> Self {
+    pub fn new<Ext: External>(span: Span, opening: TagName<Ext>, closing: TagName<Ext>) -> Self {
         Self {
             span,
             opening: (opening.span(), opening.to_string()),
             closing: (closing.span(), closing.to_string()),
+            path: opening.path_string(),
         }
     }
 }
 
 impl super::ErrorI for UnclosedTag {
-    fn to_report(self) -> ariadne::Report<'static, crate::utils::Span> {
+    fn code(&self) -> String {
+        "X101".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
         let mut colors = ColorGenerator::new();
         let opening = colors.next();
         let closing = colors.next();
 
-        self.span
-            .clone()
-            .build_report(ReportKind::Error)
-            .with_code("X101")
+        // A closing tag close to the opening one is likely a typo,
+        // otherwise, it probably closes an outer tag.
+        let note = match suggest::closest(&self.closing.1, [self.opening.1.as_str()]) {
+            Some(opening) => suggest::did_you_mean(format!("</{opening}>")),
+            None => format!("`<{}>` may be missing its closing tag.", self.opening.1),
+        };
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
             .with_message("Unclosed tag")
             .with_labels([
                 Label::new(self.span)
@@ -86,11 +106,71 @@ impl super::ErrorI for UnclosedTag {
                         (&self.closing.1).fg(closing)
                     )),
             ])
-            .with_help(format!(
-                "Rename `</{}>` to `</{}>`",
-                self.opening.1.fg(opening),
-                self.closing.1.fg(closing)
+            .with_note(note)
+            .with_fix(Fix::new(
+                self.closing.0,
+                self.path.clone(),
+                format!("Rename `</{}>` to `</{}>`.", self.closing.1, self.path),
+            ))
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct ImplicitlyClosedTag {
+    ///
+    /// Where the tag ended, without its closing tag.
+    ///
+    span: Span,
+    opening: (Span, String),
+
+    ///
+    /// The path of the opening tag's name, to close it with.
+    ///
+    path: String,
+}
+
+impl ImplicitlyClosedTag {
+    pub fn new<Ext: External>(span: Span, opening: &TagName<Ext>) -> Self {
+        Self {
+            span,
+            opening: (opening.span(), opening.to_string()),
+            path: opening.path_string(),
+        }
+    }
+}
+
+impl super::ErrorI for ImplicitlyClosedTag {
+    fn code(&self) -> String {
+        "X107".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let opening = colors.next();
+        let closing = format!("</{}>", self.path);
+
+        Diagnostic::new(Severity::Error, self.opening.0)
+            .with_code(self.code())
+            .with_message("Unclosed tag")
+            .with_labels([
+                Label::new(self.opening.0)
+                    .with_color(opening)
+                    .with_message(format!(
+                        "This `<{}>` tag is never closed.",
+                        (&self.opening.1).fg(opening)
+                    )),
+                Label::new(self.span)
+                    .with_color(colors.next())
+                    .with_message(format!("Expected `{closing}` here.")),
+            ])
+            .with_note(format!(
+                "`<{}>` ends here, where its parent (or the file) does.",
+                self.opening.1
+            ))
+            .with_fix(Fix::new(
+                self.span,
+                closing.clone(),
+                format!("Close it with `{closing}`."),
             ))
-            .finish()
     }
 }
@@ -0,0 +1,180 @@
+//!
+//! Errors for the types of Pony expressions
+//! (see [crate::semantic::types]).
+//!
+
+use ariadne::{ColorGenerator, Fmt};
+use avpony_macros::ErrorType;
+
+use crate::{
+    semantic::types::Type,
+    utils::{
+        diagnostic::{Diagnostic, Label, Severity},
+        Span,
+    },
+};
+
+use super::ErrorI;
+
+#[ErrorType(crate::utils::Error)]
+pub struct TypeMismatch {
+    span: Span,
+    expected: Type,
+    found: Type,
+}
+
+impl TypeMismatch {
+    pub fn new(span: Span, expected: Type, found: Type) -> Self {
+        Self {
+            span,
+            expected,
+            found,
+        }
+    }
+}
+
+impl ErrorI for TypeMismatch {
+    fn code(&self) -> String {
+        "X400".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        let report = Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Mismatched types")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "Expected `{}`, found `{}`.",
+                        self.expected.fg(color),
+                        self.found
+                    )),
+            );
+
+        match (&self.expected, &self.found) {
+            (Type::Length, Type::Number) => report.with_help("Add a unit, like `5px`."),
+            (Type::Color, _) => report.with_help(
+                "Colors are written `rgb(r, g, b)`, `hsl(h, s, l)`, or `hex\"#rrggbb\"`.",
+            ),
+            _ => report,
+        }
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnknownUnit {
+    span: Span,
+    unit: String,
+}
+
+impl UnknownUnit {
+    pub fn new(span: Span, unit: String) -> Self {
+        Self { span, unit }
+    }
+}
+
+impl ErrorI for UnknownUnit {
+    fn code(&self) -> String {
+        "X401".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Unknown unit")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!("`{}` isn't a unit of length.", self.unit.fg(color))),
+            )
+            .with_note(format!(
+                "Units of length are {}.",
+                crate::semantic::types::UNITS
+                    .iter()
+                    .map(|unit| format!("`{unit}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct UnexpectedField {
+    span: Span,
+    name: String,
+    expected: Type,
+}
+
+impl UnexpectedField {
+    pub fn new(span: Span, name: String, expected: Type) -> Self {
+        Self {
+            span,
+            name,
+            expected,
+        }
+    }
+}
+
+impl ErrorI for UnexpectedField {
+    fn code(&self) -> String {
+        "X402".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Unexpected field")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!(
+                        "`{}` isn't a field of `{}`.",
+                        self.name.fg(color),
+                        self.expected
+                    )),
+            )
+    }
+}
+
+#[ErrorType(crate::utils::Error)]
+pub struct MissingField {
+    span: Span,
+    name: String,
+    ty: Type,
+}
+
+impl MissingField {
+    pub fn new(span: Span, name: String, ty: Type) -> Self {
+        Self { span, name, ty }
+    }
+}
+
+impl ErrorI for MissingField {
+    fn code(&self) -> String {
+        "X403".to_string()
+    }
+
+    fn to_diagnostic(self) -> Diagnostic {
+        let mut colors = ColorGenerator::new();
+        let color = colors.next();
+
+        Diagnostic::new(Severity::Error, self.span)
+            .with_code(self.code())
+            .with_message("Missing field")
+            .with_label(
+                Label::new(self.span)
+                    .with_color(color)
+                    .with_message(format!("Missing `.{}: {}`.", self.name.fg(color), self.ty)),
+            )
+    }
+}
@@ -1,12 +1,13 @@
 use std::{
+    fmt::{Debug, Display},
     fs::{self},
     path::Path,
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
 use chumsky::input::{Input, WithContext};
 
-use super::span::Span;
+use super::{line_index::LineIndex, source_map::FileId, span::Span};
 
 ///
 /// Input for any Pony parser.
@@ -17,8 +18,14 @@ pub type PonyInput<'src> = WithContext<Span, &'src str>;
 /// The raw source code of a file.
 ///
 pub struct SourceFile {
-    path: Arc<str>,
+    id: FileId,
     contents: String,
+
+    ///
+    /// Built the first time they're needed.
+    ///
+    lines: OnceLock<LineIndex>,
+    report_source: OnceLock<ariadne::Source>,
 }
 
 impl SourceFile {
@@ -29,10 +36,56 @@ impl SourceFile {
         let path = path.as_ref();
         let contents = fs::read_to_string(path)?;
 
-        Ok(Self {
-            path: path.to_string_lossy().into(),
-            contents,
-        })
+        Ok(Self::new(path.to_string_lossy(), contents))
+    }
+
+    ///
+    /// A virtual file, not backed by anything on disk.
+    ///
+    pub fn new(path: impl AsRef<str>, contents: impl Into<String>) -> Self {
+        Self {
+            id: FileId::intern(path.as_ref()),
+            contents: contents.into(),
+            lines: OnceLock::new(),
+            report_source: OnceLock::new(),
+        }
+    }
+
+    ///
+    /// The id of this file's path.
+    ///
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    ///
+    /// The path of this file.
+    ///
+    pub fn path(&self) -> Arc<str> {
+        self.id.path()
+    }
+
+    ///
+    /// The contents of this file.
+    ///
+    pub fn contents(&self) -> &str {
+        &self.contents
+    }
+
+    ///
+    /// Where each line of this file is, to find
+    /// the line, and column of a [Span].
+    ///
+    pub fn lines(&self) -> &LineIndex {
+        self.lines.get_or_init(|| LineIndex::new(&self.contents))
+    }
+
+    ///
+    /// This file, for rendering [ariadne] reports.
+    ///
+    pub(super) fn report_source(&self) -> &ariadne::Source {
+        self.report_source
+            .get_or_init(|| ariadne::Source::from(self.contents.clone()))
     }
 
     ///
@@ -40,21 +93,37 @@ impl SourceFile {
     /// to be used by a [chumsky::Parser]
     ///
     pub fn stream(&self) -> WithContext<Span, &str> {
-        (&self.contents).with_context(self.path.clone())
+        (&self.contents).with_context(self.id)
     }
 
     ///
     /// Make a virtual file, for unit testing code.
     ///
     #[cfg(test)]
-    pub fn test_file(contents: impl ToString) -> (Self, (Arc<str>, ariadne::Source)) {
+    pub fn test_file(contents: impl ToString) -> (Self, (FileId, ariadne::Source)) {
         let contents = contents.to_string();
-        (
-            Self {
-                path: "TEST".into(),
-                contents: contents.clone(),
-            },
-            ("TEST".into(), ariadne::Source::from(contents)),
-        )
+        let file = Self::new("TEST", contents.clone());
+        let id = file.id;
+
+        (file, (id, ariadne::Source::from(contents)))
+    }
+}
+
+///
+/// Render [ariadne] reports against a single file.
+///
+impl ariadne::Cache<FileId> for &SourceFile {
+    type Storage = String;
+
+    fn fetch(&mut self, id: &FileId) -> Result<&ariadne::Source, Box<dyn Debug + '_>> {
+        if *id != self.id {
+            return Err(Box::new(format!("Unknown file `{id}`.")));
+        }
+
+        Ok(self.report_source())
+    }
+
+    fn display<'a>(&self, id: &'a FileId) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(id))
     }
 }
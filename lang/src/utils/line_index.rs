@@ -0,0 +1,272 @@
+//!
+//! Conversion between byte offsets (as in a [Span](super::Span)),
+//! and lines, and columns.
+//!
+
+use std::ops::Range;
+
+///
+/// The unit columns are counted in.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    ///
+    /// Bytes, as in a Rust string.
+    ///
+    Utf8,
+
+    ///
+    /// UTF-16 code units, as in the Language Server Protocol,
+    /// and JavaScript strings.
+    ///
+    Utf16,
+
+    ///
+    /// Unicode scalar values, i.e. [char]s,
+    /// usually what a person would count.
+    ///
+    Char,
+}
+
+///
+/// A line, and column, both zero-based.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+///
+/// A character taking more than one byte.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WideChar {
+    ///
+    /// Byte offset from the start of its line.
+    ///
+    start: usize,
+    len_utf8: usize,
+    len_utf16: usize,
+}
+
+impl WideChar {
+    fn len(&self, encoding: Encoding) -> usize {
+        match encoding {
+            Encoding::Utf8 => self.len_utf8,
+            Encoding::Utf16 => self.len_utf16,
+            Encoding::Char => 1,
+        }
+    }
+}
+
+///
+/// Where every line of a file starts, and ends.
+///
+/// Lines are broken by `\n`, or `\r\n`; the line break
+/// itself isn't part of a line, so offsets within one
+/// are at the end of the line they break.
+///
+/// ### Example
+///
+/// ```
+/// use avpony_lang::utils::line_index::{Encoding, LineCol, LineIndex};
+///
+/// let index = LineIndex::new("a\r\né𝄞b");
+///
+/// let b = LineCol { line: 1, col: 2 };
+/// assert_eq!(index.line_col(9, Encoding::Char), b);
+/// assert_eq!(index.line_col(9, Encoding::Utf16), LineCol { col: 3, ..b });
+/// assert_eq!(index.offset(b, Encoding::Char), Some(9));
+/// ```
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    ///
+    /// The byte range of each line, without its line break.
+    ///
+    lines: Vec<Range<usize>>,
+
+    ///
+    /// Multi-byte characters of each line, in order.
+    ///
+    wide: Vec<Vec<WideChar>>,
+}
+
+impl LineIndex {
+    pub fn new(src: &str) -> Self {
+        let mut lines = Vec::new();
+        let mut wide = Vec::new();
+
+        let mut start = 0;
+        let mut line_wide = Vec::new();
+        for (i, ch) in src.char_indices() {
+            if ch == '\n' {
+                let end = if src[..i].ends_with('\r') { i - 1 } else { i };
+                lines.push(start..end);
+                wide.push(std::mem::take(&mut line_wide));
+                start = i + 1;
+            } else if ch.len_utf8() > 1 {
+                line_wide.push(WideChar {
+                    start: i - start,
+                    len_utf8: ch.len_utf8(),
+                    len_utf16: ch.len_utf16(),
+                });
+            }
+        }
+        lines.push(start..src.len());
+        wide.push(line_wide);
+
+        Self { lines, wide }
+    }
+
+    ///
+    /// The number of lines (a file always has at least one).
+    ///
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    ///
+    /// The byte range of `line`, without its line break.
+    ///
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        self.lines.get(line).cloned()
+    }
+
+    ///
+    /// The line of byte `offset`.
+    ///
+    pub fn line(&self, offset: usize) -> usize {
+        self.lines
+            .partition_point(|line| line.start <= offset)
+            .saturating_sub(1)
+    }
+
+    ///
+    /// The line, and column of byte `offset`.
+    ///
+    /// Offsets within a line break are at the end of the line,
+    /// and offsets within a character at its start.
+    ///
+    pub fn line_col(&self, offset: usize, encoding: Encoding) -> LineCol {
+        let line = self.line(offset);
+        let range = &self.lines[line];
+        let byte_col = offset.clamp(range.start, range.end) - range.start;
+
+        let mut col = byte_col;
+        for ch in &self.wide[line] {
+            if ch.start >= byte_col {
+                break;
+            }
+
+            if byte_col < ch.start + ch.len_utf8 {
+                // Within the character.
+                col -= byte_col - ch.start;
+                break;
+            }
+
+            col = col - ch.len_utf8 + ch.len(encoding);
+        }
+
+        LineCol { line, col }
+    }
+
+    ///
+    /// The line, and column of both ends of byte `range`.
+    ///
+    pub fn range(&self, range: Range<usize>, encoding: Encoding) -> Range<LineCol> {
+        self.line_col(range.start, encoding)..self.line_col(range.end, encoding)
+    }
+
+    ///
+    /// The byte offset of `position`, or [None] if its line doesn't exist.
+    ///
+    /// Columns past the end of a line are at its end,
+    /// and columns within a character (like half a surrogate pair) at its start.
+    ///
+    pub fn offset(&self, position: LineCol, encoding: Encoding) -> Option<usize> {
+        let range = self.lines.get(position.line)?;
+
+        // How many fewer columns than bytes there are before the current character.
+        let mut shrink = 0;
+        for ch in &self.wide[position.line] {
+            let col = ch.start - shrink;
+            if position.col < col {
+                break;
+            }
+
+            if position.col < col + ch.len(encoding) {
+                return Some(range.start + ch.start);
+            }
+
+            shrink += ch.len_utf8 - ch.len(encoding);
+        }
+
+        let byte_col = position.col + shrink;
+        Some(range.start + byte_col.min(range.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Encoding, LineCol, LineIndex};
+
+    fn at(line: usize, col: usize) -> LineCol {
+        LineCol { line, col }
+    }
+
+    #[test]
+    fn lines() {
+        let index = LineIndex::new("ab\r\n\ncd\n");
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_range(0), Some(0..2));
+        assert_eq!(index.line_range(1), Some(4..4));
+        assert_eq!(index.line_range(2), Some(5..7));
+        assert_eq!(index.line_range(3), Some(8..8));
+        assert_eq!(index.line_range(4), None);
+
+        // Within `\r\n`, at the end of the line.
+        assert_eq!(index.line_col(2, Encoding::Utf8), at(0, 2));
+        assert_eq!(index.line_col(3, Encoding::Utf8), at(0, 2));
+        assert_eq!(index.line_col(6, Encoding::Utf8), at(2, 1));
+        assert_eq!(index.line_col(100, Encoding::Utf8), at(3, 0));
+
+        assert_eq!(index.offset(at(2, 1), Encoding::Utf8), Some(6));
+        assert_eq!(index.offset(at(0, 10), Encoding::Utf8), Some(2));
+        assert_eq!(index.offset(at(4, 0), Encoding::Utf8), None);
+    }
+
+    #[test]
+    fn multi_byte() {
+        // `é` is 2 bytes, and 1 UTF-16 unit; `𝄞` 4 bytes, and 2 units.
+        let src = "x\r\né𝄞b𝄞";
+        let index = LineIndex::new(src);
+        let b = src.find('b').unwrap();
+
+        assert_eq!(index.line_col(b, Encoding::Utf8), at(1, 6));
+        assert_eq!(index.line_col(b, Encoding::Utf16), at(1, 3));
+        assert_eq!(index.line_col(b, Encoding::Char), at(1, 2));
+        assert_eq!(index.line_col(src.len(), Encoding::Utf16), at(1, 6));
+
+        // Within `𝄞`, at its start.
+        assert_eq!(index.line_col(b - 1, Encoding::Utf16), at(1, 1));
+
+        for encoding in [Encoding::Utf8, Encoding::Utf16, Encoding::Char] {
+            for (offset, _) in src.char_indices().chain([(src.len(), ' ')]) {
+                if offset == 2 {
+                    // Within `\r\n`.
+                    continue;
+                }
+
+                let position = index.line_col(offset, encoding);
+                assert_eq!(index.offset(position, encoding), Some(offset));
+            }
+        }
+
+        // Half a surrogate pair.
+        assert_eq!(index.offset(at(1, 2), Encoding::Utf16), Some(b - 4));
+        assert_eq!(index.offset(at(1, 100), Encoding::Char), Some(src.len()));
+    }
+}
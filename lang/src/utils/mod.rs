@@ -2,10 +2,14 @@
 //! Common utilities used throughout parsing.
 //!
 
+pub mod diagnostic;
 pub mod error;
 pub mod input;
+pub mod line_index;
 pub mod placeholder;
+pub mod source_map;
 pub mod span;
+pub mod suggest;
 
 use std::fmt::Debug;
 
@@ -13,12 +17,44 @@ use chumsky::extra::Full;
 use chumsky::Parser;
 pub use error::{Error, ErrorI};
 pub use input::{PonyInput, SourceFile};
+pub use line_index::{Encoding, LineCol, LineIndex};
 use placeholder::{HasPlaceholder, Marker, Maybe, Placeholder};
+pub use source_map::{FileId, SourceMap};
 pub use span::{Span, Spanned};
 
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct PonyContext {
     pub is_in_member: bool,
+
+    ///
+    /// Keywords of the logic blocks this is in, outermost first.
+    ///
+    pub blocks: Vec<&'static str>,
+
+    ///
+    /// Names of the tags this is in, outermost first.
+    ///
+    pub tags: Vec<String>,
+}
+
+impl PonyContext {
+    ///
+    /// This context, inside a logic block of `keyword`.
+    ///
+    pub fn in_block(&self, keyword: &'static str) -> Self {
+        let mut ctx = self.clone();
+        ctx.blocks.push(keyword);
+        ctx
+    }
+
+    ///
+    /// This context, inside a tag called `name`.
+    ///
+    pub fn in_tag(&self, name: String) -> Self {
+        let mut ctx = self.clone();
+        ctx.tags.push(name);
+        ctx
+    }
 }
 
 pub type Extra<'a> = Full<Error, (), PonyContext>;
@@ -8,7 +8,11 @@ use std::fmt::Debug;
 use avpony_macros::Spanned;
 use chumsky::Parser;
 
-use crate::utils::Span;
+use crate::{
+    syntax::external::External,
+    utils::Span,
+    visit::{Fold, Visit, VisitMut, Visitable},
+};
 
 use super::{error::expected::Expected, PonyParser};
 
@@ -24,9 +28,12 @@ pub trait HasPlaceholder {
 }
 
 #[derive(Clone, PartialEq, Spanned)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(tag = "kind"))]
 pub struct Placeholder {
     span: Span,
+    #[cfg_attr(feature = "serde", serde(skip))]
     marker: u8,
+    #[cfg_attr(feature = "serde", serde(rename = "expected"))]
     display: &'static str,
 }
 
@@ -47,6 +54,13 @@ impl Placeholder {
         self.display
     }
 
+    ///
+    /// See [Span::shift].
+    ///
+    pub fn shift(&mut self, from: usize, delta: isize) {
+        self.span.shift(from, delta);
+    }
+
     pub fn at<M: Marker>(span: Span) -> Self {
         Self {
             span,
@@ -56,6 +70,23 @@ impl Placeholder {
     }
 }
 
+impl<Ext: External> Visitable<Ext> for Placeholder {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        Visitable::<Ext>::visit(&self.span, visitor);
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        Visitable::<Ext>::visit_mut(&mut self.span, visitor);
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        Self {
+            span: Visitable::<Ext>::fold(self.span, folder),
+            ..self
+        }
+    }
+}
+
 impl Debug for Placeholder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple(self.expected()).field(&self.span).finish()
@@ -64,6 +95,7 @@ impl Debug for Placeholder {
 
 //
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
 pub enum Maybe<P: HasPlaceholder> {
     Present(P),
     Placeholder(Placeholder),
@@ -76,6 +108,16 @@ impl<P: HasPlaceholder> Maybe<P> {
             Maybe::Placeholder(ph) => unwrapper(ph),
         }
     }
+
+    ///
+    /// The node, unless it's a placeholder.
+    ///
+    pub fn present(&self) -> Option<&P> {
+        match self {
+            Maybe::Present(p) => Some(p),
+            Maybe::Placeholder(_) => None,
+        }
+    }
 }
 
 pub trait MaybeParser<'src, P: HasPlaceholder>: PonyParser<'src, P> + Sized + Clone {
@@ -0,0 +1,159 @@
+//!
+//! Interned file ids, and a map of them to [SourceFile]s.
+//!
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Display},
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use super::SourceFile;
+
+///
+/// A small id for the path of a file,
+/// so [Span](super::Span)s don't have to hold the path itself.
+///
+/// Ids are interned: the same path always has the same id,
+/// for as long as the program runs.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(u32);
+
+///
+/// Every path interned so far.
+///
+#[derive(Default)]
+struct Paths {
+    paths: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, FileId>,
+}
+
+fn paths() -> &'static RwLock<Paths> {
+    static PATHS: OnceLock<RwLock<Paths>> = OnceLock::new();
+    PATHS.get_or_init(Default::default)
+}
+
+impl FileId {
+    ///
+    /// The id of `path`.
+    ///
+    pub fn intern(path: &str) -> Self {
+        if let Some(id) = paths().read().unwrap().ids.get(path) {
+            return *id;
+        }
+
+        let mut paths = paths().write().unwrap();
+        if let Some(id) = paths.ids.get(path) {
+            return *id;
+        }
+
+        let id = Self(paths.paths.len() as u32);
+        let path: Arc<str> = path.into();
+        paths.paths.push(path.clone());
+        paths.ids.insert(path, id);
+
+        id
+    }
+
+    ///
+    /// The path this id was interned from.
+    ///
+    pub fn path(self) -> Arc<str> {
+        paths().read().unwrap().paths[self.0 as usize].clone()
+    }
+}
+
+impl Debug for FileId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FileId({}, {:?})", self.0, self.path())
+    }
+}
+
+impl Display for FileId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path())
+    }
+}
+
+///
+/// Every file of a project, by id.
+///
+/// This is an [ariadne::Cache], so reports
+/// can be rendered against any of its files:
+/// ```ignore
+/// let mut files = SourceMap::default();
+/// let id = files.add(SourceFile::read("Button.pony")?);
+/// error.to_report().eprint(&files)?;
+/// ```
+///
+#[derive(Default)]
+pub struct SourceMap {
+    files: HashMap<FileId, SourceFile>,
+}
+
+impl SourceMap {
+    ///
+    /// Add `file`, replacing any file with the same path.
+    ///
+    pub fn add(&mut self, file: SourceFile) -> FileId {
+        let id = file.id();
+        self.files.insert(id, file);
+        id
+    }
+
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(&id)
+    }
+
+    pub fn remove(&mut self, id: FileId) -> Option<SourceFile> {
+        self.files.remove(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SourceFile> {
+        self.files.values()
+    }
+}
+
+impl ariadne::Cache<FileId> for &SourceMap {
+    type Storage = String;
+
+    fn fetch(&mut self, id: &FileId) -> Result<&ariadne::Source, Box<dyn Debug + '_>> {
+        match self.files.get(id) {
+            Some(file) => Ok(file.report_source()),
+            None => Err(Box::new(format!("Unknown file `{id}`."))),
+        }
+    }
+
+    fn display<'a>(&self, id: &'a FileId) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::SourceFile;
+
+    use super::{FileId, SourceMap};
+
+    #[test]
+    fn interned() {
+        let a = FileId::intern("src/A.pony");
+        let b = FileId::intern("src/B.pony");
+
+        assert_eq!(a, FileId::intern("src/A.pony"));
+        assert_ne!(a, b);
+        assert_eq!(b.path().as_ref(), "src/B.pony");
+        assert_eq!(a.to_string(), "src/A.pony");
+    }
+
+    #[test]
+    fn source_map() {
+        let mut files = SourceMap::default();
+        let id = files.add(SourceFile::new("src/C.pony", "<Text />"));
+
+        assert_eq!(files.get(id).map(SourceFile::contents), Some("<Text />"));
+        assert!(files.get(FileId::intern("src/D.pony")).is_none());
+        assert!(files.remove(id).is_some());
+    }
+}
@@ -1,4 +1,6 @@
-use std::{ops::Range, sync::Arc};
+use std::ops::Range;
+
+use super::source_map::FileId;
 
 ///
 /// Where something is in source code.
@@ -10,78 +12,83 @@ use std::{ops::Range, sync::Arc};
 /// arr.length
 /// ```
 ///
-/// Here, the token `arr` has span (`/some/file,avpony`, 0).
+/// Here, the token `arr` has span (the [FileId] of `/some/file.avpony`, `0..3`).
 ///
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Span {
-    path: Arc<str>,
+    file: FileId,
 
     ///
-    /// In this case, [Range<usize>] refers to the byte
-    /// offset in a source code file.
+    /// In this case, these are the byte
+    /// offsets in a source code file.
     ///
-    range: Range<usize>,
+    start: u32,
+    end: u32,
 }
 
 impl Span {
     pub fn combine(self, other: Self) -> Option<Self> {
-        match (self, other) {
-            (
-                Span {
-                    path: p1,
-                    range: left,
-                },
-                Span {
-                    path: p2,
-                    range: right,
-                },
-            ) if p1 == p2 => Some(Self {
-                path: p1,
-                range: left.start.min(right.start)..right.end.max(left.end),
-            }),
-            _ => None,
-        }
+        (self.file == other.file).then(|| Self {
+            file: self.file,
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        })
+    }
+
+    ///
+    /// The file this span is in.
+    ///
+    pub fn file(&self) -> FileId {
+        self.file
+    }
+
+    ///
+    /// The byte range of this span.
+    ///
+    pub fn range(&self) -> Range<usize> {
+        self.start as usize..self.end as usize
     }
 }
 
 impl chumsky::span::Span for Span {
-    type Context = Arc<str>;
+    type Context = FileId;
 
     type Offset = usize;
 
     fn new(context: Self::Context, range: Range<Self::Offset>) -> Self {
         Self {
-            path: context,
-            range,
+            file: context,
+            start: range.start as u32,
+            end: range.end as u32,
         }
     }
 
     fn context(&self) -> Self::Context {
-        self.path.clone()
+        self.file
     }
 
     fn start(&self) -> Self::Offset {
-        self.range.start
+        self.start as usize
     }
 
     fn end(&self) -> Self::Offset {
-        self.range.end
+        self.end as usize
     }
 }
 
 impl ariadne::Span for Span {
-    type SourceId = Arc<str>;
+    type SourceId = FileId;
 
     fn source(&self) -> &Self::SourceId {
-        &self.path
+        &self.file
     }
 
     fn start(&self) -> usize {
-        self.range.start
+        self.start as usize
     }
 
     fn end(&self) -> usize {
-        self.range.end
+        self.end as usize
     }
 }
 
@@ -93,14 +100,30 @@ impl Span {
     pub(super) fn build_report(
         self,
         kind: ariadne::ReportKind,
-    ) -> ariadne::ReportBuilder<'_, Self> {
-        ariadne::Report::build(kind, self.path.as_ref(), self.range.start)
+    ) -> ariadne::ReportBuilder<'static, Self> {
+        ariadne::Report::build(kind, self.file, self.start as usize)
+    }
+
+    ///
+    /// Move the ends of this span at, or after byte `from` by `delta` bytes,
+    /// after an edit of the source before them.
+    ///
+    pub fn shift(&mut self, from: usize, delta: isize) {
+        let shift = |offset: &mut u32| {
+            if *offset as usize >= from {
+                *offset = (*offset as isize + delta) as u32;
+            }
+        };
+
+        shift(&mut self.start);
+        shift(&mut self.end);
     }
 
     pub fn relative_range(&self, range: Range<usize>) -> Self {
         Self {
-            path: self.path.clone(),
-            range: (self.range.start + range.start)..(self.range.start + range.end),
+            file: self.file,
+            start: self.start + range.start as u32,
+            end: self.start + range.end as u32,
         }
     }
 }
@@ -115,6 +138,6 @@ pub trait Spanned {
 
 impl Spanned for Span {
     fn span(&self) -> self::Span {
-        self.clone()
+        *self
     }
 }
@@ -0,0 +1,94 @@
+//!
+//! Suggestions for misspelled names, by their edit distance
+//! to names which are valid, shown as a note of the error
+//! (see [did_you_mean]).
+//!
+
+///
+/// How many single character insertions, deletions, substitutions,
+/// or swaps of neighbouring characters turn `a` into `b`.
+///
+pub fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    // Distances between prefixes of `a`, and `b`,
+    // keeping the last two rows for swaps.
+    let mut before = Vec::new();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+
+        for j in 1..=b.len() {
+            let substitution = previous[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            current[j] = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+
+        before = std::mem::replace(&mut previous, current);
+    }
+
+    previous[b.len()]
+}
+
+///
+/// The candidate closest to `name`, if any are close enough
+/// to be a likely misspelling (and not `name` itself).
+///
+/// Ties go to the first candidate in alphabetical order,
+/// so candidates can come in any order.
+///
+pub fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max = (name.chars().count() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, candidate)| (*distance, *candidate))
+        .map(|(_, candidate)| candidate)
+}
+
+///
+/// A note suggesting `suggestion` instead.
+///
+pub fn did_you_mean(suggestion: impl std::fmt::Display) -> String {
+    format!("Did you mean `{suggestion}`?")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest, distance};
+
+    #[test]
+    fn distances() {
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("for", ""), 3);
+        assert_eq!(distance("for", "for"), 0);
+        assert_eq!(distance("for", "fro"), 1);
+        assert_eq!(distance("nbsp", "nbps"), 1);
+        assert_eq!(distance("Button", "Buton"), 1);
+        assert_eq!(distance("await", "wait"), 1);
+        assert_eq!(distance("ä", "a"), 1);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggestions() {
+        let keywords = ["if", "for", "await", "key", "match"];
+        let names = ["Rows", "Row", "Columns", "Column"];
+
+        assert_eq!(closest("fro", keywords), Some("for"));
+        assert_eq!(closest("mathc", keywords), Some("match"));
+        assert_eq!(closest("awiat", keywords), Some("await"));
+        assert_eq!(closest("for", keywords), None);
+        assert_eq!(closest("while", keywords), None);
+        assert_eq!(closest("Rowz", names), Some("Row"));
+        assert_eq!(closest("Colunm", names), Some("Column"));
+    }
+}
@@ -0,0 +1,455 @@
+//!
+//! ## Visitors
+//!
+//! Traversals over the syntax tree, for analyses, and rewrites:
+//! * [Visit] walks a tree by reference;
+//! * [VisitMut] walks a tree by mutable reference;
+//! * [Fold] rebuilds a tree by value.
+//!
+//! Each has a method per node type (`visit_if_block`, `visit_if_block_mut`,
+//! `fold_if_block`, ...), which by default walks into the node's children,
+//! so implementors only override the nodes they care about.
+//! To keep walking from an overridden method, call
+//! [Visitable::visit_children] (or its `_mut`, and `fold_` counterparts).
+//!
+//! Nodes implement [Visitable] with `#[derive(Visit)]`;
+//! external language nodes (modules, expressions, ...) are leaves,
+//! passed to `visit_external` as an [ExternalNode].
+//! The [Span]s of nodes are passed to `visit_span`, like any other field.
+//!
+
+use std::marker::PhantomData;
+
+use crate::{
+    lexical::{
+        boolean::BooleanLit,
+        number::{FloatLit, IntegerLit, NumberLit},
+        string::StringLit,
+        Identifier, Literal,
+    },
+    ponyx::{
+        blocks::{
+            await_block::{self, AwaitBlock},
+            for_block::ForBlock,
+            if_block::{self, ElseBranch, ElseIfBranch, IfBlock, IfBranch},
+            key_block::KeyBlock,
+            match_block::{Arm, DefaultArm, MatchBlock, WhenArm},
+            LogicBlock,
+        },
+        comment::Comment,
+        entity::Entity,
+        statement::{debug_statement::DebugStatement, AtStatement},
+        tag::{
+            attribute::{Attribute, AttributeAssignment, AttributeKey, Directive},
+            name::TagName,
+            EnclosingTag, SelfClosingTag, Tag,
+        },
+        text::Text,
+        Node,
+    },
+    syntax::{
+        application::Application,
+        array::Array,
+        external::{External, ExternalExpr, ExternalNode},
+        file::File,
+        index::Indexing,
+        map::{Field, FieldKey, FieldKeyValue, Map},
+        member::MemberAccess,
+        operation::{BinaryOperation, UnarayOperation},
+        operator::{BinaryOperator, NamedBinary, Symbolic, UnaryOperator},
+        parenthesized::Parenthesized,
+        tuple::Tuple,
+        Expr, SoloExpr,
+    },
+    utils::{
+        placeholder::{HasPlaceholder, Maybe, Placeholder},
+        Empty, Span,
+    },
+};
+
+///
+/// A node of the syntax tree, that can be walked by visitors.
+///
+/// `visit`, `visit_mut`, and `fold` dispatch to the visitor's method for
+/// this node; the `_children` methods walk into the node's children.
+///
+pub trait Visitable<Ext: External>: Sized {
+    fn visit<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        self.visit_children(visitor)
+    }
+
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V);
+
+    fn visit_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        self.visit_children_mut(visitor)
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V);
+
+    fn fold<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        self.fold_children(folder)
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self;
+}
+
+macro_rules! visitors {
+    ($($ty:ty => $visit:ident, $visit_mut:ident, $fold:ident;)*) => {
+        ///
+        /// Walks a syntax tree by reference.
+        ///
+        pub trait Visit<Ext: External> {
+            fn visit_placeholder(&mut self, placeholder: &Placeholder) {
+                Visitable::<Ext>::visit_children(placeholder, self)
+            }
+
+            fn visit_span(&mut self, _span: &Span) {}
+
+            fn visit_external(&mut self, _node: &dyn ExternalNode) {}
+
+            $(
+                fn $visit(&mut self, node: &$ty) {
+                    Visitable::<Ext>::visit_children(node, self)
+                }
+            )*
+        }
+
+        ///
+        /// Walks a syntax tree by mutable reference.
+        ///
+        pub trait VisitMut<Ext: External> {
+            fn visit_placeholder_mut(&mut self, placeholder: &mut Placeholder) {
+                Visitable::<Ext>::visit_children_mut(placeholder, self)
+            }
+
+            fn visit_span_mut(&mut self, _span: &mut Span) {}
+
+            fn visit_external_mut(&mut self, _node: &mut dyn ExternalNode) {}
+
+            $(
+                fn $visit_mut(&mut self, node: &mut $ty) {
+                    Visitable::<Ext>::visit_children_mut(node, self)
+                }
+            )*
+        }
+
+        ///
+        /// Rebuilds a syntax tree by value.
+        ///
+        pub trait Fold<Ext: External> {
+            fn fold_placeholder(&mut self, placeholder: Placeholder) -> Placeholder {
+                Visitable::<Ext>::fold_children(placeholder, self)
+            }
+
+            fn fold_span(&mut self, span: Span) -> Span {
+                span
+            }
+
+            $(
+                fn $fold(&mut self, node: $ty) -> $ty {
+                    Visitable::<Ext>::fold_children(node, self)
+                }
+            )*
+        }
+    };
+}
+
+visitors! {
+    File<Ext> => visit_file, visit_file_mut, fold_file;
+    Node<Ext> => visit_node, visit_node_mut, fold_node;
+    Comment => visit_comment, visit_comment_mut, fold_comment;
+    Text => visit_text, visit_text_mut, fold_text;
+    Entity => visit_entity, visit_entity_mut, fold_entity;
+    AtStatement<Ext> => visit_at_statement, visit_at_statement_mut, fold_at_statement;
+    DebugStatement<Ext> => visit_debug_statement, visit_debug_statement_mut, fold_debug_statement;
+    LogicBlock<Ext> => visit_logic_block, visit_logic_block_mut, fold_logic_block;
+    IfBlock<Ext> => visit_if_block, visit_if_block_mut, fold_if_block;
+    if_block::Branch<Ext> => visit_if_block_branch, visit_if_block_branch_mut, fold_if_block_branch;
+    IfBranch<Ext> => visit_if_branch, visit_if_branch_mut, fold_if_branch;
+    ElseIfBranch<Ext> => visit_else_if_branch, visit_else_if_branch_mut, fold_else_if_branch;
+    ElseBranch<Ext> => visit_else_branch, visit_else_branch_mut, fold_else_branch;
+    ForBlock<Ext> => visit_for_block, visit_for_block_mut, fold_for_block;
+    AwaitBlock<Ext> => visit_await_block, visit_await_block_mut, fold_await_block;
+    await_block::Branch => visit_await_branch, visit_await_branch_mut, fold_await_branch;
+    KeyBlock<Ext> => visit_key_block, visit_key_block_mut, fold_key_block;
+    MatchBlock<Ext> => visit_match_block, visit_match_block_mut, fold_match_block;
+    Arm<Ext> => visit_arm, visit_arm_mut, fold_arm;
+    WhenArm<Ext> => visit_when_arm, visit_when_arm_mut, fold_when_arm;
+    DefaultArm<Ext> => visit_default_arm, visit_default_arm_mut, fold_default_arm;
+    Tag<Ext> => visit_tag, visit_tag_mut, fold_tag;
+    SelfClosingTag<Ext> => visit_self_closing_tag, visit_self_closing_tag_mut, fold_self_closing_tag;
+    EnclosingTag<Ext> => visit_enclosing_tag, visit_enclosing_tag_mut, fold_enclosing_tag;
+    TagName<Ext> => visit_tag_name, visit_tag_name_mut, fold_tag_name;
+    Attribute<Ext> => visit_attribute, visit_attribute_mut, fold_attribute;
+    AttributeKey => visit_attribute_key, visit_attribute_key_mut, fold_attribute_key;
+    Directive => visit_directive, visit_directive_mut, fold_directive;
+    AttributeAssignment<Ext> => visit_attribute_assignment, visit_attribute_assignment_mut, fold_attribute_assignment;
+    Expr<Ext> => visit_expr, visit_expr_mut, fold_expr;
+    SoloExpr<Ext> => visit_solo_expr, visit_solo_expr_mut, fold_solo_expr;
+    ExternalExpr<Ext> => visit_external_expr, visit_external_expr_mut, fold_external_expr;
+    Array<Ext> => visit_array, visit_array_mut, fold_array;
+    Map<Ext> => visit_map, visit_map_mut, fold_map;
+    Field<Ext> => visit_field, visit_field_mut, fold_field;
+    FieldKey => visit_field_key, visit_field_key_mut, fold_field_key;
+    FieldKeyValue<Ext> => visit_field_key_value, visit_field_key_value_mut, fold_field_key_value;
+    Tuple<Ext> => visit_tuple, visit_tuple_mut, fold_tuple;
+    Parenthesized<Ext> => visit_parenthesized, visit_parenthesized_mut, fold_parenthesized;
+    MemberAccess<Ext> => visit_member_access, visit_member_access_mut, fold_member_access;
+    Indexing<Ext> => visit_indexing, visit_indexing_mut, fold_indexing;
+    UnarayOperation<Ext> => visit_unary_operation, visit_unary_operation_mut, fold_unary_operation;
+    BinaryOperation<Ext> => visit_binary_operation, visit_binary_operation_mut, fold_binary_operation;
+    UnaryOperator => visit_unary_operator, visit_unary_operator_mut, fold_unary_operator;
+    BinaryOperator => visit_binary_operator, visit_binary_operator_mut, fold_binary_operator;
+    Symbolic => visit_symbolic, visit_symbolic_mut, fold_symbolic;
+    NamedBinary => visit_named_binary, visit_named_binary_mut, fold_named_binary;
+    Application<Ext> => visit_application, visit_application_mut, fold_application;
+    Identifier => visit_identifier, visit_identifier_mut, fold_identifier;
+    Literal => visit_literal, visit_literal_mut, fold_literal;
+    NumberLit => visit_number_lit, visit_number_lit_mut, fold_number_lit;
+    IntegerLit => visit_integer_lit, visit_integer_lit_mut, fold_integer_lit;
+    FloatLit => visit_float_lit, visit_float_lit_mut, fold_float_lit;
+    StringLit => visit_string_lit, visit_string_lit_mut, fold_string_lit;
+    BooleanLit => visit_boolean_lit, visit_boolean_lit_mut, fold_boolean_lit;
+}
+
+impl<Ext: External, T: HasPlaceholder + Visitable<Ext>> Visitable<Ext> for Maybe<T> {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        match self {
+            Maybe::Present(node) => node.visit(visitor),
+            Maybe::Placeholder(placeholder) => visitor.visit_placeholder(placeholder),
+        }
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        match self {
+            Maybe::Present(node) => node.visit_mut(visitor),
+            Maybe::Placeholder(placeholder) => visitor.visit_placeholder_mut(placeholder),
+        }
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        match self {
+            Maybe::Present(node) => Maybe::Present(node.fold(folder)),
+            Maybe::Placeholder(placeholder) => {
+                Maybe::Placeholder(folder.fold_placeholder(placeholder))
+            }
+        }
+    }
+}
+
+impl<Ext: External, T: Visitable<Ext>> Visitable<Ext> for Vec<T> {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        self.iter().for_each(|node| node.visit(visitor));
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        self.iter_mut().for_each(|node| node.visit_mut(visitor));
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        self.into_iter().map(|node| node.fold(folder)).collect()
+    }
+}
+
+impl<Ext: External, T: Visitable<Ext>> Visitable<Ext> for Option<T> {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.visit(visitor);
+        }
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.visit_mut(visitor);
+        }
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        self.map(|node| node.fold(folder))
+    }
+}
+
+impl<Ext: External, T: Visitable<Ext>> Visitable<Ext> for Box<T> {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        (**self).visit(visitor);
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        (**self).visit_mut(visitor);
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        Box::new((*self).fold(folder))
+    }
+}
+
+impl<Ext: External, A: Visitable<Ext>, B: Visitable<Ext>> Visitable<Ext> for (A, B) {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        self.0.visit(visitor);
+        self.1.visit(visitor);
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        self.0.visit_mut(visitor);
+        self.1.visit_mut(visitor);
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        (self.0.fold(folder), self.1.fold(folder))
+    }
+}
+
+impl<Ext: External, T> Visitable<Ext> for PhantomData<T> {
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, _visitor: &mut V) {}
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, _visitor: &mut V) {}
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, _folder: &mut F) -> Self {
+        self
+    }
+}
+
+///
+/// Implement [Visitable] for types with no children to walk into.
+///
+macro_rules! leaf {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<Ext: $crate::syntax::external::External> $crate::visit::Visitable<Ext> for $ty {
+                fn visit_children<V: $crate::visit::Visit<Ext> + ?Sized>(&self, _visitor: &mut V) {}
+
+                fn visit_children_mut<V: $crate::visit::VisitMut<Ext> + ?Sized>(
+                    &mut self,
+                    _visitor: &mut V,
+                ) {
+                }
+
+                fn fold_children<F: $crate::visit::Fold<Ext> + ?Sized>(self, _folder: &mut F) -> Self {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use leaf;
+
+///
+/// Implement [Visitable] for nodes of an external language,
+/// which are passed to `visit_external`, and `visit_external_mut`
+/// (and kept as they are by folds).
+///
+macro_rules! external {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<Ext: $crate::syntax::external::External> $crate::visit::Visitable<Ext> for $ty {
+                fn visit<V: $crate::visit::Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+                    visitor.visit_external(self)
+                }
+
+                fn visit_children<V: $crate::visit::Visit<Ext> + ?Sized>(&self, _visitor: &mut V) {}
+
+                fn visit_mut<V: $crate::visit::VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+                    visitor.visit_external_mut(self)
+                }
+
+                fn visit_children_mut<V: $crate::visit::VisitMut<Ext> + ?Sized>(
+                    &mut self,
+                    _visitor: &mut V,
+                ) {
+                }
+
+                fn fold_children<F: $crate::visit::Fold<Ext> + ?Sized>(self, _folder: &mut F) -> Self {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use external;
+
+leaf!(String, i32, f64, Empty);
+
+impl<Ext: External> Visitable<Ext> for Span {
+    fn visit<V: Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_span(self)
+    }
+
+    fn visit_children<V: Visit<Ext> + ?Sized>(&self, _visitor: &mut V) {}
+
+    fn visit_mut<V: VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+        visitor.visit_span_mut(self)
+    }
+
+    fn visit_children_mut<V: VisitMut<Ext> + ?Sized>(&mut self, _visitor: &mut V) {}
+
+    fn fold<F: Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+        folder.fold_span(self)
+    }
+
+    fn fold_children<F: Fold<Ext> + ?Sized>(self, _folder: &mut F) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::Parser;
+
+    use crate::{
+        lexical::Identifier,
+        syntax::{external::typescript::TypeScript, file::File},
+        utils::{Parseable, SourceFile},
+    };
+
+    use super::{Fold, Visit, Visitable};
+
+    #[derive(Default)]
+    struct Identifiers(Vec<String>);
+
+    impl Visit<TypeScript> for Identifiers {
+        fn visit_identifier(&mut self, ident: &Identifier) {
+            self.0.push(ident.value.clone());
+        }
+    }
+
+    struct Rename;
+
+    impl Fold<TypeScript> for Rename {
+        fn fold_identifier(&mut self, mut ident: Identifier) -> Identifier {
+            if ident.value == "size" {
+                ident.value = "width".to_string();
+            }
+            ident
+        }
+    }
+
+    fn identifiers(file: &File<TypeScript>) -> Vec<String> {
+        let mut visitor = Identifiers::default();
+        file.visit(&mut visitor);
+        visitor.0
+    }
+
+    #[test]
+    fn visit_and_fold() {
+        let (source, _) = SourceFile::test_file(
+            "let a = 1;\n---\n<Row gap=size><Text label=(item.name) /></Row>",
+        );
+        let file = File::<TypeScript>::parser()
+            .parse(source.stream())
+            .into_result()
+            .unwrap();
+
+        assert_eq!(
+            identifiers(&file),
+            ["Row", "gap", "size", "Text", "label", "item", "name"]
+        );
+
+        let file = file.fold(&mut Rename);
+        assert_eq!(
+            identifiers(&file),
+            ["Row", "gap", "width", "Text", "label", "item", "name"]
+        );
+    }
+}
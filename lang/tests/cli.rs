@@ -0,0 +1,60 @@
+//!
+//! Tests of the `avpony` command-line tool's exit codes, and output.
+//!
+
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+///
+/// Write `contents` to a file named `name`, in a temporary directory of this test run.
+///
+fn file(name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("avpony-cli-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join(name);
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+fn avpony(args: &[&str], path: &PathBuf) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_avpony"))
+        .args(args)
+        .arg(path)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn check() {
+    let valid = file("valid.pony", "let a = 1;\n---\n<Row>{a}</Row>\n");
+    let output = avpony(&["check"], &valid);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stderr.is_empty());
+
+    let invalid = file("invalid.pony", "let a = 1;\n---\n<Row>{a}</Rwo>\n");
+    let output = avpony(&["check", "--format", "json"], &invalid);
+    assert_eq!(output.status.code(), Some(1));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let line: serde_json::Value = serde_json::from_str(stdout.lines().next().unwrap()).unwrap();
+    assert_eq!(line["code"], "X101");
+    assert_eq!(line["severity"], "error");
+
+    let output = avpony(&["check"], &PathBuf::from("missing.pony"));
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn parse() {
+    let valid = file("parse.pony", "let a = 1;\n---\n<Row>{a}</Row>\n");
+    let output = avpony(&["parse", "--json"], &valid);
+    assert_eq!(output.status.code(), Some(0));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["version"].is_number());
+    assert_eq!(json["errors"], serde_json::json!([]));
+}
@@ -2,34 +2,55 @@ use crate::{fatal_error, q_attr};
 
 #[allow(non_snake_case)]
 mod ErrorI {
-    use crate::{q, spanned::Span};
+    use crate::q;
 
     pub fn path() -> syn::Path {
         q!(crate::utils::ErrorI)
     }
 
-    pub fn to_report(expr: syn::Expr) -> syn::Expr {
+    pub fn to_diagnostic(expr: syn::Expr) -> syn::Expr {
         let ErrorI = self::path();
-        q!(#ErrorI::to_report(#expr))
+        q!(#ErrorI::to_diagnostic(#expr))
+    }
+
+    pub fn code(expr: syn::Expr) -> syn::Expr {
+        let ErrorI = self::path();
+        q!(#ErrorI::code(#expr))
     }
 
     pub fn impl_for(en @ syn::ItemEnum { ident, .. }: &syn::ItemEnum) -> syn::ItemImpl {
         let ErrorI = self::path();
-        let Span = Span::path();
+        let Diagnostic: syn::Path = q!(crate::utils::diagnostic::Diagnostic);
+
+        let codes = en
+            .variants
+            .iter()
+            .map(|var| var.ident.clone())
+            .map(|v_ident| {
+                let expr = self::code(q!(v));
+                let arm: syn::Arm = q!(Self::#v_ident(v) => #expr,);
+                arm
+            });
 
         let vars = en
             .variants
             .iter()
             .map(|var| var.ident.clone())
             .map(|v_ident| {
-                let expr = self::to_report(q!(v));
+                let expr = self::to_diagnostic(q!(v));
                 let arm: syn::Arm = q!(Self::#v_ident(v) => #expr,);
                 arm
             });
 
         q!(
             impl #ErrorI for #ident {
-                fn to_report(self) -> ariadne::Report<'static, #Span> {
+                fn code(&self) -> String {
+                    match self {
+                        #(#codes)*
+                    }
+                }
+
+                fn to_diagnostic(self) -> #Diagnostic {
                     match self {
                         #(#vars)*
                     }
@@ -77,7 +77,7 @@ pub fn make_keyword(
             #[allow(non_snake_case)]
             let Span = Span::path().to_type();
             st.fields = syn::Fields::Named(q!({
-                span: #Span
+                pub(crate) span: #Span
             }));
 
             use traits::Parser::*;
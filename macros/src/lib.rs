@@ -12,6 +12,7 @@ mod punctuation;
 mod punctuations;
 mod spanned;
 mod utils;
+mod visit;
 
 pub(crate) use utils::*;
 
@@ -53,6 +54,43 @@ pub fn derive_spanned(target: proc_macro::TokenStream) -> proc_macro::TokenStrea
     .into()
 }
 
+///
+/// ## INTERNAL-ONLY MACRO.
+/// ***
+/// ## #\[derive(Visit)]
+/// Implements `crate::visit::Visitable` for an AST node, so that visitors
+/// (`Visit`, `VisitMut`, and `Fold`) can walk through it:
+/// * Structs &mdash; visits every field not marked `#[visit(skip)]`,
+///   through its own `Visitable` impl, so `Span`s are passed to `visit_span`
+///   (`visit_span_mut`, and `fold_span`) like any other field;
+/// * Enums &mdash; visits the fields of each variant, in the same way.
+///
+/// The node is passed to the visitor's `visit_<name>` method
+/// (`visit_<name>_mut`, and `fold_<name>`), where `<name>` is
+/// the type's name in snake case.
+///
+/// ### Attributes
+/// * `#[visit(name = ident)]` &mdash; use `visit_ident`, etc. instead.
+/// * `#[visit(skip)]` (on fields) &mdash; don't visit this field.
+///
+/// ### Example
+/// ```ignore
+/// use avpony_macros::{Spanned, Visit};
+///
+/// #[derive(Debug, Clone, Spanned, Visit)]
+/// pub struct Directive {
+///     span: Span,
+///     pub base: lexical::Identifier,
+///     pub director: Maybe<lexical::Identifier>,
+/// }
+/// ```
+///
+#[proc_macro_derive(Visit, attributes(visit))]
+pub fn derive_visit(target: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let item: syn::Item = parse_macro_input!(target);
+    visit::derive(item).into()
+}
+
 ///
 /// ## INTERNAL-ONLY MACRO.
 /// ***
@@ -0,0 +1,235 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+
+use crate::fatal_error;
+
+///
+/// Name of a node type in visitor methods:
+/// `ElseIfBranch` is visited by `visit_else_if_branch`.
+///
+fn snake_case(ident: &syn::Ident) -> String {
+    let mut out = String::new();
+    for (i, ch) in ident.to_string().chars().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.extend(ch.to_lowercase());
+    }
+    out
+}
+
+///
+/// Options from `#[visit(...)]` attributes:
+/// * `#[visit(name = ident)]` on a type -- rename its visitor methods;
+/// * `#[visit(skip)]` on a field -- don't visit the field.
+///
+#[derive(Default)]
+struct Options {
+    name: Option<syn::Ident>,
+    skip: bool,
+}
+
+impl Options {
+    fn from_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("visit")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    return Ok(());
+                }
+
+                if meta.path.is_ident("name") {
+                    options.name = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+
+                Err(meta.error("expected `skip`, or `name = ...`"))
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+///
+/// Fields of a struct, or variant that are visited, by their member.
+///
+fn visited(fields: &syn::Fields) -> syn::Result<Vec<(syn::Member, bool)>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let member = match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(syn::Index {
+                    index: index as u32,
+                    span: proc_macro2::Span::call_site(),
+                }),
+            };
+
+            let skip = Options::from_attrs(&field.attrs)?.skip;
+            Ok((member, !skip))
+        })
+        .collect()
+}
+
+///
+/// Bodies of `visit_children`, `visit_children_mut`, and `fold_children`.
+///
+struct Bodies {
+    visit: TokenStream,
+    visit_mut: TokenStream,
+    fold: TokenStream,
+}
+
+fn struct_bodies(st: &syn::ItemStruct) -> syn::Result<Bodies> {
+    let fields = visited(&st.fields)?;
+    let walked = fields
+        .iter()
+        .filter(|(_, visit)| *visit)
+        .map(|(member, _)| member)
+        .collect::<Vec<_>>();
+
+    let folded = fields.iter().map(|(member, visit)| match visit {
+        true => quote!(#member: crate::visit::Visitable::<Ext>::fold(self.#member, folder)),
+        false => quote!(#member: self.#member),
+    });
+
+    Ok(Bodies {
+        visit: quote!(#(crate::visit::Visitable::<Ext>::visit(&self.#walked, visitor);)*),
+        visit_mut: quote!(#(crate::visit::Visitable::<Ext>::visit_mut(&mut self.#walked, visitor);)*),
+        fold: quote!(Self { #(#folded),* }),
+    })
+}
+
+fn enum_bodies(en: &syn::ItemEnum) -> syn::Result<Bodies> {
+    let mut visit = Vec::new();
+    let mut visit_mut = Vec::new();
+    let mut fold = Vec::new();
+
+    for variant in &en.variants {
+        let ident = &variant.ident;
+        let fields = visited(&variant.fields)?;
+        let bindings = (0..fields.len())
+            .map(|i| format_ident!("v{i}"))
+            .collect::<Vec<_>>();
+
+        let members = fields.iter().map(|(member, _)| member);
+        let pattern = quote!(Self::#ident { #(#members: #bindings),* });
+
+        let walked = fields
+            .iter()
+            .zip(&bindings)
+            .filter(|((_, visit), _)| *visit)
+            .map(|(_, binding)| binding)
+            .collect::<Vec<_>>();
+
+        let folded = fields
+            .iter()
+            .zip(&bindings)
+            .map(|((member, visit), binding)| match visit {
+                true => quote!(#member: crate::visit::Visitable::<Ext>::fold(#binding, folder)),
+                false => quote!(#member: #binding),
+            });
+
+        visit.push(quote!(
+            #pattern => { #(crate::visit::Visitable::<Ext>::visit(#walked, visitor);)* }
+        ));
+        visit_mut.push(quote!(
+            #pattern => { #(crate::visit::Visitable::<Ext>::visit_mut(#walked, visitor);)* }
+        ));
+        fold.push(quote!(#pattern => Self::#ident { #(#folded),* }));
+    }
+
+    Ok(Bodies {
+        visit: quote!(match self { #(#visit)* }),
+        visit_mut: quote!(match self { #(#visit_mut)* }),
+        fold: quote!(match self { #(#fold,)* }),
+    })
+}
+
+fn impl_for(
+    ident: &syn::Ident,
+    generics: &syn::Generics,
+    attrs: &[syn::Attribute],
+    bodies: Bodies,
+) -> syn::Result<TokenStream> {
+    let name = match Options::from_attrs(attrs)?.name {
+        Some(name) => name.to_string(),
+        None => snake_case(ident),
+    };
+    let visit = format_ident!("visit_{name}");
+    let visit_mut = format_ident!("visit_{name}_mut");
+    let fold = format_ident!("fold_{name}");
+
+    // Types not generic over the external language are visitable in any language.
+    let mut impl_generics = generics.clone();
+    let has_ext = generics.type_params().any(|param| param.ident == "Ext");
+    if !has_ext {
+        impl_generics
+            .params
+            .push(syn::parse_quote!(Ext: crate::syntax::external::External));
+    }
+
+    let (impl_generics, _, _) = impl_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+
+    let Bodies {
+        visit: visit_body,
+        visit_mut: visit_mut_body,
+        fold: fold_body,
+    } = bodies;
+
+    Ok(quote! {
+        #[allow(unused_variables)]
+        impl #impl_generics crate::visit::Visitable<Ext> for #ident #ty_generics #where_clause {
+            fn visit<V: crate::visit::Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+                visitor.#visit(self)
+            }
+
+            fn visit_children<V: crate::visit::Visit<Ext> + ?Sized>(&self, visitor: &mut V) {
+                #visit_body
+            }
+
+            fn visit_mut<V: crate::visit::VisitMut<Ext> + ?Sized>(&mut self, visitor: &mut V) {
+                visitor.#visit_mut(self)
+            }
+
+            fn visit_children_mut<V: crate::visit::VisitMut<Ext> + ?Sized>(
+                &mut self,
+                visitor: &mut V,
+            ) {
+                #visit_mut_body
+            }
+
+            fn fold<F: crate::visit::Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+                folder.#fold(self)
+            }
+
+            fn fold_children<F: crate::visit::Fold<Ext> + ?Sized>(self, folder: &mut F) -> Self {
+                #fold_body
+            }
+        }
+    })
+}
+
+pub fn derive(item: syn::Item) -> TokenStream {
+    let res = match &item {
+        syn::Item::Struct(st) => struct_bodies(st)
+            .and_then(|bodies| impl_for(&st.ident, &st.generics, &st.attrs, bodies)),
+        syn::Item::Enum(en) => {
+            enum_bodies(en).and_then(|bodies| impl_for(&en.ident, &en.generics, &en.attrs, bodies))
+        }
+        it => {
+            return fatal_error(
+                it.span(),
+                "You can only use this macro on an enum or struct declaration",
+            )
+        }
+    };
+
+    res.unwrap_or_else(syn::Error::into_compile_error)
+}